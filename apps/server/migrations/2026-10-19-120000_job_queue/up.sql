@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE Jobs (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	kind VARCHAR NOT NULL,
+	payload TEXT NOT NULL,
+	status VARCHAR NOT NULL DEFAULT 'pending',
+	attempts INTEGER NOT NULL DEFAULT 0,
+	last_error TEXT,
+	run_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TABLE WebhookDeliveries (
+	id VARCHAR NOT NULL PRIMARY KEY,
+	event VARCHAR NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
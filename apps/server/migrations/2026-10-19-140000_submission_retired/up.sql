@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE Submissions ADD COLUMN retired INTEGER NOT NULL DEFAULT 0;
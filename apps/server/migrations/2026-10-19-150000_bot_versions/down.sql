@@ -0,0 +1,22 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE Submissions_OLD (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	user CHARACTER(36) NOT NULL,
+	script TEXT NOT NULL,
+	comment TEXT,
+	wins INTEGER NOT NULL DEFAULT 0,
+	issue_url TEXT NOT NULL,
+	issue_number INTEGER NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	disqualified INTEGER NOT NULL DEFAULT 0,
+	mmr FLOAT NOT NULL DEFAULT 0,
+	matches_played INTEGER NOT NULL DEFAULT 0,
+	retired INTEGER NOT NULL DEFAULT 0,
+	FOREIGN KEY (user) REFERENCES Users(id)
+);
+
+INSERT INTO Submissions_OLD SELECT id, user, script, comment, wins, issue_url, issue_number, created_at, updated_at, disqualified, mmr, matches_played, retired FROM Submissions;
+DROP TABLE IF EXISTS Submissions;
+ALTER TABLE Submissions_OLD RENAME TO Submissions;
+DROP TABLE IF EXISTS Bots;
@@ -0,0 +1,33 @@
+-- Your SQL goes here
+CREATE TABLE Bots (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	user CHARACTER(36) NOT NULL,
+	name TEXT NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY (user) REFERENCES Users(id)
+);
+
+ALTER TABLE Submissions ADD COLUMN bot CHARACTER(36) REFERENCES Bots(id);
+ALTER TABLE Submissions ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+-- Every issue so far becomes a bot, its first submission lends the bot its id
+INSERT INTO Bots (id, user, name, created_at, updated_at)
+SELECT id, user, COALESCE(comment, 'Bot'), created_at, updated_at FROM Submissions AS first
+WHERE NOT EXISTS (
+	SELECT 1 FROM Submissions AS earlier
+	WHERE earlier.user = first.user
+	AND earlier.issue_number = first.issue_number
+	AND (earlier.created_at < first.created_at OR (earlier.created_at = first.created_at AND earlier.id < first.id))
+);
+
+UPDATE Submissions SET bot = (
+	SELECT Bots.id FROM Bots JOIN Submissions AS first ON first.id = Bots.id
+	WHERE first.user = Submissions.user AND first.issue_number = Submissions.issue_number
+);
+
+UPDATE Submissions SET version = (
+	SELECT COUNT(*) FROM Submissions AS earlier
+	WHERE earlier.bot = Submissions.bot
+	AND (earlier.created_at < Submissions.created_at OR (earlier.created_at = Submissions.created_at AND earlier.id <= Submissions.id))
+);
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TABLE Disqualifications (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	submission CHARACTER(36) NOT NULL,
+	opponent CHARACTER(36),
+	match_id CHARACTER(36),
+	kind VARCHAR NOT NULL,
+	reason TEXT NOT NULL,
+	flipped INTEGER NOT NULL DEFAULT 0,
+	infrastructure INTEGER NOT NULL DEFAULT 0,
+	status VARCHAR NOT NULL DEFAULT 'active',
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY (submission) REFERENCES Submissions(id),
+	FOREIGN KEY (opponent) REFERENCES Submissions(id),
+	FOREIGN KEY (match_id) REFERENCES Matches(id)
+);
+
+-- Submissions disqualified so far get a record without details, the id is derived from the submission
+INSERT INTO Disqualifications (id, submission, kind, reason, created_at, updated_at)
+SELECT id, id, 'unknown', 'Disqualified before disqualifications were recorded', updated_at, updated_at
+FROM Submissions WHERE disqualified >= 1;
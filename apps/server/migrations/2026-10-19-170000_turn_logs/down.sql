@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE Turns_OLD (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	match_id CHARACTER(36) NOT NULL,
+	turn INTEGER NOT NULL,
+	board TEXT NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY (match_id) REFERENCES Matches(id)
+);
+
+INSERT INTO Turns_OLD SELECT id, match_id, turn, board, created_at, updated_at FROM Turns;
+DROP TABLE IF EXISTS Turns;
+ALTER TABLE Turns_OLD RENAME TO Turns;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE Turns ADD COLUMN log TEXT;
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE ApiTokens (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	user CHARACTER(36) NOT NULL,
+	name VARCHAR NOT NULL,
+	token_hash VARCHAR NOT NULL UNIQUE,
+	last_used_at DATETIME,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY (user) REFERENCES Users(id)
+);
@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE Jobs_OLD (
+	id CHARACTER(36) NOT NULL PRIMARY KEY,
+	kind VARCHAR NOT NULL,
+	payload TEXT NOT NULL,
+	status VARCHAR NOT NULL DEFAULT 'pending',
+	attempts INTEGER NOT NULL DEFAULT 0,
+	last_error TEXT,
+	run_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+INSERT INTO Jobs_OLD SELECT id, kind, payload, status, attempts, last_error, run_at, created_at, updated_at FROM Jobs;
+DROP TABLE IF EXISTS Jobs;
+ALTER TABLE Jobs_OLD RENAME TO Jobs;
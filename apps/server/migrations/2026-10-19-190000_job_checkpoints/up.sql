@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE Jobs ADD COLUMN checkpoint TEXT;
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::backend::models::{match_model::Match, submission_model::Submission, turn_model::Turn};
+use crate::game::board::board_from_string;
+use crate::game::replay::{replay_from_boards, ReplayAction};
+
+/// Aggregates over every stored match of the ladder
+#[derive(Debug, Default)]
+pub(crate) struct LadderStats {
+    pub matches: usize,
+    /// Player one, who moves first, won. Matches that ended in an error are not counted
+    pub flipped_wins: usize,
+    pub regular_wins: usize,
+    /// Turns of every match that has turns stored
+    pub game_lengths: Vec<usize>,
+    /// Walls placed by both players, per match with turns stored
+    pub walls_per_game: Vec<usize>,
+    pub jumps: usize,
+    pub games_with_jumps: usize,
+    /// Most common first
+    pub errors: Vec<(String, usize)>,
+    pub ratings: Option<RatingSpread>,
+}
+
+/// How the ratings of the active submissions are spread
+#[derive(Debug, PartialEq)]
+pub(crate) struct RatingSpread {
+    pub submissions: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub standard_deviation: f32,
+    pub median: f32,
+}
+
+pub(crate) fn ladder_stats(
+    matches: &[Match],
+    turns: &[Turn],
+    submissions: &[Submission],
+) -> LadderStats {
+    let mut stats = LadderStats {
+        matches: matches.len(),
+        ..Default::default()
+    };
+
+    let mut boards: HashMap<&str, Vec<&Turn>> = HashMap::new();
+    for turn in turns {
+        boards.entry(turn.match_id.as_str()).or_default().push(turn);
+    }
+
+    let mut errors: HashMap<String, usize> = HashMap::new();
+    for current in matches {
+        match current.match_error.as_ref() {
+            Some(error) => *errors.entry(error.clone()).or_default() += 1,
+            None if current.p1_is_winner == 1 => stats.flipped_wins += 1,
+            None => stats.regular_wins += 1,
+        }
+
+        let Some(match_turns) = boards.get_mut(current.id.as_str()) else {
+            continue;
+        };
+        match_turns.sort_by_key(|turn| turn.turn);
+        let frames = replay_from_boards(
+            &match_turns
+                .iter()
+                .map(|turn| board_from_string(turn.board.clone()))
+                .collect::<Vec<_>>(),
+        );
+        let walls = frames
+            .iter()
+            .filter(|frame| matches!(frame.action, ReplayAction::Wall { .. }))
+            .count();
+        let jumps = frames
+            .iter()
+            .filter(|frame| matches!(frame.action, ReplayAction::Move { jump: true, .. }))
+            .count();
+        stats.game_lengths.push(match_turns.len());
+        stats.walls_per_game.push(walls);
+        stats.jumps += jumps;
+        if jumps > 0 {
+            stats.games_with_jumps += 1;
+        }
+    }
+
+    stats.errors = errors.into_iter().collect();
+    stats
+        .errors
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats.ratings = rating_spread(
+        &submissions
+            .iter()
+            .filter(|submission| submission.is_active())
+            .map(|submission| submission.mmr)
+            .collect::<Vec<_>>(),
+    );
+    stats
+}
+
+fn rating_spread(ratings: &[f32]) -> Option<RatingSpread> {
+    if ratings.is_empty() {
+        return None;
+    }
+    let mut sorted = ratings.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let count = sorted.len() as f32;
+    let mean = sorted.iter().sum::<f32>() / count;
+    let variance = sorted
+        .iter()
+        .map(|rating| (rating - mean).powi(2))
+        .sum::<f32>()
+        / count;
+    let middle = sorted.len() / 2;
+    Some(RatingSpread {
+        submissions: sorted.len(),
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        mean,
+        standard_deviation: variance.sqrt(),
+        median: if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2.0
+        } else {
+            sorted[middle]
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ladder_stats, rating_spread, RatingSpread};
+    use crate::backend::models::{match_model::Match, submission_model::Submission};
+
+    fn played(id: &str, p1_is_winner: i32, error: Option<&str>) -> Match {
+        Match {
+            id: id.to_string(),
+            winner: String::new(),
+            loser: String::new(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+            p1_is_winner,
+            match_error: error.map(|error| error.to_string()),
+        }
+    }
+
+    #[test]
+    fn counts_sides_and_errors() {
+        let matches = vec![
+            played("1", 1, None),
+            played("2", 1, None),
+            played("3", 0, None),
+            played("4", 0, Some("Turn timeout")),
+            played("5", 1, Some("Turn timeout")),
+            played("6", 1, Some("Deadlock, both bots failed")),
+        ];
+        let mut retired = Submission::unsaved("alice", "", None, "", 1);
+        retired.retired = 1;
+        let stats = ladder_stats(&matches, &[], &[retired]);
+        assert_eq!((stats.flipped_wins, stats.regular_wins), (2, 1));
+        assert_eq!(
+            stats.errors,
+            vec![
+                ("Turn timeout".to_string(), 2),
+                ("Deadlock, both bots failed".to_string(), 1)
+            ]
+        );
+        assert!(stats.game_lengths.is_empty());
+        assert_eq!(stats.ratings, None);
+    }
+
+    #[test]
+    fn spreads_ratings() {
+        assert_eq!(
+            rating_spread(&[1200.0, 1000.0, 1400.0, 1000.0]),
+            Some(RatingSpread {
+                submissions: 4,
+                min: 1000.0,
+                max: 1400.0,
+                mean: 1150.0,
+                standard_deviation: 165.83124,
+                median: 1100.0,
+            })
+        );
+    }
+}
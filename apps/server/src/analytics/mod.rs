@@ -0,0 +1,3 @@
+pub(crate) mod match_stats;
+pub(crate) mod report;
+pub(crate) mod user_stats;
@@ -0,0 +1,143 @@
+use super::match_stats::LadderStats;
+
+const TOP_ERRORS: usize = 10;
+const GAME_LENGTH_BUCKET: usize = 10;
+
+/// The stats as markdown, charts are mermaid blocks which GitHub renders
+pub(crate) fn stats_report(stats: &LadderStats, generated_at: &str) -> String {
+    let mut report = format!(
+        "# Ladder statistics\n\n{} matches, generated {}\n\n",
+        stats.matches, generated_at
+    );
+
+    let decided = stats.flipped_wins + stats.regular_wins;
+    report.push_str("## First move advantage\n\n");
+    report.push_str(&format!(
+        "The flipped player moves first, win rates are over the {} matches that ended without an error\n\n",
+        decided
+    ));
+    if decided > 0 {
+        report.push_str(&format!(
+            "```mermaid\npie title Wins by side\n    \"Flipped\" : {}\n    \"Regular\" : {}\n```\n\n",
+            stats.flipped_wins, stats.regular_wins
+        ));
+    }
+    report.push_str(&format!(
+        "| Side | Wins | Win rate |\n|---|---|---|\n| Flipped | {} | {} |\n| Regular | {} | {} |\n\n",
+        stats.flipped_wins,
+        percentage(stats.flipped_wins, decided),
+        stats.regular_wins,
+        percentage(stats.regular_wins, decided)
+    ));
+
+    report.push_str("## Game length\n\n");
+    report.push_str(&distribution(
+        &stats.game_lengths,
+        GAME_LENGTH_BUCKET,
+        "Turns",
+    ));
+
+    report.push_str("## Walls per game\n\n");
+    report.push_str(&distribution(&stats.walls_per_game, 1, "Walls placed"));
+
+    report.push_str("## Jumps\n\n");
+    report.push_str(&format!(
+        "{} jumps, {} of {} games had at least one ({})\n\n",
+        stats.jumps,
+        stats.games_with_jumps,
+        stats.game_lengths.len(),
+        percentage(stats.games_with_jumps, stats.game_lengths.len())
+    ));
+
+    report.push_str("## Errors\n\n");
+    if stats.errors.is_empty() {
+        report.push_str("No match ended in an error\n\n");
+    } else {
+        report.push_str("| Reason | Matches |\n|---|---|\n");
+        for (reason, count) in stats.errors.iter().take(TOP_ERRORS) {
+            report.push_str(&format!(
+                "| {} | {} |\n",
+                reason.replace('|', "&#124;").replace('\n', " "),
+                count
+            ));
+        }
+        report.push('\n');
+    }
+
+    report.push_str("## Ratings\n\n");
+    match stats.ratings.as_ref() {
+        Some(ratings) => report.push_str(&format!(
+            "Over the {} active submissions\n\n| Min | Median | Mean | Max | Standard deviation |\n|---|---|---|---|---|\n| {:.0} | {:.0} | {:.0} | {:.0} | {:.1} |\n",
+            ratings.submissions,
+            ratings.min,
+            ratings.median,
+            ratings.mean,
+            ratings.max,
+            ratings.standard_deviation
+        )),
+        None => report.push_str("There are no active submissions\n"),
+    }
+    report
+}
+
+/// A bar chart of how many values fall in each bucket, with a summary line
+fn distribution(values: &[usize], bucket_size: usize, label: &str) -> String {
+    if values.is_empty() {
+        return "No matches with turns stored\n\n".to_string();
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    let mean = sorted.iter().sum::<usize>() as f32 / sorted.len() as f32;
+
+    let mut buckets = vec![0; sorted[sorted.len() - 1] / bucket_size + 1];
+    for value in sorted.iter() {
+        buckets[value / bucket_size] += 1;
+    }
+    let labels: Vec<String> = (0..buckets.len())
+        .map(|index| {
+            if bucket_size == 1 {
+                format!("\"{}\"", index)
+            } else {
+                format!(
+                    "\"{}-{}\"",
+                    index * bucket_size,
+                    (index + 1) * bucket_size - 1
+                )
+            }
+        })
+        .collect();
+    format!(
+        "```mermaid\nxychart-beta\n    x-axis \"{}\" [{}]\n    y-axis \"Matches\"\n    bar [{}]\n```\n\nMin {}, median {}, mean {:.1}, max {}\n\n",
+        label,
+        labels.join(", "),
+        buckets
+            .iter()
+            .map(|count| count.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        sorted[0],
+        sorted[sorted.len() / 2],
+        mean,
+        sorted[sorted.len() - 1]
+    )
+}
+
+fn percentage(part: usize, total: usize) -> String {
+    if total == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", part as f32 / total as f32 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::distribution;
+
+    #[test]
+    fn buckets_values() {
+        assert_eq!(
+            distribution(&[3, 12, 15, 31], 10, "Turns"),
+            "```mermaid\nxychart-beta\n    x-axis \"Turns\" [\"0-9\", \"10-19\", \"20-29\", \"30-39\"]\n    y-axis \"Matches\"\n    bar [1, 2, 0, 1]\n```\n\nMin 3, median 15, mean 15.2, max 31\n\n"
+        );
+    }
+}
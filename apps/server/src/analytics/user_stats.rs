@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+
+use diesel::SqliteConnection;
+use serde::Serialize;
+
+use crate::backend::models::{
+    bot_model::Bot, match_model::Match, submission_model::Submission, turn_model::Turn,
+    user_model::User,
+};
+use crate::game::board::board_from_string;
+use crate::game::replay::{replay_from_boards, ReplayAction};
+
+const RECENT_MATCHES: usize = 10;
+
+/// Everything a profile shows about a user, aggregated over all of their submissions
+#[derive(Debug, Serialize)]
+pub(crate) struct UserStats {
+    pub username: String,
+    /// Newest first
+    pub submissions: Vec<SubmissionSummary>,
+    pub best_rating: Option<f32>,
+    pub wins: usize,
+    pub losses: usize,
+    /// Most played opponent first
+    pub opponents: Vec<OpponentRecord>,
+    /// In turns, over the matches that have turns stored
+    pub average_game_length: Option<f32>,
+    /// Walls the user placed per match, over the matches that have turns stored
+    pub average_walls_placed: Option<f32>,
+    /// Newest first
+    pub recent_matches: Vec<RecentMatch>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SubmissionSummary {
+    pub id: String,
+    pub bot: Option<String>,
+    pub version: i32,
+    pub mmr: f32,
+    pub wins: i32,
+    pub matches_played: i32,
+    /// active, disqualified or retired
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub(crate) struct OpponentRecord {
+    pub username: String,
+    pub wins: usize,
+    pub losses: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecentMatch {
+    pub id: String,
+    pub won: bool,
+    pub submission: String,
+    pub opponent: String,
+    pub opponent_submission: String,
+    /// Missing when the turns of the match are not stored
+    pub turns: Option<usize>,
+    pub walls_placed: Option<usize>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Loads what the stats of a user are derived from and aggregates them
+pub(crate) fn load_user_stats(user: &User, conn: &SqliteConnection) -> UserStats {
+    let submissions = Submission::list(conn);
+    let mut matches: Vec<Match> = Vec::new();
+    for submission in submissions.iter().filter(|current| current.user == user.id) {
+        for current in Match::by_submission(&submission.id, conn) {
+            // Both sides are the user's when two of their bots meet
+            if !matches.iter().any(|existing| existing.id == current.id) {
+                matches.push(current);
+            }
+        }
+    }
+    let turns: Vec<Turn> = matches
+        .iter()
+        .filter_map(|current| Match::get_turns(&current.id, conn))
+        .flatten()
+        .collect();
+    user_stats(
+        user,
+        &User::list(conn),
+        &submissions,
+        &Bot::by_user(&user.id, conn),
+        &matches,
+        &turns,
+    )
+}
+
+/// The stats of a user, matches and turns of other users are ignored
+pub(crate) fn user_stats(
+    user: &User,
+    users: &[User],
+    submissions: &[Submission],
+    bots: &[Bot],
+    matches: &[Match],
+    turns: &[Turn],
+) -> UserStats {
+    let submission_by_id: HashMap<&str, &Submission> = submissions
+        .iter()
+        .map(|submission| (submission.id.as_str(), submission))
+        .collect();
+    let username = |submission_id: &str| {
+        submission_by_id
+            .get(submission_id)
+            .and_then(|submission| users.iter().find(|user| user.id == submission.user))
+            .map(|user| user.username.clone())
+            .unwrap_or("<Unknown>".to_string())
+    };
+    let is_own = |submission_id: &str| {
+        submission_by_id
+            .get(submission_id)
+            .is_some_and(|submission| submission.user == user.id)
+    };
+
+    let mut own_matches: Vec<&Match> = matches
+        .iter()
+        .filter(|current| is_own(&current.winner) || is_own(&current.loser))
+        .collect();
+    own_matches.sort_by_key(|current| std::cmp::Reverse(current.created_at));
+
+    let mut boards: HashMap<&str, Vec<&Turn>> = own_matches
+        .iter()
+        .map(|current| (current.id.as_str(), Vec::new()))
+        .collect();
+    for turn in turns {
+        if let Some(match_turns) = boards.get_mut(turn.match_id.as_str()) {
+            match_turns.push(turn);
+        }
+    }
+
+    let mut opponents: Vec<OpponentRecord> = Vec::new();
+    let mut recent_matches = Vec::new();
+    let (mut wins, mut losses) = (0, 0);
+    let (mut stored_matches, mut total_turns, mut total_walls) = (0, 0, 0);
+    for current in own_matches {
+        let won = is_own(&current.winner);
+        let (submission, opponent_submission) = if won {
+            (&current.winner, &current.loser)
+        } else {
+            (&current.loser, &current.winner)
+        };
+        let opponent = username(opponent_submission);
+        if won {
+            wins += 1;
+        } else {
+            losses += 1;
+        }
+        match opponents
+            .iter_mut()
+            .find(|record| record.username == opponent)
+        {
+            Some(record) if won => record.wins += 1,
+            Some(record) => record.losses += 1,
+            None => opponents.push(OpponentRecord {
+                username: opponent.clone(),
+                wins: won as usize,
+                losses: !won as usize,
+            }),
+        }
+
+        // Player one is the winner when p1_is_winner is set
+        let player_one = won == (current.p1_is_winner == 1);
+        let summary = boards
+            .get(current.id.as_str())
+            .filter(|match_turns| !match_turns.is_empty())
+            .map(|match_turns| match_summary(match_turns, player_one));
+        if let Some((length, walls)) = summary {
+            stored_matches += 1;
+            total_turns += length;
+            total_walls += walls;
+        }
+
+        if recent_matches.len() < RECENT_MATCHES {
+            recent_matches.push(RecentMatch {
+                id: current.id.clone(),
+                won,
+                submission: submission.clone(),
+                opponent,
+                opponent_submission: opponent_submission.clone(),
+                turns: summary.map(|(length, _)| length),
+                walls_placed: summary.map(|(_, walls)| walls),
+                created_at: current.created_at,
+            });
+        }
+    }
+    opponents.sort_by_key(|record| std::cmp::Reverse(record.wins + record.losses));
+
+    let mut own_submissions: Vec<&Submission> = submissions
+        .iter()
+        .filter(|submission| submission.user == user.id)
+        .collect();
+    own_submissions.sort_by_key(|submission| std::cmp::Reverse(submission.created_at));
+    let average = |total: usize| (stored_matches > 0).then(|| total as f32 / stored_matches as f32);
+
+    UserStats {
+        username: user.username.clone(),
+        best_rating: own_submissions
+            .iter()
+            .map(|submission| submission.mmr)
+            .max_by(|a, b| a.total_cmp(b)),
+        submissions: own_submissions
+            .into_iter()
+            .map(|submission| SubmissionSummary {
+                id: submission.id.clone(),
+                bot: submission
+                    .bot
+                    .as_ref()
+                    .and_then(|bot_id| bots.iter().find(|bot| &bot.id == bot_id))
+                    .map(|bot| bot.name.clone()),
+                version: submission.version,
+                mmr: submission.mmr,
+                wins: submission.wins,
+                matches_played: submission.matches_played,
+                status: submission.status().to_string(),
+            })
+            .collect(),
+        wins,
+        losses,
+        opponents,
+        average_game_length: average(total_turns),
+        average_walls_placed: average(total_walls),
+        recent_matches,
+    }
+}
+
+/// The length of a match and how many walls one of the players placed
+fn match_summary(match_turns: &[&Turn], player_one: bool) -> (usize, usize) {
+    let mut match_turns = match_turns.to_vec();
+    match_turns.sort_by_key(|turn| turn.turn);
+    let boards: Vec<_> = match_turns
+        .iter()
+        .map(|turn| board_from_string(turn.board.clone()))
+        .collect();
+    let walls = replay_from_boards(&boards)
+        .iter()
+        .filter(|frame| match frame.action {
+            ReplayAction::Wall {
+                player_one: placed_by_one,
+                ..
+            } => placed_by_one == player_one,
+            _ => false,
+        })
+        .count();
+    (match_turns.len(), walls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{user_stats, OpponentRecord};
+    use crate::backend::models::{
+        match_model::Match, submission_model::Submission, turn_model::Turn, user_model::User,
+    };
+    use crate::game::{board::populate_board, game::Wall, methods};
+
+    fn user(id: &str) -> User {
+        User {
+            id: id.to_string(),
+            username: id.to_string(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn submission(id: &str, user: &str, mmr: f32) -> Submission {
+        let mut submission = Submission::unsaved(user, "", None, "", 1);
+        submission.id = id.to_string();
+        submission.mmr = mmr;
+        submission
+    }
+
+    fn played(id: &str, winner: &str, loser: &str, p1_is_winner: i32) -> Match {
+        Match {
+            id: id.to_string(),
+            winner: winner.to_string(),
+            loser: loser.to_string(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+            p1_is_winner,
+            match_error: None,
+        }
+    }
+
+    fn turn(match_id: &str, turn: i32, board: String) -> Turn {
+        Turn {
+            id: format!("{}-{}", match_id, turn),
+            match_id: match_id.to_string(),
+            turn,
+            board,
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+            log: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_over_submissions() {
+        let users = vec![user("alice"), user("bob"), user("carol")];
+        let submissions = vec![
+            submission("a1", "alice", 1200.0),
+            submission("a2", "alice", 1350.0),
+            submission("b1", "bob", 1000.0),
+            submission("c1", "carol", 1100.0),
+        ];
+        let matches = vec![
+            played("m1", "a1", "b1", 0),
+            played("m2", "b1", "a2", 1),
+            played("m3", "a2", "b1", 1),
+            played("m4", "c1", "b1", 1),
+        ];
+
+        // In m1 alice is player two and places a wall on the second turn
+        let mut game = methods::new(String::new());
+        let mut boards = Vec::new();
+        game.player_one.y = 7;
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        game.walls.push(Wall {
+            x1: 3,
+            y1: 6,
+            x2: 4,
+            y2: 6,
+        });
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        let turns: Vec<Turn> = boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| {
+                turn(
+                    "m1",
+                    index as i32 + 1,
+                    crate::game::board::board_to_string(board.clone()),
+                )
+            })
+            .collect();
+
+        let stats = user_stats(&users[0], &users, &submissions, &[], &matches, &turns);
+        assert_eq!((stats.wins, stats.losses), (2, 1));
+        assert_eq!(stats.best_rating, Some(1350.0));
+        assert_eq!(stats.submissions.len(), 2);
+        assert_eq!(
+            stats.opponents,
+            vec![OpponentRecord {
+                username: "bob".to_string(),
+                wins: 2,
+                losses: 1,
+            }]
+        );
+        assert_eq!(stats.average_game_length, Some(2.0));
+        assert_eq!(stats.average_walls_placed, Some(1.0));
+        assert_eq!(stats.recent_matches.len(), 3);
+        let m1 = stats
+            .recent_matches
+            .iter()
+            .find(|current| current.id == "m1")
+            .unwrap();
+        assert_eq!((m1.turns, m1.walls_placed), (Some(2), Some(1)));
+        assert!(stats
+            .recent_matches
+            .iter()
+            .all(|current| current.opponent == "bob"));
+    }
+}
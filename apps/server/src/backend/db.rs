@@ -32,3 +32,13 @@ pub(crate) fn run_migrations(conn: &SqliteConnection) {
     embedded_migrations::run_with_output(conn, &mut std::io::stdout())
         .expect("Failed to run migrations");
 }
+
+/// An in-memory database with all migrations applied
+#[cfg(test)]
+pub(crate) fn establish_test_connection() -> DbPool {
+    let manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+    // A single connection, otherwise every connection gets its own in-memory database
+    let pool = r2d2::Pool::builder().max_size(1).build(manager).unwrap();
+    embedded_migrations::run(&pool.get().unwrap()).expect("Failed to run migrations");
+    pool
+}
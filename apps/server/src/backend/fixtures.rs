@@ -0,0 +1,46 @@
+use diesel::SqliteConnection;
+
+use crate::backend::db::{establish_test_connection, DbPool};
+use crate::backend::models::{bot_model::Bot, submission_model::Submission, user_model::User};
+use crate::external_related::github::webhook_signature::WebhookSecret;
+
+/// Walks straight to the goal, never places a wall
+pub(crate) const UPWARDS_BOT: &str = "function onTurn(context)\n    return \"0\"\nend\n\nfunction onJump(context)\n    return \"0\"\nend\n";
+
+/// Secret that test webhook deliveries are signed with
+pub(crate) const WEBHOOK_SECRET: &str = "It's a Secret to Everybody";
+
+/// App data for the webhook route, passed explicitly so
+/// tests don't share the secret through the environment
+pub(crate) fn webhook_secret() -> WebhookSecret {
+    WebhookSecret(Some(WEBHOOK_SECRET.to_string()))
+}
+
+pub(crate) fn setup() -> DbPool {
+    std::env::set_var("GITHUB_USER", "Hampfh");
+    std::env::set_var("GITHUB_REPO", "Hampfh");
+    std::env::set_var("PLAGIARISM_CHECK", "false");
+    establish_test_connection()
+}
+
+/// A user with a single bot named after them
+pub(crate) fn submission(username: &str, script: &str, conn: &SqliteConnection) -> Submission {
+    let user = User::create(username, conn).unwrap();
+    let bot = Bot::create(&user.id, username, conn).unwrap();
+    Submission::create(&user.id, &bot.id, script, None, 0, "", 1, conn)
+        .unwrap()
+        .unwrap()
+}
+
+/// Same as submission, with a rating other than the starting one
+pub(crate) fn rated_submission(
+    username: &str,
+    script: &str,
+    mmr: f32,
+    conn: &SqliteConnection,
+) -> Submission {
+    let mut submission = submission(username, script, conn);
+    submission.mmr = mmr;
+    submission.save(conn);
+    submission
+}
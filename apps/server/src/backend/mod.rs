@@ -1,4 +1,6 @@
 pub mod db;
+#[cfg(test)]
+pub(crate) mod fixtures;
 pub mod models;
 #[allow(non_snake_case)]
 pub mod schema;
@@ -0,0 +1,95 @@
+use crate::backend::schema::ApiTokens;
+use crate::backend::schema::ApiTokens::dsl::ApiTokens as token_dsl;
+use diesel::prelude::*;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const TOKEN_PREFIX: &str = "hampfh_";
+
+/// Lets a user submit bots through the http api. Only a hash of the
+/// token is stored, the token itself is shown once when it is created.
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
+#[table_name = "ApiTokens"]
+pub struct ApiToken {
+    pub id: String,
+    pub user: String,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+impl ApiToken {
+    pub fn list(conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::ApiTokens::dsl::created_at;
+        token_dsl
+            .order(created_at.desc())
+            .load::<ApiToken>(conn)
+            .expect("Error loading api tokens")
+    }
+
+    pub fn by_id(id: &str, conn: &SqliteConnection) -> Option<Self> {
+        token_dsl.find(id).get_result::<ApiToken>(conn).ok()
+    }
+
+    pub fn by_user(user_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::ApiTokens::dsl::{created_at, user};
+        token_dsl
+            .filter(user.eq(user_id))
+            .order(created_at.desc())
+            .load::<ApiToken>(conn)
+            .expect("Error loading api tokens")
+    }
+
+    /// The token that belongs to a bearer token, its last use is updated
+    pub fn authenticate(token: &str, conn: &SqliteConnection) -> Option<Self> {
+        use crate::backend::schema::ApiTokens::dsl::{id, last_used_at, token_hash};
+        let mut record = token_dsl
+            .filter(token_hash.eq(hash_token(token)))
+            .first::<ApiToken>(conn)
+            .ok()?;
+        record.last_used_at = Some(chrono::Local::now().naive_local());
+        diesel::update(token_dsl.filter(id.eq(&record.id)))
+            .set(last_used_at.eq(record.last_used_at))
+            .execute(conn)
+            .expect("Could not update record");
+        Some(record)
+    }
+
+    /// Returns the record together with the token, which cannot be recovered later
+    pub fn create(user_id: &str, name: &str, conn: &SqliteConnection) -> Option<(Self, String)> {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let token = format!("{}{}", TOKEN_PREFIX, hex::encode(secret));
+
+        let new_id = Uuid::new_v4().to_hyphenated().to_string();
+        let new_token = ApiToken {
+            id: new_id.clone(),
+            user: user_id.into(),
+            name: name.trim().into(),
+            token_hash: hash_token(&token),
+            last_used_at: None,
+            created_at: chrono::Local::now().naive_local(),
+        };
+
+        diesel::insert_into(token_dsl)
+            .values(&new_token)
+            .execute(conn)
+            .expect("Error saving new api token");
+        Self::by_id(&new_id, conn).map(|record| (record, token))
+    }
+
+    pub fn delete(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::ApiTokens::dsl::id;
+        diesel::delete(token_dsl.filter(id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete api token");
+    }
+}
+
+/// Tokens are random, so a plain hash is enough to keep them out of the database
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
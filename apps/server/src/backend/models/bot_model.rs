@@ -0,0 +1,90 @@
+use crate::backend::models::submission_model::Submission;
+use crate::backend::schema::Bots;
+use crate::backend::schema::Bots::dsl::Bots as bot_dsl;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A bot of a user, every submission is a version of exactly one bot
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
+#[table_name = "Bots"]
+pub struct Bot {
+    pub id: String,
+    pub user: String,
+    pub name: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+impl Bot {
+    pub fn list(conn: &SqliteConnection) -> Vec<Self> {
+        bot_dsl.load::<Bot>(conn).expect("Error loading bots")
+    }
+    pub fn by_id(id: &str, conn: &SqliteConnection) -> Option<Self> {
+        bot_dsl.find(id).get_result::<Bot>(conn).ok()
+    }
+    pub fn by_user(user_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Bots::dsl::{created_at, user};
+        bot_dsl
+            .filter(user.eq(user_id))
+            .order(created_at.asc())
+            .load::<Bot>(conn)
+            .expect("Error loading bots")
+    }
+    /// Names are compared case insensitively
+    pub fn by_name(user_id: &str, name: &str, conn: &SqliteConnection) -> Option<Self> {
+        Self::by_user(user_id, conn)
+            .into_iter()
+            .find(|bot| bot.name.trim().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The bot a new submission is a version of. Resubmissions on the same
+    /// issue or pull request continue its bot, otherwise a bot of the user
+    /// with the same name is continued. A new bot is created if neither exists.
+    pub fn for_submission(
+        user_id: &str,
+        name: &str,
+        issue_number: i32,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
+        Self::by_issue(user_id, issue_number, conn)
+            .or_else(|| Self::by_name(user_id, name, conn))
+            .or_else(|| Self::create(user_id, name, conn))
+    }
+
+    /// The bot of the user's latest submission on an issue or pull request
+    pub fn by_issue(user_id: &str, issue_number: i32, conn: &SqliteConnection) -> Option<Self> {
+        Submission::latest_by_issue_number(issue_number, conn)
+            .filter(|submission| submission.user == user_id)
+            .and_then(|submission| submission.bot)
+            .and_then(|bot_id| Self::by_id(&bot_id, conn))
+    }
+
+    pub fn create(user_id: &str, name: &str, conn: &SqliteConnection) -> Option<Self> {
+        let new_id = Uuid::new_v4().to_hyphenated().to_string();
+        let new_bot = Bot {
+            id: new_id.clone(),
+            user: user_id.into(),
+            name: name.trim().into(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        };
+
+        diesel::insert_into(bot_dsl)
+            .values(&new_bot)
+            .execute(conn)
+            .expect("Error saving new bot");
+        Self::by_id(&new_id, conn)
+    }
+
+    pub fn delete(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Bots::dsl::id;
+        diesel::delete(bot_dsl.filter(id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete bot");
+    }
+
+    /// Every version of the bot, oldest first
+    pub fn versions(&self, conn: &SqliteConnection) -> Vec<Submission> {
+        Submission::by_bot(&self.id, conn)
+    }
+}
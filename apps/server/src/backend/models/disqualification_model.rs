@@ -0,0 +1,200 @@
+use crate::backend::schema::Disqualifications;
+use crate::backend::schema::Disqualifications::dsl::Disqualifications as disqualification_dsl;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisqualificationKind {
+    RuntimeError,
+    TurnTimeout,
+    Deadlock,
+    /// Disqualified by an admin
+    Manual,
+    /// Disqualified before disqualifications were recorded
+    Unknown,
+}
+impl DisqualificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisqualificationKind::RuntimeError => "runtime_error",
+            DisqualificationKind::TurnTimeout => "turn_timeout",
+            DisqualificationKind::Deadlock => "deadlock",
+            DisqualificationKind::Manual => "manual",
+            DisqualificationKind::Unknown => "unknown",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "runtime_error" => DisqualificationKind::RuntimeError,
+            "turn_timeout" => DisqualificationKind::TurnTimeout,
+            "deadlock" => DisqualificationKind::Deadlock,
+            "manual" => DisqualificationKind::Manual,
+            _ => DisqualificationKind::Unknown,
+        }
+    }
+
+    /// Timeouts might be caused by load on the host rather than the bot.
+    /// Deadlocks can't, a match is called after MAX_TURNS moves however
+    /// long each of them took, so a rerun has nothing to rule out.
+    pub fn may_be_infrastructure(&self) -> bool {
+        *self == DisqualificationKind::TurnTimeout
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisqualificationStatus {
+    Active,
+    /// A rerun failed the same way, it is up to the admins now
+    Confirmed,
+    Reinstated,
+}
+impl DisqualificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisqualificationStatus::Active => "active",
+            DisqualificationStatus::Confirmed => "confirmed",
+            DisqualificationStatus::Reinstated => "reinstated",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(DisqualificationStatus::Active),
+            "confirmed" => Some(DisqualificationStatus::Confirmed),
+            "reinstated" => Some(DisqualificationStatus::Reinstated),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
+#[table_name = "Disqualifications"]
+pub struct Disqualification {
+    pub id: String,
+    pub submission: String,
+    pub opponent: Option<String>,
+    /// Only set if the match could be recorded, deadlocks have no winner
+    pub match_id: Option<String>,
+    pub kind: String,
+    pub reason: String,
+    pub flipped: i32,        // Boolean, the submission played as p1
+    pub infrastructure: i32, // Boolean, a rerun showed the server was at fault
+    pub status: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+impl Disqualification {
+    pub fn list(conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Disqualifications::dsl::created_at;
+        disqualification_dsl
+            .order(created_at.desc())
+            .load::<Disqualification>(conn)
+            .expect("Error loading disqualifications")
+    }
+
+    pub fn by_status(value: DisqualificationStatus, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Disqualifications::dsl::{created_at, status};
+        disqualification_dsl
+            .filter(status.eq(value.as_str()))
+            .order(created_at.desc())
+            .load::<Disqualification>(conn)
+            .expect("Error loading disqualifications")
+    }
+
+    pub fn by_id(id: &str, conn: &SqliteConnection) -> Option<Self> {
+        disqualification_dsl
+            .find(id)
+            .get_result::<Disqualification>(conn)
+            .ok()
+    }
+
+    pub fn by_submission(submission_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Disqualifications::dsl::{created_at, submission};
+        disqualification_dsl
+            .filter(submission.eq(submission_id))
+            .order(created_at.desc())
+            .load::<Disqualification>(conn)
+            .expect("Error loading disqualifications")
+    }
+
+    pub fn create(
+        submission_id: &str,
+        opponent_id: Option<&str>,
+        match_id: Option<&str>,
+        kind: DisqualificationKind,
+        reason: &str,
+        flipped: bool,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
+        let new_id = Uuid::new_v4().to_hyphenated().to_string();
+        let new_record = Disqualification {
+            id: new_id.clone(),
+            submission: submission_id.into(),
+            opponent: opponent_id.map(|id| id.into()),
+            match_id: match_id.map(|id| id.into()),
+            kind: kind.as_str().into(),
+            reason: reason.into(),
+            flipped: flipped as i32,
+            infrastructure: 0,
+            status: DisqualificationStatus::Active.as_str().into(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        };
+
+        diesel::insert_into(disqualification_dsl)
+            .values(&new_record)
+            .execute(conn)
+            .expect("Error saving new disqualification");
+        Self::by_id(&new_id, conn)
+    }
+
+    pub fn save(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Disqualifications::dsl::{
+            id, infrastructure, reason, status, updated_at,
+        };
+        diesel::update(disqualification_dsl.filter(id.eq(&self.id)))
+            .set((
+                reason.eq(&self.reason),
+                infrastructure.eq(self.infrastructure),
+                status.eq(&self.status),
+                updated_at.eq(chrono::Local::now().naive_local()),
+            ))
+            .execute(conn)
+            .expect("Could not update record");
+    }
+
+    /// Removes every record of a submission, used when it is deleted
+    pub fn delete_by_submission(submission_id: &str, conn: &SqliteConnection) {
+        use crate::backend::schema::Disqualifications::dsl::{opponent, submission};
+        diesel::delete(disqualification_dsl.filter(submission.eq(submission_id)))
+            .execute(conn)
+            .expect("Could not delete disqualifications");
+        diesel::update(disqualification_dsl.filter(opponent.eq(submission_id)))
+            .set(opponent.eq(None::<String>))
+            .execute(conn)
+            .expect("Could not update disqualifications");
+    }
+
+    /// Keeps the records of a deleted match, only the reference is removed
+    pub fn detach_match(deleted_match_id: &str, conn: &SqliteConnection) {
+        use crate::backend::schema::Disqualifications::dsl::match_id;
+        diesel::update(disqualification_dsl.filter(match_id.eq(deleted_match_id)))
+            .set(match_id.eq(None::<String>))
+            .execute(conn)
+            .expect("Could not update disqualifications");
+    }
+
+    pub fn kind(&self) -> DisqualificationKind {
+        DisqualificationKind::parse(&self.kind)
+    }
+
+    pub fn is_reinstated(&self) -> bool {
+        self.status == DisqualificationStatus::Reinstated.as_str()
+    }
+
+    pub fn set_status(&mut self, value: DisqualificationStatus) {
+        self.status = value.as_str().to_string();
+    }
+}
@@ -0,0 +1,176 @@
+use crate::backend::schema::Jobs;
+use crate::backend::schema::Jobs::dsl::Jobs as job_dsl;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "failed" => Some(JobStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
+#[table_name = "Jobs"]
+pub struct Job {
+    pub id: String,
+    pub kind: String,
+    #[serde(skip_serializing)]
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub run_at: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    /// The last step the job got through, a retry continues after it
+    pub checkpoint: Option<String>,
+}
+impl Job {
+    pub fn list(conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Jobs::dsl::created_at;
+        job_dsl
+            .order(created_at.desc())
+            .load::<Job>(conn)
+            .expect("Error loading jobs")
+    }
+
+    pub fn by_status(job_status: JobStatus, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Jobs::dsl::{created_at, status};
+        job_dsl
+            .filter(status.eq(job_status.as_str()))
+            .order(created_at.desc())
+            .load::<Job>(conn)
+            .expect("Error loading jobs")
+    }
+
+    pub fn by_id(id: &str, conn: &SqliteConnection) -> Option<Self> {
+        job_dsl.find(id).get_result::<Job>(conn).ok()
+    }
+
+    /// The newest job of a kind for a payload, e.g. the placements of a submission
+    pub fn latest_by_payload(
+        job_kind: &str,
+        job_payload: &str,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
+        use crate::backend::schema::Jobs::dsl::{created_at, kind, payload};
+        job_dsl
+            .filter(kind.eq(job_kind))
+            .filter(payload.eq(job_payload))
+            .order(created_at.desc())
+            .first::<Job>(conn)
+            .ok()
+    }
+
+    /// Returns the oldest pending job that is due to run
+    pub fn next_due(conn: &SqliteConnection) -> Option<Self> {
+        use crate::backend::schema::Jobs::dsl::{run_at, status};
+        job_dsl
+            .filter(status.eq(JobStatus::Pending.as_str()))
+            .filter(run_at.le(chrono::Local::now().naive_local()))
+            .order(run_at.asc())
+            .first::<Job>(conn)
+            .ok()
+    }
+
+    pub fn create(kind: &str, payload: &str, conn: &SqliteConnection) -> Option<Self> {
+        Self::schedule(kind, payload, chrono::Local::now().naive_local(), conn)
+    }
+
+    /// A job that doesn't run before `run_at`
+    pub fn schedule(
+        kind: &str,
+        payload: &str,
+        run_at: chrono::NaiveDateTime,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
+        let new_id = Uuid::new_v4().to_hyphenated().to_string();
+        let mut new_job = Self::new_job_struct(&new_id, kind, payload);
+        new_job.run_at = run_at;
+
+        match diesel::insert_into(job_dsl).values(&new_job).execute(conn) {
+            Ok(_) => Self::by_id(&new_id, conn),
+            Err(_) => None,
+        }
+    }
+
+    /// Jobs that were running when the server went down
+    /// are put back into the queue.
+    pub fn requeue_interrupted(conn: &SqliteConnection) -> usize {
+        use crate::backend::schema::Jobs::dsl::{status, updated_at};
+        diesel::update(job_dsl.filter(status.eq(JobStatus::Running.as_str())))
+            .set((
+                status.eq(JobStatus::Pending.as_str()),
+                updated_at.eq(chrono::Local::now().naive_local()),
+            ))
+            .execute(conn)
+            .expect("Could not requeue interrupted jobs")
+    }
+
+    pub fn save(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Jobs::dsl::{
+            attempts, id, last_error, run_at, status, updated_at,
+        };
+        diesel::update(job_dsl.filter(id.eq(&self.id)))
+            .set((
+                status.eq(&self.status),
+                attempts.eq(self.attempts),
+                last_error.eq(&self.last_error),
+                run_at.eq(self.run_at),
+                updated_at.eq(chrono::Local::now().naive_local()),
+            ))
+            .execute(conn)
+            .expect("Could not update record");
+    }
+
+    /// Stored right away, so that it survives the job failing later on
+    pub fn save_checkpoint(&self, step: &str, conn: &SqliteConnection) {
+        use crate::backend::schema::Jobs::dsl::{checkpoint, id};
+        diesel::update(job_dsl.filter(id.eq(&self.id)))
+            .set(checkpoint.eq(step))
+            .execute(conn)
+            .expect("Could not update record");
+    }
+
+    pub fn set_status(&mut self, job_status: JobStatus) {
+        self.status = job_status.as_str().to_string();
+    }
+
+    fn new_job_struct(id: &str, kind: &str, payload: &str) -> Self {
+        Job {
+            id: id.into(),
+            kind: kind.into(),
+            payload: payload.into(),
+            status: JobStatus::Pending.as_str().to_string(),
+            attempts: 0,
+            last_error: None,
+            run_at: chrono::Local::now().naive_local(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+            checkpoint: None,
+        }
+    }
+}
@@ -7,6 +7,15 @@ use uuid::Uuid;
 
 use super::{submission_model::Submission, turn_model::Turn, user_model::User};
 
+/// Narrows down a listing of matches, unset fields match every match
+#[derive(Debug, Default)]
+pub struct MatchFilter {
+    /// Matches the submission played in, on either side
+    pub submission: Option<String>,
+    pub after: Option<chrono::NaiveDateTime>,
+    pub before: Option<chrono::NaiveDateTime>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
 #[table_name = "Matches"]
 pub struct Match {
@@ -73,9 +82,10 @@ impl Match {
     }
 
     pub fn get_turns(target_match_id: &str, conn: &SqliteConnection) -> Option<Vec<Turn>> {
-        use crate::backend::schema::Turns::dsl::match_id;
+        use crate::backend::schema::Turns::dsl::{match_id, turn};
         match turns_dsl
             .filter(match_id.eq(target_match_id))
+            .order(turn.asc())
             .load::<Turn>(conn)
         {
             Ok(result) => Some(result),
@@ -83,6 +93,65 @@ impl Match {
         }
     }
 
+    /// Matches the submission played in, newest first
+    pub fn by_submission(submission_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Matches::dsl::{created_at, loser, winner};
+        matches_dsl
+            .filter(winner.eq(submission_id).or(loser.eq(submission_id)))
+            .order(created_at.desc())
+            .load::<Match>(conn)
+            .expect("Error loading matches")
+    }
+
+    /// A page of the matches matching the filter, newest first,
+    /// together with how many match in total
+    pub fn search(
+        filter: &MatchFilter,
+        offset: i64,
+        limit: i64,
+        conn: &SqliteConnection,
+    ) -> (Vec<Self>, i64) {
+        use crate::backend::schema::Matches::dsl::created_at;
+        let total = Self::filtered(filter)
+            .select(diesel::dsl::count_star())
+            .first::<i64>(conn)
+            .expect("Error counting matches");
+        let page = Self::filtered(filter)
+            .order(created_at.desc())
+            .offset(offset)
+            .limit(limit)
+            .load::<Match>(conn)
+            .expect("Error loading matches");
+        (page, total)
+    }
+
+    fn filtered(filter: &MatchFilter) -> Matches::BoxedQuery<'_, diesel::sqlite::Sqlite> {
+        use crate::backend::schema::Matches::dsl::{created_at, loser, winner};
+        let mut query = matches_dsl.into_boxed();
+        if let Some(submission_id) = filter.submission.as_ref() {
+            query = query.filter(winner.eq(submission_id).or(loser.eq(submission_id)));
+        }
+        if let Some(after) = filter.after {
+            query = query.filter(created_at.ge(after));
+        }
+        if let Some(before) = filter.before {
+            query = query.filter(created_at.lt(before));
+        }
+        query
+    }
+
+    /// Deletes the match together with its turns
+    pub fn delete(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Matches::dsl::id;
+        use crate::backend::schema::Turns::dsl::match_id;
+        diesel::delete(turns_dsl.filter(match_id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete turns");
+        diesel::delete(matches_dsl.filter(id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete match");
+    }
+
     pub fn create(
         winner_id: &str,
         loser_id: &str,
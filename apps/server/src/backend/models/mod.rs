@@ -1,4 +1,9 @@
+pub(crate) mod api_token_model;
+pub(crate) mod bot_model;
+pub(crate) mod disqualification_model;
+pub(crate) mod job_model;
 pub(crate) mod match_model;
 pub(crate) mod submission_model;
 pub(crate) mod turn_model;
 pub(crate) mod user_model;
+pub(crate) mod webhook_delivery_model;
@@ -5,6 +5,18 @@ use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// The issue number of submissions made through the http api
+pub const NO_ISSUE: i32 = 0;
+
+/// Narrows down a listing of submissions, unset fields match every submission
+#[derive(Debug, Default)]
+pub struct SubmissionFilter {
+    pub user: Option<String>,
+    pub disqualified: Option<bool>,
+    pub min_mmr: Option<f32>,
+    pub max_mmr: Option<f32>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
 #[table_name = "Submissions"]
 pub struct Submission {
@@ -20,6 +32,9 @@ pub struct Submission {
     pub disqualified: i32, // Boolean SQLite doesn't support booleans
     pub mmr: f32,
     pub matches_played: i32,
+    pub retired: i32, // Boolean, retired submissions no longer take part in matches
+    pub bot: Option<String>,
+    pub version: i32, // Starts at 1 for the first submission of a bot
 }
 impl Submission {
     pub fn list(conn: &SqliteConnection) -> Vec<Self> {
@@ -34,16 +49,104 @@ impl Submission {
             None
         }
     }
-    pub fn by_script(script_str: &str, conn: &SqliteConnection) -> Option<Self> {
-        use crate::backend::schema::Submissions::dsl::script;
-        if let Ok(record) = submission_dsl
-            .filter(script.eq(script_str))
+    /// A submission with the same script. Earlier submissions of the
+    /// user's own issue don't count, a retired issue can be reopened.
+    pub fn by_script(
+        script_str: &str,
+        user_id: &str,
+        number: i32,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
+        use crate::backend::schema::Submissions::dsl::{issue_number, script, user};
+        let mut query = submission_dsl.filter(script.eq(script_str)).into_boxed();
+        if number != NO_ISSUE {
+            query = query.filter(user.ne(user_id).or(issue_number.ne(number)));
+        }
+        query.first::<Submission>(conn).ok()
+    }
+
+    /// The submission of an issue that is still taking part in matches
+    pub fn by_issue_number(number: i32, conn: &SqliteConnection) -> Option<Self> {
+        use crate::backend::schema::Submissions::dsl::{created_at, issue_number, retired};
+        submission_dsl
+            .filter(issue_number.eq(number))
+            .filter(retired.eq(0))
+            .order(created_at.desc())
             .first::<Submission>(conn)
-        {
-            Some(record)
-        } else {
-            None
+            .ok()
+    }
+
+    /// The most recent submission of an issue, including retired ones
+    pub fn latest_by_issue_number(number: i32, conn: &SqliteConnection) -> Option<Self> {
+        use crate::backend::schema::Submissions::dsl::{created_at, issue_number};
+        submission_dsl
+            .filter(issue_number.eq(number))
+            .order(created_at.desc())
+            .first::<Submission>(conn)
+            .ok()
+    }
+
+    /// Every version of a bot, oldest first
+    pub fn by_bot(bot_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Submissions::dsl::{bot, version};
+        submission_dsl
+            .filter(bot.eq(bot_id))
+            .order(version.asc())
+            .load::<Submission>(conn)
+            .expect("Error loading submissions")
+    }
+
+    pub fn by_user(user_id: &str, conn: &SqliteConnection) -> Vec<Self> {
+        use crate::backend::schema::Submissions::dsl::{created_at, user};
+        submission_dsl
+            .filter(user.eq(user_id))
+            .order(created_at.asc())
+            .load::<Submission>(conn)
+            .expect("Error loading submissions")
+    }
+
+    /// A page of the submissions matching the filter, best rated first,
+    /// together with how many match in total
+    pub fn search(
+        filter: &SubmissionFilter,
+        offset: i64,
+        limit: i64,
+        conn: &SqliteConnection,
+    ) -> (Vec<Self>, i64) {
+        use crate::backend::schema::Submissions::dsl::mmr;
+        let total = Self::filtered(filter)
+            .select(diesel::dsl::count_star())
+            .first::<i64>(conn)
+            .expect("Error counting submissions");
+        let page = Self::filtered(filter)
+            .order(mmr.desc())
+            .offset(offset)
+            .limit(limit)
+            .load::<Submission>(conn)
+            .expect("Error loading submissions");
+        (page, total)
+    }
+
+    fn filtered(filter: &SubmissionFilter) -> Submissions::BoxedQuery<'_, diesel::sqlite::Sqlite> {
+        use crate::backend::schema::Submissions::dsl::{disqualified, mmr, user};
+        let mut query = submission_dsl.into_boxed();
+        if let Some(user_id) = filter.user.as_ref() {
+            query = query.filter(user.eq(user_id));
+        }
+        if let Some(is_disqualified) = filter.disqualified {
+            query = if is_disqualified {
+                query.filter(disqualified.ge(1))
+            } else {
+                query.filter(disqualified.eq(0))
+            };
         }
+        if let Some(min_mmr) = filter.min_mmr {
+            query = query.filter(mmr.ge(min_mmr));
+        }
+        if let Some(max_mmr) = filter.max_mmr {
+            query = query.filter(mmr.le(max_mmr));
+        }
+        query
     }
 
     pub fn by_score(wins_value: i32, conn: &SqliteConnection) -> Option<Self> {
@@ -59,8 +162,10 @@ impl Submission {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create(
         user_id: &str,
+        bot_id: &str,
         script: &str,
         comment: Option<&str>,
         score: i32,
@@ -72,11 +177,12 @@ impl Submission {
 
         // Check that script doens't already exist
         // we do not allow duplicates
-        if is_plagiarism_enabled() && Self::by_script(&script, conn).is_some() {
+        if is_plagiarism_enabled() && Self::by_script(script, user_id, issue_number, conn).is_some()
+        {
             return Err(());
         }
 
-        let new_submission = Self::new_submission_struct(
+        let mut new_submission = Self::new_submission_struct(
             &new_id,
             user_id,
             script,
@@ -86,6 +192,10 @@ impl Submission {
             issue_url,
             issue_number,
         );
+        new_submission.bot = Some(bot_id.into());
+        new_submission.version = Self::by_bot(bot_id, conn)
+            .last()
+            .map_or(1, |previous| previous.version + 1);
 
         diesel::insert_into(submission_dsl)
             .values(&new_submission)
@@ -94,9 +204,30 @@ impl Submission {
         Ok(Self::by_id(&new_id, conn))
     }
 
+    /// A submission that is never inserted, used to
+    /// evaluate bots before they enter the ladder
+    pub fn unsaved(
+        user_id: &str,
+        script: &str,
+        comment: Option<&str>,
+        issue_url: &str,
+        issue_number: i32,
+    ) -> Self {
+        Self::new_submission_struct(
+            &Uuid::new_v4().to_hyphenated().to_string(),
+            user_id,
+            script,
+            comment,
+            0,
+            MMR_START_RATING,
+            issue_url,
+            issue_number,
+        )
+    }
+
     pub fn save(&self, conn: &SqliteConnection) {
         use crate::backend::schema::Submissions::dsl::{
-            disqualified, id, matches_played, mmr, updated_at, wins,
+            disqualified, id, matches_played, mmr, retired, updated_at, wins,
         };
         diesel::update(submission_dsl.filter(id.eq(&self.id)))
             .set((
@@ -105,11 +236,39 @@ impl Submission {
                 disqualified.eq(self.disqualified),
                 mmr.eq(self.mmr),
                 matches_played.eq(self.matches_played),
+                retired.eq(self.retired),
             ))
             .execute(conn)
             .expect("Could not update record");
     }
 
+    pub fn delete(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Submissions::dsl::id;
+        diesel::delete(submission_dsl.filter(id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete submission");
+    }
+
+    /// Submissions made through the http api have no issue to report to
+    pub fn has_issue(&self) -> bool {
+        self.issue_number != NO_ISSUE
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.disqualified == 0 && self.retired == 0
+    }
+
+    /// active, disqualified or retired
+    pub fn status(&self) -> &'static str {
+        if self.disqualified >= 1 {
+            "disqualified"
+        } else if self.retired >= 1 {
+            "retired"
+        } else {
+            "active"
+        }
+    }
+
     fn new_submission_struct(
         id: &str,
         user_id: &str,
@@ -129,6 +288,9 @@ impl Submission {
             disqualified: 0,
             mmr,
             matches_played: 0,
+            retired: 0,
+            bot: None,
+            version: 1,
             issue_url: issue_url.into(),
             issue_number,
             created_at: chrono::Local::now().naive_local(),
@@ -136,3 +298,22 @@ impl Submission {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Submission;
+    use crate::backend::fixtures::{setup, submission, UPWARDS_BOT};
+
+    #[test]
+    fn scripts_of_the_same_issue_are_no_duplicates() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let alice = submission("alice", UPWARDS_BOT, &conn);
+
+        assert!(
+            Submission::by_script(UPWARDS_BOT, &alice.user, alice.issue_number, &conn).is_none()
+        );
+        assert!(Submission::by_script(UPWARDS_BOT, &alice.user, 2, &conn).is_some());
+        assert!(Submission::by_script(UPWARDS_BOT, "bob", alice.issue_number, &conn).is_some());
+    }
+}
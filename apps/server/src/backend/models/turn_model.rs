@@ -15,6 +15,8 @@ pub struct Turn {
     pub board: String,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// What the bot printed with log() during the turn, one line per call
+    pub log: Option<String>,
 }
 impl Turn {
     pub fn list(conn: &SqliteConnection) -> Vec<Self> {
@@ -41,7 +43,13 @@ impl Turn {
         }
     }
 
-    pub fn create(match_id: &str, turn: i32, board: &str, conn: &SqliteConnection) -> Option<Self> {
+    pub fn create(
+        match_id: &str,
+        turn: i32,
+        board: &str,
+        log: Option<&str>,
+        conn: &SqliteConnection,
+    ) -> Option<Self> {
         let new_id = Uuid::new_v4().to_hyphenated().to_string();
 
         // Make sure match exists
@@ -50,7 +58,7 @@ impl Turn {
             return None;
         }
 
-        let new_match = Self::new_turn_struct(&new_id, match_id, turn, board);
+        let new_match = Self::new_turn_struct(&new_id, match_id, turn, board, log);
 
         diesel::insert_into(turns_dsl)
             .values(&new_match)
@@ -58,7 +66,13 @@ impl Turn {
             .expect("Error saving new turns");
         Self::by_id(&new_id, conn)
     }
-    fn new_turn_struct(id: &str, match_id: &str, turn: i32, board: &str) -> Self {
+    fn new_turn_struct(
+        id: &str,
+        match_id: &str,
+        turn: i32,
+        board: &str,
+        log: Option<&str>,
+    ) -> Self {
         Turn {
             id: id.into(),
             match_id: match_id.into(),
@@ -66,6 +80,7 @@ impl Turn {
             board: board.into(),
             created_at: chrono::Local::now().naive_local(),
             updated_at: chrono::Local::now().naive_local(),
+            log: log.map(|log| log.to_string()),
         }
     }
 }
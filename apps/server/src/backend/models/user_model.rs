@@ -51,6 +51,13 @@ impl User {
             .expect("Error saving new user");
         Self::by_id(&new_id, conn)
     }
+    pub fn delete(&self, conn: &SqliteConnection) {
+        use crate::backend::schema::Users::dsl::id;
+        diesel::delete(user_dsl.filter(id.eq(&self.id)))
+            .execute(conn)
+            .expect("Could not delete user");
+    }
+
     fn new_user_struct(id: &str, username: &str) -> Self {
         User {
             id: id.into(),
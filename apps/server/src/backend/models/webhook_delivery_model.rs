@@ -0,0 +1,34 @@
+use crate::backend::schema::WebhookDeliveries;
+use crate::backend::schema::WebhookDeliveries::dsl::WebhookDeliveries as delivery_dsl;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A GitHub webhook delivery that has already been accepted,
+/// the id is the value of the X-GitHub-Delivery header.
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Insertable)]
+#[table_name = "WebhookDeliveries"]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+impl WebhookDelivery {
+    /// Returns None if the delivery has already been recorded
+    pub fn create(id: &str, event: &str, conn: &SqliteConnection) -> Option<Self> {
+        let new_delivery = WebhookDelivery {
+            id: id.into(),
+            event: event.into(),
+            created_at: chrono::Local::now().naive_local(),
+        };
+
+        // The primary key makes sure two simultaneous
+        // retries cannot both claim the same delivery
+        match diesel::insert_into(delivery_dsl)
+            .values(&new_delivery)
+            .execute(conn)
+        {
+            Ok(_) => Some(new_delivery),
+            Err(_) => None,
+        }
+    }
+}
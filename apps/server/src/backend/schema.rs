@@ -1,5 +1,57 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    ApiTokens (id) {
+        id -> Text,
+        user -> Text,
+        name -> Text,
+        token_hash -> Text,
+        last_used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    Bots (id) {
+        id -> Text,
+        user -> Text,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    Disqualifications (id) {
+        id -> Text,
+        submission -> Text,
+        opponent -> Nullable<Text>,
+        match_id -> Nullable<Text>,
+        kind -> Text,
+        reason -> Text,
+        flipped -> Integer,
+        infrastructure -> Integer,
+        status -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    Jobs (id) {
+        id -> Text,
+        kind -> Text,
+        payload -> Text,
+        status -> Text,
+        attempts -> Integer,
+        last_error -> Nullable<Text>,
+        run_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        checkpoint -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     Matches (id) {
         id -> Text,
@@ -26,6 +78,9 @@ diesel::table! {
         disqualified -> Integer,
         mmr -> Float,
         matches_played -> Integer,
+        retired -> Integer,
+        bot -> Nullable<Text>,
+        version -> Integer,
     }
 }
 
@@ -37,6 +92,7 @@ diesel::table! {
         board -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        log -> Nullable<Text>,
     }
 }
 
@@ -49,12 +105,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    WebhookDeliveries (id) {
+        id -> Text,
+        event -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(ApiTokens -> Users (user));
+diesel::joinable!(Bots -> Users (user));
+diesel::joinable!(Disqualifications -> Matches (match_id));
+diesel::joinable!(Submissions -> Bots (bot));
 diesel::joinable!(Submissions -> Users (user));
 diesel::joinable!(Turns -> Matches (match_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    ApiTokens,
+    Bots,
+    Disqualifications,
+    Jobs,
     Matches,
     Submissions,
     Turns,
     Users,
+    WebhookDeliveries,
 );
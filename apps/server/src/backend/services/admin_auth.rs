@@ -0,0 +1,23 @@
+use actix_web::HttpRequest;
+
+/// Admin endpoints require `Authorization: Bearer <ADMIN_API_TOKEN>`,
+/// they are disabled when no token is configured.
+pub(super) fn require_admin(req: &HttpRequest) -> actix_web::Result<()> {
+    let token = match std::env::var("ADMIN_API_TOKEN") {
+        Ok(token) if !token.is_empty() => token,
+        _ => {
+            return Err(actix_web::error::ErrorForbidden(
+                "Admin endpoints are disabled",
+            ))
+        }
+    };
+    let provided = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match provided {
+        Some(provided) if provided == token => Ok(()),
+        _ => Err(actix_web::error::ErrorUnauthorized("Invalid admin token")),
+    }
+}
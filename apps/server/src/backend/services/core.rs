@@ -1,117 +1,132 @@
 use crate::backend::db::DbPool;
-use crate::backend::models::submission_model::Submission;
-use crate::backend::models::user_model::User;
-use crate::external_related::code_unwrapper::unwrap_code;
-use crate::external_related::github::close_issue::{close_issue, CloseType};
-use crate::external_related::github::create_issue_comment::create_issue_comment;
-use crate::external_related::github::webhook_schema::{GithubPayload, Label};
-use crate::match_maker::match_executor::MatchReport;
-use crate::match_maker::placements::run_placements;
-use crate::match_maker::regenerate_markdown_files::regen_markdown_files_and_update_repo;
-use actix_web::{post, web};
+use crate::backend::models::job_model::Job;
+use crate::backend::models::webhook_delivery_model::WebhookDelivery;
+use crate::external_related::github::webhook_events::{
+    classify_pull_request, classify_webhook, WebhookAction,
+};
+use crate::external_related::github::webhook_schema::{GithubPayload, PullRequestPayload};
+use crate::external_related::github::webhook_signature::{verify_signature, WebhookSecret};
+use crate::external_related::repo_updater::is_pull_request_submission_enabled;
+use crate::job_queue::command_job::COMMAND_JOB;
+use crate::job_queue::pull_request_job::{PULL_REQUEST_CHECK_JOB, PULL_REQUEST_MERGE_JOB};
+use crate::job_queue::submission_job::{RESUBMISSION_JOB, SUBMISSION_JOB};
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use diesel::Connection;
+use serde::de::DeserializeOwned;
 
+/// Receives "issues" and "issue_comment" events for challenger issues
+/// and "pull_request" events for bots submitted as files.
+/// Submissions and commands are only queued here, they are executed
+/// by the job worker once the webhook has returned.
 #[post("/api/challenge")]
 pub async fn submit_challenge(
-    webhook_post: web::Json<GithubPayload>,
+    req: HttpRequest,
+    body: web::Bytes,
     db_pool: web::Data<DbPool>,
-) -> actix_web::Result<String> {
-    let conn = db_pool.get().unwrap();
-    // Validate the the submission is a challenger submission
-    if !valid_request(&webhook_post.action, &webhook_post.issue.labels) {
-        return Ok(format!(
-            "Only accepts \"opened\" actions and must be marked with the \"challenger\" label"
-        ));
-    }
-
-    // If user doesn't exist we create it
-    let mut user = User::by_username(&webhook_post.sender.login, &conn);
-    if user.is_none() {
-        // Create user
-        user = User::create(&webhook_post.sender.login, &conn);
-
-        if user.is_none() {
-            println!("Error: Could not create user");
-            create_issue_comment(
-                webhook_post.issue.number,
-                "Internal error, please try again later",
-            );
-            close_issue(CloseType::NotPlanned, webhook_post.issue.number);
-            return Ok(format!("Internal error"));
+    webhook_secret: web::Data<WebhookSecret>,
+) -> actix_web::Result<HttpResponse> {
+    // Deliveries must be signed with the webhook secret,
+    // otherwise anyone could submit in someone else's name
+    let secret = match &webhook_secret.0 {
+        Some(secret) => secret,
+        None => {
+            println!("[ERROR] GITHUB_WEBHOOK_SECRET is not set, rejecting webhook");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Webhook secret is not configured",
+            ));
         }
+    };
+    if let Err(reason) = verify_signature(secret, &body, header_value(&req, "X-Hub-Signature-256"))
+    {
+        return Err(actix_web::error::ErrorUnauthorized(reason));
     }
 
-    // Get lua code from issue body
-    let code = match unwrap_code(&webhook_post.issue.body) {
-        Ok(code) => code,
-        Err(e) => {
-            create_issue_comment(webhook_post.issue.number, &e);
-            close_issue(CloseType::NotPlanned, webhook_post.issue.number);
-            return Ok(format!("{}", e));
+    let delivery_id = match header_value(&req, "X-GitHub-Delivery") {
+        Some(delivery_id) => delivery_id.to_string(),
+        None => {
+            return Err(actix_web::error::ErrorBadRequest(
+                "Missing X-GitHub-Delivery header",
+            ))
         }
     };
+    let event = header_value(&req, "X-GitHub-Event").unwrap_or("unknown");
 
-    // Create submission
-    let challenger = match Submission::create(
-        &user.as_ref().unwrap().id,
-        &code,
-        Some(&webhook_post.issue.title),
-        0,
-        &webhook_post.issue.html_url,
-        webhook_post.issue.number,
-        &conn,
-    ) {
-        Ok(submission) => submission,
-        Err(_) => {
-            create_issue_comment(
-                webhook_post.issue.number,
-                &format!("This submission has already been submitted before"),
-            );
-            close_issue(CloseType::NotPlanned, webhook_post.issue.number);
-            return Ok(format!(
-                "{}",
-                "This submission has already been submitted before"
-            ));
+    // Payloads are serialized again for the job so that it only stores the fields we use
+    let (action, payload) = if event == "pull_request" {
+        if !is_pull_request_submission_enabled() {
+            return Ok(HttpResponse::Ok().body("Pull request submissions are disabled"));
         }
+        let pull_request: PullRequestPayload = parse_body(&body)?;
+        (
+            classify_pull_request(&pull_request),
+            serde_json::to_string(&pull_request),
+        )
+    } else {
+        let webhook_post: GithubPayload = parse_body(&body)?;
+        (
+            classify_webhook(event, &webhook_post),
+            serde_json::to_string(&webhook_post),
+        )
     };
 
-    if challenger.is_none() {
-        create_issue_comment(
-            webhook_post.issue.number,
-            "Error: Internal error, could not create submission...<br>Try again later",
-        );
-        close_issue(CloseType::NotPlanned, webhook_post.issue.number);
-        return Ok(format!("Could not create submission...<br>Try again later"));
-    }
+    let job_kind = match action {
+        WebhookAction::Submit => SUBMISSION_JOB,
+        WebhookAction::Resubmit => RESUBMISSION_JOB,
+        WebhookAction::Command(_) | WebhookAction::UnknownCommand(_) => COMMAND_JOB,
+        WebhookAction::CheckPullRequest => PULL_REQUEST_CHECK_JOB,
+        WebhookAction::MergePullRequest => PULL_REQUEST_MERGE_JOB,
+        WebhookAction::Ignore(reason) => return Ok(HttpResponse::Ok().body(reason)),
+    };
 
-    create_issue_comment(webhook_post.issue.number, &format!("User: {}<br>Script-id: {}<br>Thanks for submitting!<br>Your code is being processed...", webhook_post.sender.login, challenger.as_ref().unwrap().id));
+    let payload = match payload {
+        Ok(payload) => payload,
+        Err(error) => return Err(actix_web::error::ErrorBadRequest(error)),
+    };
 
-    let reports = run_placements(&challenger.clone().unwrap(), &conn);
+    let conn = db_pool.get().unwrap();
 
-    let mut output = String::new();
-    let mut opponent_output: Vec<&MatchReport> = Vec::new();
-    for (challenger_report, opponent_report) in reports.iter() {
-        output += &challenger_report.report;
-        output += "<br>";
-        opponent_output.push(opponent_report);
-    }
-    if reports.len() == 0 {
-        create_issue_comment(webhook_post.issue.number, &format!("Bot has been registered but could not be match-maked against another bot, wait for someone else to create a bot..."));
-    } else {
-        create_issue_comment(webhook_post.issue.number, &output);
-    }
+    // GitHub redelivers on timeouts, a delivery we have
+    // already accepted must not create another submission.
+    // The delivery is only recorded together with its job so
+    // that one we failed to queue can still be redelivered.
+    let queued = conn.transaction(|| {
+        if WebhookDelivery::create(&delivery_id, event, &conn).is_none() {
+            return Err(QueueError::AlreadyProcessed);
+        }
+        Job::create(job_kind, &payload, &conn).ok_or(QueueError::Database)
+    });
 
-    // Notify opponents too
-    for opponent in opponent_output.iter() {
-        create_issue_comment(opponent.issue_number, &opponent.report);
+    match queued {
+        Ok(job) => Ok(HttpResponse::Accepted().json(job)),
+        Err(QueueError::AlreadyProcessed) => Ok(HttpResponse::Ok().body(format!(
+            "Delivery {} has already been processed",
+            delivery_id
+        ))),
+        Err(QueueError::Database) => Err(actix_web::error::ErrorInternalServerError(
+            "Could not queue job",
+        )),
     }
+}
 
-    close_issue(CloseType::Completed, webhook_post.issue.number);
+/// Why a delivery didn't result in a job, either
+/// way nothing of it is left in the database
+enum QueueError {
+    AlreadyProcessed,
+    Database,
+}
 
-    match regen_markdown_files_and_update_repo(&conn) {
-        Ok(msg) | Err(msg) => Ok(msg),
+impl From<diesel::result::Error> for QueueError {
+    fn from(_: diesel::result::Error) -> Self {
+        QueueError::Database
     }
 }
 
-fn valid_request(action: &String, labels: &Vec<Label>) -> bool {
-    return action == "opened" && labels.iter().any(|current| current.name == "challenger");
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> actix_web::Result<T> {
+    serde_json::from_slice(body).map_err(actix_web::error::ErrorBadRequest)
+}
+
+fn header_value<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
 }
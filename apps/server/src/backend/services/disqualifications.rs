@@ -0,0 +1,100 @@
+use actix_web::{
+    get, post,
+    web::{self, Json},
+    HttpRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    db::DbPool,
+    models::{
+        disqualification_model::{Disqualification, DisqualificationStatus},
+        submission_model::Submission,
+    },
+};
+use crate::match_maker::disqualifications::reinstate;
+
+use super::admin_auth::require_admin;
+
+#[derive(Deserialize)]
+pub(crate) struct DisqualificationsQuery {
+    status: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct HttpResponseStruct {
+    disqualifications: Vec<Disqualification>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct HttpDisqualification {
+    disqualification: Disqualification,
+    submission: Option<Submission>,
+    opponent: Option<Submission>,
+}
+
+#[get("/api/disqualifications")]
+pub(super) async fn get_disqualifications_route(
+    query: web::Query<DisqualificationsQuery>,
+    db_pool: web::Data<DbPool>,
+) -> actix_web::Result<Json<HttpResponseStruct>> {
+    let conn = db_pool.get().unwrap();
+
+    let disqualifications = match &query.status {
+        Some(status) => match DisqualificationStatus::parse(status) {
+            Some(status) => Disqualification::by_status(status, &conn),
+            None => {
+                return Err(actix_web::error::ErrorBadRequest(format!(
+                    "Unknown disqualification status \"{}\"",
+                    status
+                )))
+            }
+        },
+        None => Disqualification::list(&conn),
+    };
+
+    Ok(Json(HttpResponseStruct { disqualifications }))
+}
+
+#[get("/api/disqualifications/{id}")]
+pub(super) async fn get_disqualification_route(
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> actix_web::Result<Json<HttpDisqualification>> {
+    let conn = db_pool.get().unwrap();
+    match Disqualification::by_id(&path.into_inner(), &conn) {
+        Some(disqualification) => Ok(Json(HttpDisqualification {
+            submission: Submission::by_id(&disqualification.submission, &conn),
+            opponent: disqualification
+                .opponent
+                .as_ref()
+                .and_then(|opponent| Submission::by_id(opponent, &conn)),
+            disqualification,
+        })),
+        None => Err(actix_web::error::ErrorNotFound(
+            "Could not find your disqualification...",
+        )),
+    }
+}
+
+#[post("/api/disqualifications/{id}/reinstate")]
+pub(super) async fn reinstate_disqualification_route(
+    req: HttpRequest,
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> actix_web::Result<Json<Disqualification>> {
+    require_admin(&req)?;
+    let conn = db_pool.get().unwrap();
+    let mut record = match Disqualification::by_id(&path.into_inner(), &conn) {
+        Some(record) => record,
+        None => {
+            return Err(actix_web::error::ErrorNotFound(
+                "Could not find your disqualification...",
+            ))
+        }
+    };
+    match reinstate(&mut record, &conn) {
+        Ok(_) => Ok(Json(record)),
+        Err(error) => Err(actix_web::error::ErrorConflict(error)),
+    }
+}
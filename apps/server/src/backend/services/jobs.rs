@@ -0,0 +1,57 @@
+use actix_web::{
+    get,
+    web::{self, Json},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    db::DbPool,
+    models::job_model::{Job, JobStatus},
+};
+
+#[derive(Deserialize)]
+pub(crate) struct JobsQuery {
+    status: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct HttpResponseStruct {
+    jobs: Vec<Job>,
+}
+
+#[get("/api/jobs")]
+pub(super) async fn get_jobs_route(
+    query: web::Query<JobsQuery>,
+    db_pool: web::Data<DbPool>,
+) -> actix_web::Result<Json<HttpResponseStruct>> {
+    let conn = db_pool.get().unwrap();
+
+    let jobs = match &query.status {
+        Some(status) => match JobStatus::parse(status) {
+            Some(status) => Job::by_status(status, &conn),
+            None => {
+                return Err(actix_web::error::ErrorBadRequest(format!(
+                    "Unknown job status \"{}\"",
+                    status
+                )))
+            }
+        },
+        None => Job::list(&conn),
+    };
+
+    Ok(Json(HttpResponseStruct { jobs }))
+}
+
+#[get("/api/jobs/{id}")]
+pub(super) async fn get_job_route(
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> actix_web::Result<Json<Job>> {
+    let conn = db_pool.get().unwrap();
+    match Job::by_id(&path.into_inner(), &conn) {
+        Some(job) => Ok(Json(job)),
+        None => Err(actix_web::error::ErrorNotFound(
+            "Could not find your job...",
+        )),
+    }
+}
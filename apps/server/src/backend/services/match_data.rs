@@ -10,6 +10,11 @@ use crate::backend::{
         match_model::Match, submission_model::Submission, turn_model::Turn, user_model::User,
     },
 };
+use crate::game::{
+    board::{board_from_string, Tile},
+    evaluation::{evaluate_match, TurnEvaluation},
+    replay::replay_from_boards,
+};
 
 #[derive(Serialize)]
 pub(crate) struct HttpMatch {
@@ -28,6 +33,8 @@ pub(crate) struct HttpMatch {
 pub(crate) struct HttpResponseStruct {
     result: HttpMatch,
     turns: Vec<Turn>,
+    /// One per turn, starting with the start position
+    evaluation: Vec<TurnEvaluation>,
 }
 
 #[get("/api/matches/{id}")]
@@ -45,7 +52,7 @@ pub(super) async fn get_match_route(
         ));
     };
 
-    let turns = if let Some(turns) = Turn::by_match_id(&id, &conn) {
+    let mut turns = if let Some(turns) = Turn::by_match_id(&id, &conn) {
         turns
     } else {
         return Err(actix_web::error::ErrorNotFound(
@@ -61,6 +68,13 @@ pub(super) async fn get_match_route(
         ));
     };
 
+    turns.sort_by_key(|turn| turn.turn);
+    let boards: Vec<Vec<Tile>> = turns
+        .iter()
+        .map(|turn| board_from_string(turn.board.clone()))
+        .collect();
+    let evaluation = evaluate_match(&replay_from_boards(&boards));
+
     return Ok(Json(HttpResponseStruct {
         result: HttpMatch {
             id: target_match.id,
@@ -74,5 +88,6 @@ pub(super) async fn get_match_route(
             match_error: target_match.match_error,
         },
         turns,
+        evaluation,
     }));
 }
@@ -1,5 +1,9 @@
+pub(crate) mod admin_auth;
 pub(crate) mod core;
+pub(crate) mod disqualifications;
+pub(crate) mod jobs;
 pub(crate) mod match_data;
 pub(crate) mod matches;
 pub(crate) mod ping;
 pub(crate) mod routes;
+pub(crate) mod v1;
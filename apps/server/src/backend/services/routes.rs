@@ -1,10 +1,18 @@
+use actix_files::Files;
 use actix_web::web;
 
+use crate::external_related::site_generator::SITE_DIR;
+
 use super::{
     core::submit_challenge,
+    disqualifications::{
+        get_disqualification_route, get_disqualifications_route, reinstate_disqualification_route,
+    },
+    jobs::{get_job_route, get_jobs_route},
     match_data::get_match_route,
     matches::get_matches_route,
     ping::{get_api_ping, get_ping},
+    v1,
 };
 
 pub(crate) fn routes(cfg: &mut web::ServiceConfig) {
@@ -13,4 +21,11 @@ pub(crate) fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(get_api_ping);
     cfg.service(get_match_route);
     cfg.service(get_matches_route);
+    cfg.service(get_jobs_route);
+    cfg.service(get_job_route);
+    cfg.service(get_disqualifications_route);
+    cfg.service(get_disqualification_route);
+    cfg.service(reinstate_disqualification_route);
+    cfg.configure(v1::routes);
+    cfg.service(Files::new("/site", SITE_DIR).index_file("index.html"));
 }
@@ -0,0 +1,20 @@
+use actix_web::HttpRequest;
+use diesel::SqliteConnection;
+
+use crate::backend::models::{api_token_model::ApiToken, user_model::User};
+
+use super::error::ApiError;
+
+/// The user behind `Authorization: Bearer <token>`, tokens are
+/// handed out with the tokens command of the admin cli
+pub(super) fn authenticate(req: &HttpRequest, conn: &SqliteConnection) -> Result<User, ApiError> {
+    let provided = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| ApiError::unauthorized("Missing api token"))?;
+    ApiToken::authenticate(provided, conn)
+        .and_then(|token| User::by_id(&token.user, conn))
+        .ok_or_else(|| ApiError::unauthorized("Invalid api token"))
+}
@@ -0,0 +1,61 @@
+use actix_web::{
+    get,
+    web::{self, Json},
+};
+use serde::Serialize;
+
+use crate::backend::{
+    db::DbPool,
+    models::{bot_model::Bot, submission_model::Submission, user_model::User},
+};
+use crate::match_maker::leaderboard::bot_standings;
+
+use super::{
+    connection,
+    error::ApiError,
+    pagination::{Page, PageQuery},
+};
+
+#[derive(Serialize)]
+pub(crate) struct ApiStanding {
+    rank: usize,
+    bot: String,
+    bot_name: String,
+    user: String,
+    username: Option<String>,
+    /// The version that plays for the bot
+    submission: String,
+    version: i32,
+    mmr: f32,
+    /// Compared to the version before, missing for the first version
+    rating_delta: Option<f32>,
+}
+
+/// One row per bot, best rated first
+#[get("/leaderboard")]
+pub(crate) async fn get_leaderboard_route(
+    page: web::Query<PageQuery>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<Page<ApiStanding>>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let users = User::list(&conn);
+    let standings = bot_standings(&Bot::list(&conn), &Submission::list(&conn))
+        .into_iter()
+        .enumerate()
+        .map(|(index, standing)| ApiStanding {
+            rank: index + 1,
+            rating_delta: standing.rating_delta(),
+            username: users
+                .iter()
+                .find(|user| user.id == standing.bot.user)
+                .map(|user| user.username.clone()),
+            bot: standing.bot.id,
+            bot_name: standing.bot.name,
+            user: standing.bot.user,
+            submission: standing.current.id,
+            version: standing.current.version,
+            mmr: standing.current.mmr,
+        })
+        .collect();
+    Ok(Json(page.slice(standings)?))
+}
@@ -0,0 +1,76 @@
+use actix_web::{
+    get,
+    web::{self, Json},
+    HttpRequest, HttpResponse,
+};
+use actix_ws::{Message, Session};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::match_maker::live_matches::{self, LiveEvent, LiveMatchSummary};
+
+use super::error::ApiError;
+
+/// The matches being played right now, oldest first
+#[get("/live")]
+pub(crate) async fn get_live_matches_route() -> Json<Vec<LiveMatchSummary>> {
+    Json(live_matches::list())
+}
+
+/// Streams a match in progress over a websocket, one json message per
+/// event. Spectators that join late get the turns played so far first,
+/// the socket is closed after the finished event.
+#[get("/live/{id}")]
+pub(crate) async fn watch_live_match_route(
+    req: HttpRequest,
+    body: web::Payload,
+    path: web::Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let id = path.into_inner();
+    let (history, mut receiver) = live_matches::subscribe(&id).ok_or_else(|| {
+        ApiError::not_found(format!("Match \"{}\" is not being played right now", id))
+    })?;
+    let (response, mut session, mut stream) = actix_ws::handle(&req, body)?;
+
+    actix_web::rt::spawn(async move {
+        for event in history {
+            if !send(&mut session, &event).await {
+                return;
+            }
+        }
+        loop {
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => {
+                        if !send(&mut session, &event).await {
+                            return;
+                        }
+                    }
+                    // Cannot happen, the channel holds every event of a match
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+                message = stream.recv() => match message {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => (),
+                },
+            }
+        }
+        let _ = session.close(None).await;
+    });
+
+    Ok(response)
+}
+
+/// Returns false once the spectator is gone or the match is over
+async fn send(session: &mut Session, event: &LiveEvent) -> bool {
+    let message = match serde_json::to_string(event) {
+        Ok(message) => message,
+        Err(_) => return false,
+    };
+    session.text(message).await.is_ok() && !matches!(event, LiveEvent::Finished(_))
+}
@@ -0,0 +1,138 @@
+use actix_web::{
+    get,
+    web::{self, Json},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    db::DbPool,
+    models::{
+        match_model::{Match, MatchFilter},
+        turn_model::Turn,
+    },
+};
+use crate::game::{
+    board::{board_from_string, Tile},
+    evaluation::{evaluate_match, TurnEvaluation},
+    replay::replay_from_boards,
+};
+
+use super::{
+    connection,
+    error::ApiError,
+    pagination::{Page, PageQuery},
+};
+
+#[derive(Serialize)]
+pub(crate) struct ApiMatch {
+    id: String,
+    /// Submission ids
+    winner: String,
+    loser: String,
+    p1_is_winner: bool,
+    error: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<Match> for ApiMatch {
+    fn from(target: Match) -> Self {
+        ApiMatch {
+            id: target.id,
+            winner: target.winner,
+            loser: target.loser,
+            p1_is_winner: target.p1_is_winner == 1,
+            error: target.match_error,
+            created_at: target.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ApiMatchDetail {
+    #[serde(flatten)]
+    result: ApiMatch,
+    turns: Vec<ApiTurn>,
+    /// One per turn, starting with the start position
+    evaluation: Vec<TurnEvaluation>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ApiTurn {
+    turn: i32,
+    board: String,
+    log: Option<String>,
+}
+
+/// ?submission=<id>&after=2026-10-01&before=2026-10-19T12:00:00
+#[derive(Deserialize)]
+pub(crate) struct MatchesQuery {
+    submission: Option<String>,
+    after: Option<String>,
+    before: Option<String>,
+}
+
+#[get("/matches")]
+pub(crate) async fn get_matches_route(
+    query: web::Query<MatchesQuery>,
+    page: web::Query<PageQuery>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<Page<ApiMatch>>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let filter = MatchFilter {
+        submission: query.submission.clone(),
+        after: query.after.as_deref().map(parse_date).transpose()?,
+        before: query.before.as_deref().map(parse_date).transpose()?,
+    };
+    let (offset, limit) = page.bounds()?;
+    let (matches, total) = Match::search(&filter, offset, limit, &conn);
+    Ok(Json(page.page(
+        matches.into_iter().map(ApiMatch::from).collect(),
+        total,
+    )?))
+}
+
+#[get("/matches/{id}")]
+pub(crate) async fn get_match_route(
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<ApiMatchDetail>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let id = path.into_inner();
+    let target = Match::by_id(&id, &conn)
+        .ok_or_else(|| ApiError::not_found(format!("Could not find match \"{}\"", id)))?;
+
+    let mut turns = Turn::by_match_id(&id, &conn).unwrap_or_default();
+    turns.sort_by_key(|turn| turn.turn);
+    let boards: Vec<Vec<Tile>> = turns
+        .iter()
+        .map(|turn| board_from_string(turn.board.clone()))
+        .collect();
+    Ok(Json(ApiMatchDetail {
+        result: ApiMatch::from(target),
+        evaluation: evaluate_match(&replay_from_boards(&boards)),
+        turns: turns
+            .into_iter()
+            .map(|turn| ApiTurn {
+                turn: turn.turn,
+                board: turn.board,
+                log: turn.log,
+            })
+            .collect(),
+    }))
+}
+
+/// A day like 2026-10-19 or a time like 2026-10-19T12:00:00
+fn parse_date(value: &str) -> Result<NaiveDateTime, ApiError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+        })
+        .map_err(|_| {
+            ApiError::bad_request(format!(
+                "Invalid date \"{}\", expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS",
+                value
+            ))
+        })
+}
@@ -0,0 +1,58 @@
+use actix_web::web;
+use diesel::{
+    r2d2::{ConnectionManager, PooledConnection},
+    SqliteConnection,
+};
+
+use crate::backend::db::DbPool;
+
+use self::error::ApiError;
+
+mod auth;
+pub(crate) mod error;
+pub(crate) mod leaderboard;
+pub(crate) mod live;
+pub(crate) mod matches;
+pub(crate) mod pagination;
+pub(crate) mod submissions;
+pub(crate) mod users;
+
+/// Everything under /api/v1, malformed requests and unknown paths get the same json errors as the handlers
+pub(crate) fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1")
+            .app_data(
+                web::QueryConfig::default()
+                    .error_handler(|error, _| ApiError::bad_request(error.to_string()).into()),
+            )
+            .app_data(
+                web::JsonConfig::default()
+                    .error_handler(|error, _| ApiError::bad_request(error.to_string()).into()),
+            )
+            .service(submissions::get_submissions_route)
+            .service(submissions::post_submission_route)
+            .service(submissions::get_submission_route)
+            .service(users::get_users_route)
+            .service(users::get_user_route)
+            .service(leaderboard::get_leaderboard_route)
+            .service(matches::get_matches_route)
+            .service(matches::get_match_route)
+            .service(live::get_live_matches_route)
+            .service(live::watch_live_match_route)
+            .default_service(web::to(|| async {
+                Err::<String, ApiError>(ApiError::not_found("Unknown endpoint"))
+            })),
+    );
+}
+
+pub(crate) fn connection(
+    db_pool: &DbPool,
+) -> Result<PooledConnection<ConnectionManager<SqliteConnection>>, ApiError> {
+    db_pool.get().map_err(|error| {
+        ApiError::internal(format!("Could not connect to the database: {}", error))
+    })
+}
+
+mod tests {
+    mod api_tests;
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::error::ApiError;
+
+const DEFAULT_PER_PAGE: i64 = 25;
+const MAX_PER_PAGE: i64 = 100;
+
+/// ?page=2&per_page=50, pages start at 1
+#[derive(Deserialize)]
+pub(crate) struct PageQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+impl PageQuery {
+    /// The offset and limit of the page
+    pub(crate) fn bounds(&self) -> Result<(i64, i64), ApiError> {
+        let page = self.page.unwrap_or(1);
+        let per_page = self.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if page < 1 {
+            return Err(ApiError::bad_request("page starts at 1"));
+        }
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::bad_request(format!(
+                "per_page must be between 1 and {}",
+                MAX_PER_PAGE
+            )));
+        }
+        let offset = (page - 1)
+            .checked_mul(per_page)
+            .ok_or_else(|| ApiError::bad_request("page is out of range"))?;
+        Ok((offset, per_page))
+    }
+
+    /// A page of items that are all in memory
+    pub(crate) fn slice<T>(&self, items: Vec<T>) -> Result<Page<T>, ApiError> {
+        let (offset, limit) = self.bounds()?;
+        let total = items.len() as i64;
+        let data = items
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+        self.page(data, total)
+    }
+
+    pub(crate) fn page<T>(&self, data: Vec<T>, total: i64) -> Result<Page<T>, ApiError> {
+        let (offset, limit) = self.bounds()?;
+        Ok(Page {
+            data,
+            page: offset / limit + 1,
+            per_page: limit,
+            total,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Page<T> {
+    pub(crate) data: Vec<T>,
+    pub(crate) page: i64,
+    pub(crate) per_page: i64,
+    /// Items over all pages
+    pub(crate) total: i64,
+}
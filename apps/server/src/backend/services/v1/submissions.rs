@@ -0,0 +1,234 @@
+use actix_web::{
+    get, post,
+    web::{self, Json},
+    HttpRequest, HttpResponse,
+};
+use chrono::NaiveDateTime;
+use diesel::SqliteConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    db::DbPool,
+    models::{
+        bot_model::Bot,
+        job_model::Job,
+        match_model::Match,
+        submission_model::{Submission, SubmissionFilter, NO_ISSUE},
+        user_model::User,
+    },
+};
+use crate::external_related::repo_updater::is_plagiarism_enabled;
+use crate::job_queue::api_submission_job::API_SUBMISSION_JOB;
+use crate::plagiarism::similarity_check::{check_similarity, Verdict};
+
+use super::{
+    auth::authenticate,
+    connection,
+    error::ApiError,
+    matches::ApiMatch,
+    pagination::{Page, PageQuery},
+};
+
+/// A submission without its script
+#[derive(Serialize)]
+pub(crate) struct ApiSubmission {
+    id: String,
+    user: String,
+    username: Option<String>,
+    bot: Option<String>,
+    bot_name: Option<String>,
+    version: i32,
+    mmr: f32,
+    wins: i32,
+    matches_played: i32,
+    /// active, disqualified or retired
+    status: &'static str,
+    issue_url: String,
+    created_at: NaiveDateTime,
+}
+
+impl ApiSubmission {
+    fn new(submission: &Submission, conn: &SqliteConnection) -> Self {
+        ApiSubmission {
+            id: submission.id.clone(),
+            user: submission.user.clone(),
+            username: User::by_id(&submission.user, conn).map(|user| user.username),
+            bot: submission.bot.clone(),
+            bot_name: submission
+                .bot
+                .as_ref()
+                .and_then(|bot_id| Bot::by_id(bot_id, conn))
+                .map(|bot| bot.name),
+            version: submission.version,
+            mmr: submission.mmr,
+            wins: submission.wins,
+            matches_played: submission.matches_played,
+            status: submission.status(),
+            issue_url: submission.issue_url.clone(),
+            created_at: submission.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ApiSubmissionDetail {
+    #[serde(flatten)]
+    submission: ApiSubmission,
+    script: String,
+    /// Newest first
+    matches: Vec<ApiMatch>,
+    /// Only for submissions made through the api
+    placement: Option<ApiPlacement>,
+}
+
+impl ApiSubmissionDetail {
+    fn new(submission: Submission, conn: &SqliteConnection) -> Self {
+        ApiSubmissionDetail {
+            submission: ApiSubmission::new(&submission, conn),
+            matches: Match::by_submission(&submission.id, conn)
+                .into_iter()
+                .map(ApiMatch::from)
+                .collect(),
+            placement: Job::latest_by_payload(API_SUBMISSION_JOB, &submission.id, conn).map(
+                |job| ApiPlacement {
+                    job: job.id,
+                    status: job.status,
+                    attempts: job.attempts,
+                    last_error: job.last_error,
+                },
+            ),
+            script: submission.script,
+        }
+    }
+}
+
+/// The job that plays the placement matches, the results show up
+/// in the matches and the rating of the submission as they are played
+#[derive(Serialize)]
+pub(crate) struct ApiPlacement {
+    job: String,
+    /// pending, running, completed or failed
+    status: String,
+    attempts: i32,
+    last_error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewSubmission {
+    /// The bot this is a version of, a new bot is created for an unknown name
+    name: String,
+    script: String,
+}
+
+/// ?user=<username>&disqualified=false&min_mmr=1000&max_mmr=1500
+#[derive(Deserialize)]
+pub(crate) struct SubmissionsQuery {
+    user: Option<String>,
+    disqualified: Option<bool>,
+    min_mmr: Option<f32>,
+    max_mmr: Option<f32>,
+}
+
+#[get("/submissions")]
+pub(crate) async fn get_submissions_route(
+    query: web::Query<SubmissionsQuery>,
+    page: web::Query<PageQuery>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<Page<ApiSubmission>>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let user = match query.user.as_ref() {
+        Some(username) => Some(
+            User::by_username(username, &conn)
+                .ok_or_else(|| {
+                    ApiError::not_found(format!("Could not find user \"{}\"", username))
+                })?
+                .id,
+        ),
+        None => None,
+    };
+    let filter = SubmissionFilter {
+        user,
+        disqualified: query.disqualified,
+        min_mmr: query.min_mmr,
+        max_mmr: query.max_mmr,
+    };
+    let (offset, limit) = page.bounds()?;
+    let (submissions, total) = Submission::search(&filter, offset, limit, &conn);
+    Ok(Json(
+        page.page(
+            submissions
+                .iter()
+                .map(|submission| ApiSubmission::new(submission, &conn))
+                .collect(),
+            total,
+        )?,
+    ))
+}
+
+#[get("/submissions/{id}")]
+pub(crate) async fn get_submission_route(
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<ApiSubmissionDetail>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let id = path.into_inner();
+    let submission = Submission::by_id(&id, &conn)
+        .ok_or_else(|| ApiError::not_found(format!("Could not find submission \"{}\"", id)))?;
+    Ok(Json(ApiSubmissionDetail::new(submission, &conn)))
+}
+
+/// Registers a bot for the user of the api token and queues its placement
+/// matches, the submission is returned before they are played. The
+/// previous versions of the bot are retired once the job runs.
+#[post("/submissions")]
+pub(crate) async fn post_submission_route(
+    req: HttpRequest,
+    body: web::Json<NewSubmission>,
+    db_pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let conn = connection(&db_pool)?;
+    let user = authenticate(&req, &conn)?;
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("name must not be empty"));
+    }
+    if body.script.trim().is_empty() {
+        return Err(ApiError::bad_request("script must not be empty"));
+    }
+
+    if is_plagiarism_enabled() {
+        let report = check_similarity(&body.script, &user.id, &conn);
+        if report.verdict() == Verdict::Rejected {
+            return Err(ApiError::unprocessable(format!(
+                "This bot is too similar to a bot submitted by another user\n\n{}",
+                report.to_markdown()
+            )));
+        }
+    }
+
+    let bot = Bot::by_name(&user.id, name, &conn)
+        .or_else(|| Bot::create(&user.id, name, &conn))
+        .ok_or_else(|| ApiError::internal("Could not create bot"))?;
+    let submission = match Submission::create(
+        &user.id,
+        &bot.id,
+        &body.script,
+        Some(name),
+        0,
+        "",
+        NO_ISSUE,
+        &conn,
+    ) {
+        Ok(Some(submission)) => submission,
+        Ok(None) => return Err(ApiError::internal("Could not create submission")),
+        Err(_) => {
+            return Err(ApiError::conflict(
+                "This submission has already been submitted before",
+            ))
+        }
+    };
+
+    Job::create(API_SUBMISSION_JOB, &submission.id, &conn)
+        .ok_or_else(|| ApiError::internal("Could not queue placement matches"))?;
+    Ok(HttpResponse::Accepted().json(ApiSubmissionDetail::new(submission, &conn)))
+}
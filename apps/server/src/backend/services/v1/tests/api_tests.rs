@@ -0,0 +1,247 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test as actix_test, web::Data, App};
+    use serde_json::{json, Value};
+
+    use crate::backend::{
+        db::DbPool,
+        fixtures::{rated_submission, setup as setup_database},
+        models::{
+            api_token_model::ApiToken, match_model::Match, submission_model::Submission,
+            user_model::User,
+        },
+        services::v1::routes,
+    };
+    use crate::match_maker::live_matches::{self, MatchKind};
+
+    /// alice and carol each beat bob once, bob is disqualified
+    fn setup() -> DbPool {
+        let pool = setup_database();
+        let conn = pool.get().unwrap();
+        let alice = rated_submission("alice", "a", 1300.0, &conn);
+        let mut bob = rated_submission("bob", "b", 1000.0, &conn);
+        let carol = rated_submission("carol", "c", 1100.0, &conn);
+        Match::create(&alice.id, &bob.id, true, None, &conn).unwrap();
+        Match::create(&carol.id, &bob.id, false, None, &conn).unwrap();
+        bob.disqualified = 1;
+        bob.save(&conn);
+        drop(conn);
+        pool
+    }
+
+    async fn get(pool: &DbPool, uri: &str) -> (StatusCode, Value) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .configure(routes),
+        )
+        .await;
+        let response =
+            actix_test::call_service(&app, actix_test::TestRequest::get().uri(uri).to_request())
+                .await;
+        let status = response.status();
+        (status, actix_test::read_body_json(response).await)
+    }
+
+    #[actix_web::test]
+    async fn pages_submissions_by_rating() {
+        let pool = setup();
+        let (status, body) = get(&pool, "/api/v1/submissions?per_page=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["page"], 1);
+        assert_eq!(body["data"][0]["username"], "alice");
+        assert_eq!(body["data"][1]["username"], "carol");
+        assert!(body["data"][0].get("script").is_none());
+
+        let (_, body) = get(&pool, "/api/v1/submissions?per_page=2&page=2").await;
+        assert_eq!(body["data"][0]["username"], "bob");
+        assert_eq!(body["data"][0]["status"], "disqualified");
+    }
+
+    #[actix_web::test]
+    async fn filters_submissions() {
+        let pool = setup();
+        let (_, body) = get(&pool, "/api/v1/submissions?disqualified=false&min_mmr=1200").await;
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["data"][0]["username"], "alice");
+
+        let (_, body) = get(&pool, "/api/v1/submissions?user=bob").await;
+        assert_eq!(body["total"], 1);
+        let id = body["data"][0]["id"].as_str().unwrap().to_string();
+        let (_, detail) = get(&pool, &format!("/api/v1/submissions/{}", id)).await;
+        assert_eq!(detail["script"], "b");
+        assert_eq!(detail["matches"].as_array().unwrap().len(), 2);
+
+        let (_, body) = get(&pool, &format!("/api/v1/matches?submission={}", id)).await;
+        assert_eq!(body["total"], 2);
+        let (_, body) = get(&pool, "/api/v1/matches?before=2000-01-01").await;
+        assert_eq!(body["total"], 0);
+    }
+
+    #[actix_web::test]
+    async fn lists_users_and_leaderboard() {
+        let pool = setup();
+        let (_, body) = get(&pool, "/api/v1/users").await;
+        let usernames: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["username"].as_str().unwrap())
+            .collect();
+        assert_eq!(usernames, vec!["alice", "bob", "carol"]);
+
+        let (status, body) = get(&pool, "/api/v1/users/alice").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["wins"], 1);
+
+        let (_, body) = get(&pool, "/api/v1/leaderboard").await;
+        assert_eq!(body["data"][0]["rank"], 1);
+        assert_eq!(body["data"][0]["username"], "alice");
+    }
+
+    #[actix_web::test]
+    async fn answers_errors_as_json() {
+        let pool = setup();
+        for (uri, status, code) in [
+            ("/api/v1/users/nobody", StatusCode::NOT_FOUND, "not_found"),
+            (
+                "/api/v1/submissions?user=nobody",
+                StatusCode::NOT_FOUND,
+                "not_found",
+            ),
+            (
+                "/api/v1/matches/missing",
+                StatusCode::NOT_FOUND,
+                "not_found",
+            ),
+            ("/api/v1/unknown", StatusCode::NOT_FOUND, "not_found"),
+            ("/api/v1/live/missing", StatusCode::NOT_FOUND, "not_found"),
+            (
+                "/api/v1/submissions?per_page=500",
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+            ),
+            (
+                "/api/v1/submissions?page=9223372036854775807",
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+            ),
+            (
+                "/api/v1/submissions?min_mmr=high",
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+            ),
+            (
+                "/api/v1/matches?after=yesterday",
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+            ),
+        ] {
+            let (actual, body) = get(&pool, uri).await;
+            assert_eq!(actual, status, "{}", uri);
+            assert_eq!(body["error"]["code"], code, "{}", uri);
+            assert!(body["error"]["message"].is_string());
+        }
+    }
+
+    #[actix_web::test]
+    async fn submitting_requires_a_token() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let alice = User::by_username("alice", &conn).unwrap();
+        let (record, token) = ApiToken::create(&alice.id, "ci", &conn).unwrap();
+        drop(conn);
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .configure(routes),
+        )
+        .await;
+
+        let submit = |authorization: Option<String>, body: Value| {
+            let mut request = actix_test::TestRequest::post()
+                .uri("/api/v1/submissions")
+                .set_json(body);
+            if let Some(authorization) = authorization {
+                request = request.insert_header(("Authorization", authorization));
+            }
+            request.to_request()
+        };
+        let bot = json!({ "name": "alice", "script": "function onTurn() return \"0\" end" });
+        for (authorization, body, status) in [
+            (None, bot.clone(), StatusCode::UNAUTHORIZED),
+            (
+                Some("Bearer hampfh_wrong".to_string()),
+                bot.clone(),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                Some(format!("Bearer {}", token)),
+                json!({ "name": " ", "script": "return" }),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                Some(format!("Bearer {}", token)),
+                json!({ "script": "return" }),
+                StatusCode::BAD_REQUEST,
+            ),
+        ] {
+            let response = actix_test::call_service(&app, submit(authorization, body)).await;
+            assert_eq!(response.status(), status);
+            let body: Value = actix_test::read_body_json(response).await;
+            assert!(body["error"]["code"].is_string());
+        }
+
+        let response =
+            actix_test::call_service(&app, submit(Some(format!("Bearer {}", token)), bot)).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body: Value = actix_test::read_body_json(response).await;
+        // The name continues alice's bot
+        assert_eq!(body["version"], 2);
+        assert_eq!(body["placement"]["status"], "pending");
+        let conn = pool.get().unwrap();
+        assert!(ApiToken::by_id(&record.id, &conn)
+            .unwrap()
+            .last_used_at
+            .is_some());
+    }
+
+    #[actix_web::test]
+    async fn lists_and_streams_live_matches() {
+        let pool = setup();
+        let player_one = Submission::unsaved("alice", "", None, "", 1);
+        let player_two = Submission::unsaved("bob", "", None, "", 2);
+        let _live = live_matches::start(MatchKind::Scheduled, &player_one, &player_two);
+
+        let (status, body) = get(&pool, "/api/v1/live").await;
+        assert_eq!(status, StatusCode::OK);
+        let live = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|current| current["player_one"] == player_one.id.as_str())
+            .unwrap();
+        assert_eq!(live["kind"], "scheduled");
+        assert_eq!(live["turn"], 0);
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .configure(routes),
+        )
+        .await;
+        let response = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::get()
+                .uri(&format!("/api/v1/live/{}", live["id"].as_str().unwrap()))
+                .insert_header(("Upgrade", "websocket"))
+                .insert_header(("Connection", "Upgrade"))
+                .insert_header(("Sec-WebSocket-Version", "13"))
+                .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+    }
+}
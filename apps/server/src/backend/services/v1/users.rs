@@ -0,0 +1,57 @@
+use actix_web::{
+    get,
+    web::{self, Json},
+};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::analytics::user_stats::{load_user_stats, UserStats};
+use crate::backend::{db::DbPool, models::user_model::User};
+
+use super::{
+    connection,
+    error::ApiError,
+    pagination::{Page, PageQuery},
+};
+
+#[derive(Serialize)]
+pub(crate) struct ApiUser {
+    id: String,
+    username: String,
+    created_at: NaiveDateTime,
+}
+
+/// Every user by username
+#[get("/users")]
+pub(crate) async fn get_users_route(
+    page: web::Query<PageQuery>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<Page<ApiUser>>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let mut users = User::list(&conn);
+    users.sort_by_key(|user| user.username.to_lowercase());
+    Ok(Json(
+        page.slice(
+            users
+                .into_iter()
+                .map(|user| ApiUser {
+                    id: user.id,
+                    username: user.username,
+                    created_at: user.created_at,
+                })
+                .collect(),
+        )?,
+    ))
+}
+
+#[get("/users/{username}")]
+pub(crate) async fn get_user_route(
+    path: web::Path<String>,
+    db_pool: web::Data<DbPool>,
+) -> Result<Json<UserStats>, ApiError> {
+    let conn = connection(&db_pool)?;
+    let username = path.into_inner();
+    let user = User::by_username(&username, &conn)
+        .ok_or_else(|| ApiError::not_found(format!("Could not find user \"{}\"", username)))?;
+    Ok(Json(load_user_stats(&user, &conn)))
+}
@@ -0,0 +1,147 @@
+use std::io::{BufRead, Write};
+
+use crate::game::{
+    entry_point::initialize_traced_game_session,
+    game::{GameResult, TurnTrace},
+    graphics::render_position,
+};
+
+use super::fail;
+
+const HELP: &str = "Commands: enter or next steps forward, back steps back, a number jumps to
+that turn, context prints the table the bot received, help, quit.
+Boards are drawn as the match is rendered, the context is what the bot saw.";
+
+/// Plays two local scripts and lets the user step through the turns,
+/// showing what the bots printed with log() every turn
+pub(super) fn debug_match(script1_path: &str, script2_path: &str) {
+    let load = |path: &str| {
+        std::fs::read_to_string(path)
+            .unwrap_or_else(|error| fail(&format!("Could not load {}: {}", path, error)))
+    };
+    let (result, _, traces) =
+        initialize_traced_game_session(&load(script1_path), &load(script2_path));
+
+    println!("{}\n{}", describe_result(&result), HELP);
+    if traces.is_empty() {
+        return;
+    }
+
+    let mut index = 0;
+    println!("\n{}", format_turn(&traces, index, &result));
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush().unwrap();
+        let input = match lines.next() {
+            Some(Ok(input)) => input.trim().to_lowercase(),
+            _ => return,
+        };
+        match input.as_str() {
+            "quit" | "q" | "exit" => return,
+            "help" | "h" => println!("{}", HELP),
+            "context" | "c" => println!("{}", traces[index].context()),
+            command => match step(command, index, traces.len()) {
+                Ok(next) => {
+                    index = next;
+                    println!("\n{}", format_turn(&traces, index, &result));
+                }
+                Err(error) => println!("{}", error),
+            },
+        }
+    }
+}
+
+/// The index of the turn to show after the command
+fn step(command: &str, index: usize, turns: usize) -> Result<usize, String> {
+    match command {
+        "" | "next" | "n" if index + 1 < turns => Ok(index + 1),
+        "" | "next" | "n" => Err("This is the last turn".to_string()),
+        "back" | "b" if index > 0 => Ok(index - 1),
+        "back" | "b" => Err("This is the first turn".to_string()),
+        turn => match turn.parse::<usize>() {
+            Ok(turn) if turn >= 1 && turn <= turns => Ok(turn - 1),
+            Ok(_) => Err(format!("There are turns 1 to {}", turns)),
+            Err(_) => Err(format!("Unknown command: {}, type help", turn)),
+        },
+    }
+}
+
+fn format_turn(traces: &[TurnTrace], index: usize, result: &GameResult) -> String {
+    let trace = &traces[index];
+    let mut output = format!(
+        "Turn {}/{}, {} to move\n{}",
+        index + 1,
+        traces.len(),
+        if trace.player_one_turn {
+            "script 1 (O)"
+        } else {
+            "script 2 (X)"
+        },
+        render_position(&trace.player_one, &trace.player_two, &trace.walls)
+    );
+    if trace.log.is_empty() {
+        output.push_str("Nothing logged\n");
+    } else {
+        output.push_str("Log:\n");
+        for line in trace.log.iter() {
+            output.push_str(&format!("  {}\n", line));
+        }
+    }
+    match (&trace.played, result) {
+        (Some(played), _) => output.push_str(&format!("Played {:?}", played)),
+        (None, GameResult::Error(error)) => output.push_str(&format!("Failed: {}", error.reason())),
+        (None, _) => output.push_str("No move"),
+    }
+    output
+}
+
+pub(super) fn describe_result(result: &GameResult) -> String {
+    match result {
+        GameResult::PlayerOneWon => "Script 1 (O) won".to_string(),
+        GameResult::PlayerTwoWon => "Script 2 (X) won".to_string(),
+        GameResult::Error(error) => format!("The match ended with an error: {}", error.reason()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_turn, step};
+    use crate::game::{
+        game::{ErrorType, GameResult, Move, TurnTrace},
+        methods,
+    };
+
+    #[test]
+    fn steps_within_the_match() {
+        assert_eq!(step("", 0, 3), Ok(1));
+        assert_eq!(step("back", 1, 3), Ok(0));
+        assert_eq!(step("3", 0, 3), Ok(2));
+        assert!(step("next", 2, 3).is_err());
+        assert!(step("b", 0, 3).is_err());
+        assert!(step("4", 0, 3).is_err());
+        assert!(step("jump", 0, 3).is_err());
+    }
+
+    #[test]
+    fn shows_the_log_and_the_outcome_of_a_turn() {
+        let game = methods::new(String::new());
+        let mut played = TurnTrace::new(&game);
+        played.played = Some(Move::Up);
+        played.log = vec!["searching".to_string()];
+        let failed = TurnTrace::new(&game);
+        let traces = vec![played, failed];
+        let result = GameResult::Error(ErrorType::RuntimeError {
+            reason: "boom".to_string(),
+            fault: None,
+        });
+
+        let first = format_turn(&traces, 0, &result);
+        assert!(first.starts_with("Turn 1/2, script 1 (O) to move"));
+        assert!(first.contains("  searching\n"));
+        assert!(first.ends_with("Played Up"));
+        let second = format_turn(&traces, 1, &result);
+        assert!(second.contains("Nothing logged"));
+        assert!(second.ends_with("Failed: boom"));
+    }
+}
@@ -0,0 +1,75 @@
+use clap::Subcommand;
+
+use crate::{
+    backend::models::disqualification_model::{Disqualification, DisqualificationStatus},
+    match_maker::disqualifications::reinstate,
+};
+
+use super::{connect, fail};
+
+#[derive(Subcommand)]
+pub(super) enum DisqualificationCommand {
+    /// List disqualifications, newest first
+    List {
+        /// Only show records with this status: active, confirmed or reinstated
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Lift a disqualification
+    Reinstate { id: String },
+}
+
+pub(super) fn run(command: DisqualificationCommand) {
+    match command {
+        DisqualificationCommand::List { status } => list(status.as_deref()),
+        DisqualificationCommand::Reinstate { id } => reinstate_disqualification(&id),
+    }
+}
+
+fn list(status: Option<&str>) {
+    let conn = connect();
+    let records = match status {
+        Some(status) => match DisqualificationStatus::parse(status) {
+            Some(status) => Disqualification::by_status(status, &conn),
+            None => fail(&format!(
+                "Unknown status {}, use active, confirmed or reinstated",
+                status
+            )),
+        },
+        None => Disqualification::list(&conn),
+    };
+
+    for record in records {
+        println!("{}", format_record(&record));
+    }
+}
+
+pub(super) fn format_record(record: &Disqualification) -> String {
+    format!(
+        "{} | {} | submission {} | opponent {} | match {} | {} | {}",
+        record.id,
+        record.created_at.format("%Y-%m-%d %H:%M"),
+        record.submission,
+        record.opponent.as_deref().unwrap_or("-"),
+        record.match_id.as_deref().unwrap_or("-"),
+        record.status,
+        record.reason
+    )
+}
+
+fn reinstate_disqualification(id: &str) {
+    let conn = connect();
+    let mut record = Disqualification::by_id(id, &conn)
+        .unwrap_or_else(|| fail(&format!("Could not find disqualification {}", id)));
+    match reinstate(&mut record, &conn) {
+        Ok(submission) if submission.disqualified >= 1 => println!(
+            "Reinstated {}, submission {} has other disqualifications and stays disqualified",
+            record.id, submission.id
+        ),
+        Ok(submission) => println!(
+            "Reinstated {}, submission {} takes part in matches again",
+            record.id, submission.id
+        ),
+        Err(error) => fail(&error),
+    }
+}
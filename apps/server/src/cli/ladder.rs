@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use crate::{
+    game::{
+        entry_point::initialize_game_session,
+        game::{ErrorType, GameResult},
+        player::PlayerType,
+    },
+    match_maker::{
+        constants::{LADDER_MMR_AMPLIFIER, MMR_START_RATING},
+        mmr::{calculate_mmr, MMR},
+    },
+};
+
+use super::fail;
+
+/// A bot of a local ladder, rated the same way as submissions are
+struct LadderBot {
+    name: String,
+    script: String,
+    rating: f32,
+    matches_played: i32,
+    wins: u32,
+    losses: u32,
+    draws: u32,
+}
+
+/// Outcome of a single game from the perspective of player one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    PlayerOneWon,
+    PlayerTwoWon,
+    Draw,
+}
+impl Outcome {
+    /// The bot at fault loses games that end in an error, like in the
+    /// ladder where it would be disqualified. Deadlocks have no winner.
+    fn from_result(result: &GameResult) -> Self {
+        let fault = match result {
+            GameResult::PlayerOneWon => return Outcome::PlayerOneWon,
+            GameResult::PlayerTwoWon => return Outcome::PlayerTwoWon,
+            GameResult::Error(ErrorType::GameError { fault, .. })
+            | GameResult::Error(ErrorType::RuntimeError { fault, .. })
+            | GameResult::Error(ErrorType::TurnTimeout { fault }) => fault,
+            GameResult::Error(ErrorType::GameDeadlock) => return Outcome::Draw,
+        };
+        match fault {
+            Some(PlayerType::Flipped) => Outcome::PlayerTwoWon,
+            Some(PlayerType::Regular) => Outcome::PlayerOneWon,
+            None => Outcome::Draw,
+        }
+    }
+}
+
+struct Ladder {
+    bots: Vec<LadderBot>,
+    /// wins[row][column] is the number of games the row bot won against the column bot
+    wins: Vec<Vec<u32>>,
+    games: Vec<Vec<u32>>,
+}
+impl Ladder {
+    fn new(scripts: Vec<(String, String)>) -> Self {
+        let count = scripts.len();
+        Ladder {
+            bots: scripts
+                .into_iter()
+                .map(|(name, script)| LadderBot {
+                    name,
+                    script,
+                    rating: MMR_START_RATING,
+                    matches_played: 0,
+                    wins: 0,
+                    losses: 0,
+                    draws: 0,
+                })
+                .collect(),
+            wins: vec![vec![0; count]; count],
+            games: vec![vec![0; count]; count],
+        }
+    }
+
+    /// Every pairing plays the given number of games, the
+    /// bots swap sides after every game.
+    fn play(&mut self, games_per_pairing: u32) {
+        for first in 0..self.bots.len() {
+            for second in (first + 1)..self.bots.len() {
+                for game in 0..games_per_pairing {
+                    let (p1, p2) = if game % 2 == 0 {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    let (result, _) =
+                        initialize_game_session(&self.bots[p1].script, &self.bots[p2].script);
+                    self.record(p1, p2, Outcome::from_result(&result));
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, p1: usize, p2: usize, outcome: Outcome) {
+        self.games[p1][p2] += 1;
+        self.games[p2][p1] += 1;
+        let p1_won = match outcome {
+            Outcome::PlayerOneWon => true,
+            Outcome::PlayerTwoWon => false,
+            Outcome::Draw => {
+                self.bots[p1].draws += 1;
+                self.bots[p2].draws += 1;
+                return;
+            }
+        };
+        let (winner, loser) = if p1_won { (p1, p2) } else { (p2, p1) };
+        self.wins[winner][loser] += 1;
+        self.bots[winner].wins += 1;
+        self.bots[loser].losses += 1;
+
+        let (p1_rating, p2_rating) = calculate_mmr(
+            MMR {
+                rating: self.bots[p1].rating,
+                matches_played: self.bots[p1].matches_played,
+            },
+            MMR {
+                rating: self.bots[p2].rating,
+                matches_played: self.bots[p2].matches_played,
+            },
+            p1_won,
+            LADDER_MMR_AMPLIFIER,
+        );
+        self.bots[p1].rating = p1_rating;
+        self.bots[p2].rating = p2_rating;
+        self.bots[p1].matches_played += 1;
+        self.bots[p2].matches_played += 1;
+    }
+
+    /// Indices of the bots, best rated first
+    fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.bots.len()).collect();
+        ranking.sort_by(|a, b| self.bots[*b].rating.total_cmp(&self.bots[*a].rating));
+        ranking
+    }
+
+    fn standings_table(&self) -> String {
+        let mut table = String::from(
+            "| # | Bot | MMR | Wins | Losses | Draws | Win rate |\n|---|---|---|---|---|---|---|\n",
+        );
+        for (place, index) in self.ranking().into_iter().enumerate() {
+            let bot = &self.bots[index];
+            table.push_str(&format!(
+                "| {} | {} | {:.0} | {} | {} | {} | {} |\n",
+                place + 1,
+                bot.name,
+                bot.rating,
+                bot.wins,
+                bot.losses,
+                bot.draws,
+                win_rate(bot.wins, bot.wins + bot.losses + bot.draws)
+            ));
+        }
+        table
+    }
+
+    /// Win rate of the bot of each row against the bot of each column
+    fn win_rate_matrix(&self) -> String {
+        let ranking = self.ranking();
+        let mut table = String::from("| |");
+        for index in ranking.iter() {
+            table.push_str(&format!(" {} |", self.bots[*index].name));
+        }
+        table.push_str(&format!("\n|---|{}\n", "---|".repeat(ranking.len())));
+        for row in ranking.iter() {
+            table.push_str(&format!("| {} |", self.bots[*row].name));
+            for column in ranking.iter() {
+                if row == column {
+                    table.push_str(" - |");
+                } else {
+                    table.push_str(&format!(
+                        " {} |",
+                        win_rate(self.wins[*row][*column], self.games[*row][*column])
+                    ));
+                }
+            }
+            table.push('\n');
+        }
+        table
+    }
+}
+
+fn win_rate(wins: u32, games: u32) -> String {
+    if games == 0 {
+        return "-".to_string();
+    }
+    format!("{:.0}%", wins as f32 / games as f32 * 100.0)
+}
+
+/// Every lua script in the directory except the standard library
+fn load_scripts(dir: &Path) -> Result<Vec<(String, String)>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|error| format!("Could not read {}: {}", dir.display(), error))?;
+    let mut scripts = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("lua")
+            || path.file_name().and_then(|name| name.to_str()) == Some("std.lua")
+        {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let script = std::fs::read_to_string(&path)
+            .map_err(|error| format!("Could not load {}: {}", path.display(), error))?;
+        scripts.push((name, script));
+    }
+    scripts.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(scripts)
+}
+
+pub(super) fn run_ladder(dir: &str, games_per_pairing: u32) {
+    let scripts = load_scripts(Path::new(dir)).unwrap_or_else(|error| fail(&error));
+    if scripts.len() < 2 {
+        fail(&format!("{} needs to contain at least two bots", dir));
+    }
+
+    let mut ladder = Ladder::new(scripts);
+    ladder.play(games_per_pairing);
+    println!("{}\n{}", ladder.standings_table(), ladder.win_rate_matrix());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ladder, Outcome};
+    use crate::game::{
+        game::{ErrorType, GameResult},
+        player::PlayerType,
+    };
+
+    fn ladder() -> Ladder {
+        Ladder::new(vec![
+            ("alpha".to_string(), "".to_string()),
+            ("beta".to_string(), "".to_string()),
+            ("gamma".to_string(), "".to_string()),
+        ])
+    }
+
+    #[test]
+    fn the_bot_at_fault_loses() {
+        let timeout = GameResult::Error(ErrorType::TurnTimeout {
+            fault: Some(PlayerType::Flipped),
+        });
+        assert_eq!(Outcome::from_result(&timeout), Outcome::PlayerTwoWon);
+        let deadlock = GameResult::Error(ErrorType::GameDeadlock);
+        assert_eq!(Outcome::from_result(&deadlock), Outcome::Draw);
+    }
+
+    #[test]
+    fn rates_bots_like_the_ladder() {
+        let mut ladder = ladder();
+        ladder.record(0, 1, Outcome::PlayerOneWon);
+        ladder.record(1, 0, Outcome::PlayerTwoWon);
+        ladder.record(2, 1, Outcome::Draw);
+
+        assert_eq!(ladder.ranking(), vec![0, 2, 1]);
+        assert!(ladder.bots[0].rating > ladder.bots[2].rating);
+        assert_eq!(ladder.bots[2].rating, 1500.0);
+        assert_eq!(ladder.bots[1].matches_played, 2);
+        assert_eq!(ladder.bots[1].draws, 1);
+    }
+
+    #[test]
+    fn prints_win_rates_per_pairing() {
+        let mut ladder = ladder();
+        ladder.record(0, 1, Outcome::PlayerOneWon);
+        ladder.record(1, 0, Outcome::PlayerOneWon);
+        ladder.record(0, 2, Outcome::PlayerOneWon);
+
+        let matrix = ladder.win_rate_matrix();
+        let rows: Vec<&str> = matrix.lines().collect();
+        assert_eq!(rows[0], "| | alpha | beta | gamma |");
+        assert_eq!(rows[2], "| alpha | - | 50% | 100% |");
+        assert_eq!(rows[4], "| gamma | 0% | - | - |");
+        assert!(ladder.standings_table().contains("| 1 | alpha | 15"));
+    }
+}
@@ -1,60 +1,43 @@
-use std::{fs, process};
+use std::fs;
 
 use crate::{
-    backend::{
-        self,
-        models::{
-            match_model::Match, submission_model::Submission, turn_model::Turn, user_model::User,
-        },
+    backend::models::{
+        bot_model::Bot, match_model::Match, submission_model::Submission, turn_model::Turn,
+        user_model::User,
     },
+    external_related::html_renderer::render_match_html,
     external_related::readme_factory::{
         build_match_files_wrapper, build_match_log_wrapper, build_submission_log_wrapper,
         clear_match_dir, create_and_encode_file, generate_gif_from_turn, generate_readme,
         get_match_from_tiles_compact, write_file,
     },
+    external_related::site_generator,
+    external_related::svg_renderer::{render_match_svg, SvgLayout},
     game::{
         entry_point::initialize_game_session,
         game::{ErrorType, GameResult},
         player::PlayerType,
     },
-    match_maker::scheduler::run_scheduled_matchmaking,
 };
 
-pub fn cli(args: Vec<String>) {
-    match args[1].as_str() {
-        "match" => {
-            if (args.len() - 1) < 3 {
-                println!("match command requries 2 arguments: script1_path script2_path");
-                process::exit(1);
-            }
-            run_local_match(args[2].as_str(), args[3].as_str())
-        }
-        "generate" => {
-            clear_match_dir();
-            generate_main();
-            build_match_files_wrapper();
-            build_match_log_wrapper();
-            build_submission_log_wrapper();
-        }
-        "generate-main" => generate_main(),
-        "generate-matches" => build_match_files_wrapper(),
-        "generate-logs" => {
-            build_match_log_wrapper();
-            build_submission_log_wrapper();
-        }
-        "clear" => clear_match_dir(),
-        "schedule_matchmaking" => scheduled_matchmaking(),
-        _ => {}
-    }
+use super::debugger::describe_result;
+use super::{connect, fail};
+
+pub(super) fn generate() {
+    clear_match_dir();
+    generate_main();
+    generate_matches();
+    generate_logs();
 }
 
-fn generate_main() {
-    let conn = backend::db::establish_connection().get().unwrap();
+pub(super) fn generate_main() {
+    let conn = connect();
     write_file(
         "../../README.md",
         generate_readme(
             User::list(&conn),
             Submission::list(&conn),
+            Bot::list(&conn),
             Match::list(&conn),
             Turn::list(&conn),
         ),
@@ -62,21 +45,28 @@ fn generate_main() {
     .unwrap();
 }
 
-fn scheduled_matchmaking() {
-    let db_connection = backend::db::establish_connection();
-    let conn = match db_connection.get() {
-        Ok(conn) => conn,
-        Err(error) => {
-            println!("Could not establish connection to database: {}", error);
-            process::exit(1);
-        }
-    };
+pub(super) fn generate_matches() {
+    build_match_files_wrapper();
+}
 
-    run_scheduled_matchmaking(&conn);
-    process::exit(0);
+pub(super) fn generate_logs() {
+    build_match_log_wrapper();
+    build_submission_log_wrapper();
 }
 
-fn run_local_match(script1_path: &str, script2_path: &str) {
+pub(super) fn generate_site(out: &str) {
+    let conn = connect();
+    match site_generator::generate_site(&conn, out) {
+        Ok(count) => println!("Wrote {} files to {}", count, out),
+        Err(error) => fail(&error),
+    }
+}
+
+pub(super) fn clear() {
+    clear_match_dir();
+}
+
+pub(super) fn run_local_match(script1_path: &str, script2_path: &str) {
     let script1 = std::fs::read_to_string(script1_path).expect("Could not load script 1");
     let script2 = std::fs::read_to_string(script2_path).expect("Could not load script 2");
 
@@ -113,9 +103,24 @@ fn run_local_match(script1_path: &str, script2_path: &str) {
             }
         ))
     }
+    file.push_str("<div align=\"center\"><img src=\"match.temp.svg\" /><p><a href=\"match.temp.html\">Interactive replay</a></p></div>\n\n");
     file.push_str(&get_match_from_tiles_compact(turns.clone()));
 
     fs::write("match.temp.md", file).expect("Could not write match file");
+    fs::write(
+        "match.temp.svg",
+        render_match_svg(&turns, SvgLayout::Animated),
+    )
+    .expect("Could not write match svg");
+    fs::write(
+        "match.temp.html",
+        render_match_html(
+            &format!("{} (O) vs {} (X)", script1_path, script2_path),
+            &describe_result(&results),
+            &turns,
+        ),
+    )
+    .expect("Could not write match replay");
     let (color_palette, images) = generate_gif_from_turn(turns, Some(results), 50);
     create_and_encode_file("match.temp.gif".to_string(), images, &color_palette, 50);
 }
@@ -0,0 +1,141 @@
+use clap::Subcommand;
+use diesel::SqliteConnection;
+
+use crate::{
+    backend::models::{disqualification_model::Disqualification, match_model::Match},
+    external_related::{
+        readme_factory::{build_match_files, render_match_gif},
+        svg_renderer::{render_match_svg, SvgLayout},
+    },
+    game::board::{board_from_string, Tile},
+};
+
+use super::{connect, fail};
+
+#[derive(Subcommand)]
+pub(super) enum MatchCommand {
+    /// List matches, newest first
+    List {
+        /// Only show matches the submission played in
+        #[arg(long)]
+        submission: Option<String>,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Show a match with its players and turns
+    Show {
+        id: String,
+        /// Print what the bots logged every turn
+        #[arg(long)]
+        logs: bool,
+    },
+    /// Delete a match and its turns, the ratings of the players are kept
+    Delete { id: String },
+    /// Render the match file, svg and gif of a match again
+    Render { id: String },
+    /// Print the svg of a match
+    Svg {
+        id: String,
+        /// Draw every turn next to each other in rows of this many turns
+        #[arg(long)]
+        columns: Option<usize>,
+    },
+}
+
+pub(super) fn run(command: MatchCommand) {
+    match command {
+        MatchCommand::List { submission, limit } => list(submission.as_deref(), limit),
+        MatchCommand::Show { id, logs } => show(&id, logs),
+        MatchCommand::Delete { id } => {
+            let conn = connect();
+            let target = find(&id, &conn);
+            Disqualification::detach_match(&target.id, &conn);
+            target.delete(&conn);
+            println!("Deleted {}", target.id);
+        }
+        MatchCommand::Render { id } => {
+            let conn = connect();
+            let target = find(&id, &conn);
+            let turns = Match::get_turns(&target.id, &conn).unwrap_or_default();
+            let gif_path = format!("../../data/gifs/{}.gif", target.id);
+            render_match_gif(target.clone(), &turns, gif_path.clone(), 50);
+            build_match_files(&conn, vec![target.clone()]);
+            println!(
+                "Rendered ../../data/matches/{0}.md, ../../data/svgs/{0}.svg and {1}",
+                target.id, gif_path
+            );
+        }
+        MatchCommand::Svg { id, columns } => {
+            let conn = connect();
+            let target = find(&id, &conn);
+            let boards: Vec<Vec<Tile>> = Match::get_turns(&target.id, &conn)
+                .unwrap_or_default()
+                .iter()
+                .map(|turn| board_from_string(turn.board.clone()))
+                .collect();
+            let layout = match columns {
+                Some(columns) => SvgLayout::Strip { columns },
+                None => SvgLayout::Animated,
+            };
+            print!("{}", render_match_svg(&boards, layout));
+        }
+    }
+}
+
+fn find(id: &str, conn: &SqliteConnection) -> Match {
+    Match::by_id(id, conn).unwrap_or_else(|| fail(&format!("Could not find match {}", id)))
+}
+
+pub(super) fn format_match(target: &Match) -> String {
+    format!(
+        "{} | {} | winner {} | loser {}{}",
+        target.id,
+        target.created_at.format("%Y-%m-%d %H:%M"),
+        target.winner,
+        target.loser,
+        match target.match_error.as_ref() {
+            Some(error) => format!(" | {}", error),
+            None => "".to_string(),
+        }
+    )
+}
+
+fn list(submission: Option<&str>, limit: usize) {
+    let conn = connect();
+    let mut matches = match submission {
+        Some(submission) => Match::by_submission(submission, &conn),
+        None => Match::list(&conn),
+    };
+    matches.sort_by_key(|current| std::cmp::Reverse(current.created_at));
+
+    for current in matches.iter().take(limit) {
+        println!("{}", format_match(current));
+    }
+}
+
+fn show(id: &str, logs: bool) {
+    let conn = connect();
+    let target = find(id, &conn);
+    println!("{}", format_match(&target));
+    if let Some(((winner, _), (loser, _))) = Match::get_players(&target.id, &conn) {
+        let (p1, p2) = if target.p1_is_winner >= 1 {
+            (winner, loser)
+        } else {
+            (loser, winner)
+        };
+        println!(
+            "Player 1 (🟩): {}\nPlayer 2 (🟥): {}",
+            p1.username, p2.username
+        );
+    }
+    let mut turns = Match::get_turns(&target.id, &conn).unwrap_or_default();
+    println!("{} turns", turns.len());
+    if logs {
+        turns.sort_by_key(|turn| turn.turn);
+        for turn in turns.iter() {
+            if let Some(log) = turn.log.as_ref() {
+                println!("\nTurn {}:\n{}", turn.turn, log);
+            }
+        }
+    }
+}
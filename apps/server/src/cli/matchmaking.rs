@@ -0,0 +1,59 @@
+use crate::{
+    backend::models::{job_model::Job, submission_model::Submission},
+    job_queue::matchmaking_job::SCHEDULED_MATCHMAKING_JOB,
+    match_maker::{
+        live_matches::MatchKind, match_executor::execute_match_queue,
+        scheduler::preview_scheduled_matchmaking,
+    },
+};
+
+use super::{connect, fail};
+
+pub(super) fn scheduled_matchmaking(dry_run: bool) {
+    let conn = connect();
+    // The server plays the round, its spectators can follow it live
+    if !dry_run {
+        match Job::create(SCHEDULED_MATCHMAKING_JOB, "", &conn) {
+            Some(job) => println!("Queued scheduled matchmaking as job {}", job.id),
+            None => fail("Could not queue scheduled matchmaking"),
+        }
+        return;
+    }
+
+    let previews = preview_scheduled_matchmaking(&conn);
+    if previews.is_empty() {
+        println!("Not enough submissions to execute match-queue");
+        return;
+    }
+    for (p1, preview) in previews {
+        println!(
+            "{} ({:.0}) vs {} ({:.0}) | {}{}",
+            p1.id,
+            p1.mmr,
+            preview.opponent.id,
+            preview.opponent.mmr,
+            match preview.won {
+                Some(true) => format!("{} wins", p1.id),
+                Some(false) => format!("{} wins", preview.opponent.id),
+                None => "no winner".to_string(),
+            },
+            match preview.error_msg {
+                Some(error) => format!(" | {}", error),
+                None => "".to_string(),
+            }
+        );
+    }
+    println!("Dry run, nothing was saved or posted");
+}
+
+pub(super) fn ladder_match(submission1_id: &str, submission2_id: &str) {
+    let conn = connect();
+    let p1 = Submission::by_id(submission1_id, &conn)
+        .unwrap_or_else(|| fail(&format!("Could not find submission {}", submission1_id)));
+    let p2 = Submission::by_id(submission2_id, &conn)
+        .unwrap_or_else(|| fail(&format!("Could not find submission {}", submission2_id)));
+
+    for (report1, report2) in execute_match_queue(&conn, vec![(p1, p2)], MatchKind::Manual) {
+        println!("{}\n\n{}", report1.report, report2.report);
+    }
+}
@@ -0,0 +1,175 @@
+use std::process;
+
+use clap::{Parser, Subcommand};
+use diesel::{
+    r2d2::{ConnectionManager, PooledConnection},
+    SqliteConnection,
+};
+
+use crate::backend;
+use crate::external_related::site_generator::SITE_DIR;
+
+mod debugger;
+mod disqualifications;
+mod ladder;
+mod local;
+mod matches;
+mod matchmaking;
+mod play;
+mod stats;
+mod submissions;
+mod test_bot;
+mod tokens;
+mod users;
+
+/// Admin tools for the Hampfh server, without a command the server is started
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play two local scripts against each other, writes match.temp.md, match.temp.gif and match.temp.svg
+    Match {
+        script1_path: String,
+        script2_path: String,
+        /// Step through the turns instead, with what the bots logged with log()
+        #[arg(long)]
+        debug: bool,
+    },
+    /// Play every bot in a directory against each other and print the standings,
+    /// nothing is saved
+    Ladder {
+        /// Directory with the lua scripts of the bots
+        dir: String,
+        /// Games per pairing, the bots swap sides after every game
+        #[arg(long, default_value_t = 2)]
+        games: u32,
+    },
+    /// Play against a bot in the terminal
+    Play {
+        /// Path to a lua script or the id of a stored submission
+        opponent: String,
+        /// Play as player two, who moves second
+        #[arg(long)]
+        second: bool,
+    },
+    /// Run a bot against the positions of a scenario file and check its moves
+    TestBot {
+        script_path: String,
+        /// Json file with a list of scenarios, see ../scripts/scenarios
+        scenarios_path: String,
+    },
+    /// Regenerate the README, the match files and the logs
+    Generate,
+    /// Regenerate the README
+    GenerateMain,
+    /// Regenerate the match files
+    GenerateMatches,
+    /// Regenerate the match and submission logs
+    GenerateLogs,
+    /// Build the static site with the leaderboard, profiles and replays,
+    /// the server serves it under /site
+    GenerateSite {
+        /// Directory to write the site to, it is replaced
+        #[arg(long, default_value = SITE_DIR)]
+        out: String,
+    },
+    /// Remove every generated file in the data dir
+    Clear,
+    /// Report first move advantage, game lengths, wall usage, errors and
+    /// the rating spread over every stored match, as markdown
+    Stats {
+        /// Write the report to a file instead of printing it
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Queue a round of matchmaking between the stored submissions,
+    /// the job worker of the server plays it
+    #[command(alias = "schedule_matchmaking")]
+    ScheduleMatchmaking {
+        /// Play the matches right here without saving them or posting to GitHub
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Play a ladder match between two stored submissions, the result is saved
+    LadderMatch {
+        submission1_id: String,
+        submission2_id: String,
+    },
+    /// Manage submissions
+    #[command(subcommand)]
+    Submissions(submissions::SubmissionCommand),
+    /// Manage users
+    #[command(subcommand)]
+    Users(users::UserCommand),
+    /// Manage matches
+    #[command(subcommand)]
+    Matches(matches::MatchCommand),
+    /// Review disqualifications
+    #[command(subcommand)]
+    Disqualifications(disqualifications::DisqualificationCommand),
+    /// Manage the api tokens users submit bots with through POST /api/v1/submissions
+    #[command(subcommand)]
+    Tokens(tokens::TokenCommand),
+}
+
+pub fn cli(args: Vec<String>) {
+    match Cli::parse_from(args).command {
+        Command::Match {
+            script1_path,
+            script2_path,
+            debug: false,
+        } => local::run_local_match(&script1_path, &script2_path),
+        Command::Match {
+            script1_path,
+            script2_path,
+            debug: true,
+        } => debugger::debug_match(&script1_path, &script2_path),
+        Command::Ladder { dir, games } => ladder::run_ladder(&dir, games),
+        Command::Play { opponent, second } => play::play(&opponent, !second),
+        Command::TestBot {
+            script_path,
+            scenarios_path,
+        } => test_bot::test_bot(&script_path, &scenarios_path),
+        Command::Generate => local::generate(),
+        Command::GenerateMain => local::generate_main(),
+        Command::GenerateMatches => local::generate_matches(),
+        Command::GenerateLogs => local::generate_logs(),
+        Command::GenerateSite { out } => local::generate_site(&out),
+        Command::Clear => local::clear(),
+        Command::Stats { out } => stats::stats(out.as_deref()),
+        Command::ScheduleMatchmaking { dry_run } => matchmaking::scheduled_matchmaking(dry_run),
+        Command::LadderMatch {
+            submission1_id,
+            submission2_id,
+        } => matchmaking::ladder_match(&submission1_id, &submission2_id),
+        Command::Submissions(command) => submissions::run(command),
+        Command::Users(command) => users::run(command),
+        Command::Matches(command) => matches::run(command),
+        Command::Disqualifications(command) => disqualifications::run(command),
+        Command::Tokens(command) => tokens::run(command),
+    }
+}
+
+fn connect() -> PooledConnection<ConnectionManager<SqliteConnection>> {
+    match backend::db::establish_connection().get() {
+        Ok(conn) => conn,
+        Err(error) => fail(&format!(
+            "Could not establish connection to database: {}",
+            error
+        )),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    println!("{}", message);
+    process::exit(1);
+}
+
+mod tests {
+    mod command_tests;
+    mod submission_tests;
+}
@@ -0,0 +1,223 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    backend::models::submission_model::Submission,
+    game::{
+        game::{ErrorType, Game, GameResult, Move, Wall},
+        graphics::render_board,
+        methods::{self, load_program},
+        turn::{on_turn, play_turn, MoveSource},
+    },
+};
+
+use super::{connect, fail};
+
+const HELP: &str = "Moves: up, down, left, right (or w, s, a, d) as seen on the board.
+Walls: wall x1 y1 x2 y2, two adjacent tiles, like the bots use.
+Commands: undo, help, quit. Undo takes back your last move and the reply
+of the bot, the bot keeps any state it stored in its globals.";
+
+/// A move typed by the player, the jump is asked for up front
+/// when the move steps onto the opponent
+struct HumanMoves {
+    turn: Move,
+    jump: Option<Move>,
+}
+impl MoveSource for HumanMoves {
+    fn on_turn(&mut self, _: &Game) -> Result<Move, ErrorType> {
+        Ok(self.turn.clone())
+    }
+
+    fn on_jump(&mut self, _: &Game) -> Result<Move, ErrorType> {
+        self.jump.take().ok_or(ErrorType::GameError {
+            reason: "No jump direction given".to_string(),
+            fault: None,
+        })
+    }
+}
+
+/// Parses a move in the notation of the play command
+fn parse_move(input: &str) -> Result<Move, String> {
+    let words: Vec<&str> = input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .collect();
+    match words.as_slice() {
+        ["up"] | ["w"] => Ok(Move::Up),
+        ["down"] | ["s"] => Ok(Move::Down),
+        ["left"] | ["a"] => Ok(Move::Left),
+        ["right"] | ["d"] => Ok(Move::Right),
+        ["wall", coordinates @ ..] | coordinates if coordinates.len() == 4 => {
+            let coordinates = coordinates
+                .iter()
+                .map(|coordinate| coordinate.parse::<i32>())
+                .collect::<Result<Vec<i32>, _>>()
+                .map_err(|_| format!("Invalid wall coordinates: {}", input))?;
+            Ok(Move::Wall(Wall {
+                x1: coordinates[0],
+                y1: coordinates[1],
+                x2: coordinates[2],
+                y2: coordinates[3],
+            }))
+        }
+        _ => Err(format!(
+            "Unknown move: {}, type help for the notation",
+            input
+        )),
+    }
+}
+
+/// The bot is either a lua file or the id of a stored submission
+fn load_opponent(opponent: &str) -> String {
+    if let Ok(script) = std::fs::read_to_string(opponent) {
+        return script;
+    }
+    let conn = connect();
+    match Submission::by_id(opponent, &conn) {
+        Some(submission) => submission.script,
+        None => fail(&format!(
+            "{} is neither a lua file nor a stored submission",
+            opponent
+        )),
+    }
+}
+
+pub(super) fn play(opponent: &str, human_is_player_one: bool) {
+    let script = load_opponent(opponent);
+    let std =
+        std::fs::read_to_string("../scripts/std.lua").expect("Could not load standard library");
+    let mut game = methods::new(std);
+    if let Err(error) = load_program(&game, !human_is_player_one, &script) {
+        fail(&error);
+    }
+
+    println!(
+        "You are {} and move {}. {}\n",
+        if human_is_player_one { "O" } else { "X" },
+        if human_is_player_one { "up" } else { "down" },
+        HELP
+    );
+    let mut history: Vec<Game> = Vec::new();
+    let mut lines = std::io::stdin().lock().lines();
+    while game.running {
+        if game.player_one_turn != human_is_player_one {
+            if let Err(error) = on_turn(&mut game) {
+                println!("The bot failed: {}", error.reason());
+                return;
+            }
+            methods::winner(&mut game);
+            continue;
+        }
+
+        print!("\n{}\nYour move: ", render_board(&game));
+        std::io::stdout().flush().unwrap();
+        let input = match lines.next() {
+            Some(Ok(input)) => input.trim().to_lowercase(),
+            _ => return,
+        };
+        match input.as_str() {
+            "quit" | "exit" => return,
+            "help" => println!("{}", HELP),
+            "undo" => match history.pop() {
+                Some(previous) => game = previous,
+                None => println!("Nothing to undo"),
+            },
+            _ => {
+                let turn = match parse_move(&input) {
+                    Ok(turn) => turn,
+                    Err(error) => {
+                        println!("{}", error);
+                        continue;
+                    }
+                };
+                let jump = if steps_onto_opponent(&game, &turn) {
+                    print!("Jump direction: ");
+                    std::io::stdout().flush().unwrap();
+                    match lines.next() {
+                        Some(Ok(jump)) => parse_move(jump.trim()).ok(),
+                        _ => return,
+                    }
+                } else {
+                    None
+                };
+
+                let mut next = game.clone();
+                match play_turn(&mut next, &mut HumanMoves { turn, jump }) {
+                    Ok(_) => {
+                        history.push(game);
+                        game = next;
+                        methods::winner(&mut game);
+                    }
+                    Err(error) => println!("Illegal move: {}", error.reason()),
+                }
+            }
+        }
+    }
+
+    print!("\n{}", render_board(&game));
+    let human_won = match game.game_result {
+        Some(GameResult::PlayerOneWon) => human_is_player_one,
+        _ => !human_is_player_one,
+    };
+    println!("{}", if human_won { "You won!" } else { "The bot won" });
+}
+
+fn steps_onto_opponent(game: &Game, turn: &Move) -> bool {
+    let (active, other) = if game.player_one_turn {
+        (&game.player_one, &game.player_two)
+    } else {
+        (&game.player_two, &game.player_one)
+    };
+    active.move_player(turn) == (other.x, other.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_move, HumanMoves};
+    use crate::game::{
+        game::{Move, Wall},
+        methods,
+        turn::play_turn,
+    };
+
+    #[test]
+    fn parses_moves_and_walls() {
+        assert_eq!(parse_move("up"), Ok(Move::Up));
+        assert_eq!(parse_move("a"), Ok(Move::Left));
+        let wall = Move::Wall(Wall {
+            x1: 3,
+            y1: 4,
+            x2: 4,
+            y2: 4,
+        });
+        assert_eq!(parse_move("wall 3 4 4 4"), Ok(wall.clone()));
+        assert_eq!(parse_move("3,4,4,4"), Ok(wall));
+        assert!(parse_move("wall 3 4").is_err());
+        assert!(parse_move("jump").is_err());
+    }
+
+    #[test]
+    fn explains_illegal_moves() {
+        let mut game = methods::new(String::new());
+        let error = play_turn(
+            &mut game,
+            &mut HumanMoves {
+                turn: Move::Down,
+                jump: None,
+            },
+        )
+        .unwrap_err();
+        assert!(error.reason().contains("out of bounds"));
+
+        play_turn(
+            &mut game,
+            &mut HumanMoves {
+                turn: Move::Up,
+                jump: None,
+            },
+        )
+        .unwrap();
+        assert_eq!((game.player_one.x, game.player_one.y), (4, 7));
+        assert!(!game.player_one_turn);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{
+    analytics::{match_stats::ladder_stats, report::stats_report},
+    backend::models::{match_model::Match, submission_model::Submission, turn_model::Turn},
+    external_related::readme_factory::write_file,
+};
+
+use super::{connect, fail};
+
+pub(super) fn stats(out: Option<&str>) {
+    let conn = connect();
+    let stats = ladder_stats(
+        &Match::list(&conn),
+        &Turn::list(&conn),
+        &Submission::list(&conn),
+    );
+    let report = stats_report(
+        &stats,
+        &chrono::Local::now()
+            .naive_local()
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+    );
+    match out {
+        Some(path) => match write_file(path, report) {
+            Ok(()) => println!("Wrote the report to {}", path),
+            Err(error) => fail(&format!("Could not write {}: {}", path, error)),
+        },
+        None => println!("{}", report),
+    }
+}
@@ -0,0 +1,218 @@
+use clap::Subcommand;
+use diesel::SqliteConnection;
+
+use crate::{
+    backend::models::{
+        bot_model::Bot,
+        disqualification_model::{Disqualification, DisqualificationKind},
+        match_model::Match,
+        submission_model::Submission,
+    },
+    match_maker::disqualifications::reinstate,
+};
+
+use super::{connect, disqualifications::format_record, fail, matches::format_match};
+
+#[derive(Subcommand)]
+pub(super) enum SubmissionCommand {
+    /// List submissions, best rated first
+    List {
+        /// Only show submissions of this user id
+        #[arg(long)]
+        user: Option<String>,
+        /// Only show submissions that are active, disqualified or retired
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Show a submission with its disqualifications and matches
+    Show {
+        id: String,
+        /// Print the script as well
+        #[arg(long)]
+        script: bool,
+    },
+    /// Disqualify a submission, it no longer takes part in matches
+    Disqualify {
+        id: String,
+        #[arg(long, default_value = "Disqualified by an admin")]
+        reason: String,
+    },
+    /// Lift every disqualification of a submission
+    Reinstate { id: String },
+    /// Delete a submission together with its matches
+    Delete { id: String },
+}
+
+pub(super) fn run(command: SubmissionCommand) {
+    match command {
+        SubmissionCommand::List { user, status } => list(user.as_deref(), status.as_deref()),
+        SubmissionCommand::Show { id, script } => show(&id, script),
+        SubmissionCommand::Disqualify { id, reason } => {
+            let conn = connect();
+            let submission = find(&id, &conn);
+            match disqualify_submission(submission, &reason, &conn) {
+                Ok(submission) => println!("Disqualified {}", submission.id),
+                Err(error) => fail(&error),
+            }
+        }
+        SubmissionCommand::Reinstate { id } => {
+            let conn = connect();
+            let submission = find(&id, &conn);
+            match reinstate_submission(&submission, &conn) {
+                Ok(submission) => println!("Reinstated {}", submission.id),
+                Err(error) => fail(&error),
+            }
+        }
+        SubmissionCommand::Delete { id } => {
+            let conn = connect();
+            let submission = find(&id, &conn);
+            let deleted_matches = delete_submission(&submission, &conn);
+            println!(
+                "Deleted {} and {} of its matches",
+                submission.id, deleted_matches
+            );
+        }
+    }
+}
+
+fn find(id: &str, conn: &SqliteConnection) -> Submission {
+    Submission::by_id(id, conn)
+        .unwrap_or_else(|| fail(&format!("Could not find submission {}", id)))
+}
+
+fn list(user: Option<&str>, status: Option<&str>) {
+    let conn = connect();
+    let mut submissions = match user {
+        Some(user) => Submission::by_user(user, &conn),
+        None => Submission::list(&conn),
+    };
+    if let Some(status) = status {
+        submissions.retain(|submission| match status {
+            "active" => submission.is_active(),
+            "disqualified" => submission.disqualified >= 1,
+            "retired" => submission.retired >= 1,
+            _ => fail(&format!(
+                "Unknown status {}, use active, disqualified or retired",
+                status
+            )),
+        });
+    }
+    submissions.sort_by(|a, b| b.mmr.total_cmp(&a.mmr));
+
+    for submission in submissions {
+        println!("{}", format_submission(&submission));
+    }
+}
+
+pub(super) fn format_submission(submission: &Submission) -> String {
+    format!(
+        "{} | {} | user {} | v{} | {:.0} mmr | {} matches | {} | {}",
+        submission.id,
+        submission.created_at.format("%Y-%m-%d %H:%M"),
+        submission.user,
+        submission.version,
+        submission.mmr,
+        submission.matches_played,
+        if submission.disqualified >= 1 {
+            "disqualified"
+        } else if submission.retired >= 1 {
+            "retired"
+        } else {
+            "active"
+        },
+        submission.issue_url
+    )
+}
+
+fn show(id: &str, script: bool) {
+    let conn = connect();
+    let submission = find(id, &conn);
+    println!("{}", format_submission(&submission));
+    if let Some(comment) = submission.comment.as_ref() {
+        println!("Comment: {}", comment);
+    }
+
+    println!("\nDisqualifications:");
+    for record in Disqualification::by_submission(&submission.id, &conn) {
+        println!("{}", format_record(&record));
+    }
+    println!("\nMatches:");
+    for current in Match::by_submission(&submission.id, &conn) {
+        println!("{}", format_match(&current));
+    }
+    if script {
+        println!("\n{}", submission.script);
+    }
+}
+
+pub(super) fn disqualify_submission(
+    mut submission: Submission,
+    reason: &str,
+    conn: &SqliteConnection,
+) -> Result<Submission, String> {
+    if submission.disqualified >= 1 {
+        return Err(format!(
+            "Submission {} is already disqualified",
+            submission.id
+        ));
+    }
+    Disqualification::create(
+        &submission.id,
+        None,
+        None,
+        DisqualificationKind::Manual,
+        reason,
+        false,
+        conn,
+    )
+    .ok_or("Could not record disqualification")?;
+    submission.disqualified = 1;
+    submission.save(conn);
+    Ok(submission)
+}
+
+/// Reinstates every disqualification of the submission that isn't lifted yet
+pub(super) fn reinstate_submission(
+    submission: &Submission,
+    conn: &SqliteConnection,
+) -> Result<Submission, String> {
+    if submission.disqualified == 0 {
+        return Err(format!("Submission {} is not disqualified", submission.id));
+    }
+    let mut reinstated = submission.clone();
+    for mut record in Disqualification::by_submission(&submission.id, conn) {
+        if !record.is_reinstated() {
+            reinstated = reinstate(&mut record, conn)?;
+        }
+    }
+    // Submissions disqualified without a record are reinstated directly
+    if reinstated.disqualified >= 1 {
+        reinstated.disqualified = 0;
+        reinstated.save(conn);
+    }
+    Ok(reinstated)
+}
+
+/// Deletes the submission, its matches and its disqualifications. The bot
+/// is deleted as well if this was its only version. Returns the number of
+/// matches that were deleted.
+pub(super) fn delete_submission(submission: &Submission, conn: &SqliteConnection) -> usize {
+    let matches = Match::by_submission(&submission.id, conn);
+    for current in matches.iter() {
+        Disqualification::detach_match(&current.id, conn);
+        current.delete(conn);
+    }
+    Disqualification::delete_by_submission(&submission.id, conn);
+    submission.delete(conn);
+
+    if let Some(bot) = submission
+        .bot
+        .as_ref()
+        .and_then(|bot_id| Bot::by_id(bot_id, conn))
+    {
+        if bot.versions(conn).is_empty() {
+            bot.delete(conn);
+        }
+    }
+    matches.len()
+}
@@ -0,0 +1,33 @@
+use std::process;
+
+use crate::game::scenario::{parse_scenarios, run_scenarios};
+
+use super::fail;
+
+pub(super) fn test_bot(script_path: &str, scenarios_path: &str) {
+    let script = std::fs::read_to_string(script_path)
+        .unwrap_or_else(|error| fail(&format!("Could not load {}: {}", script_path, error)));
+    let scenarios = std::fs::read_to_string(scenarios_path)
+        .map_err(|error| format!("Could not load {}: {}", scenarios_path, error))
+        .and_then(|json| parse_scenarios(&json))
+        .unwrap_or_else(|error| fail(&error));
+    let std =
+        std::fs::read_to_string("../scripts/std.lua").expect("Could not load standard library");
+
+    let results = run_scenarios(&script, &std, &scenarios);
+    let failed = results.iter().filter(|result| !result.passed()).count();
+    for result in results.iter() {
+        match result.failure.as_ref() {
+            None => println!(
+                "PASS {} ({})",
+                result.name,
+                result.returned.as_deref().unwrap_or("-")
+            ),
+            Some(failure) => println!("FAIL {}: {}", result.name, failure),
+        }
+    }
+    println!("\n{} passed, {} failed", results.len() - failed, failed);
+    if failed > 0 {
+        process::exit(1);
+    }
+}
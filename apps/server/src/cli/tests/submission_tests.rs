@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::backend::fixtures::{setup, submission, UPWARDS_BOT};
+    use crate::backend::models::{
+        bot_model::Bot,
+        disqualification_model::{Disqualification, DisqualificationKind},
+        match_model::Match,
+        submission_model::Submission,
+        turn_model::Turn,
+    };
+    use crate::cli::submissions::{delete_submission, disqualify_submission, reinstate_submission};
+    use crate::match_maker::{live_matches::MatchKind, match_executor::execute_match_queue};
+
+    #[test]
+    fn disqualifies_and_reinstates_manually() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let alice = submission("alice", UPWARDS_BOT, &conn);
+
+        let disqualified = disqualify_submission(alice, "Abusive comment", &conn).unwrap();
+        assert_eq!(disqualified.disqualified, 1);
+        let records = Disqualification::by_submission(&disqualified.id, &conn);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind(), DisqualificationKind::Manual);
+        assert!(disqualify_submission(disqualified.clone(), "Again", &conn).is_err());
+
+        let reinstated = reinstate_submission(&disqualified, &conn).unwrap();
+        assert_eq!(reinstated.disqualified, 0);
+        assert!(Disqualification::by_submission(&reinstated.id, &conn)[0].is_reinstated());
+    }
+
+    #[test]
+    fn deleting_a_submission_removes_its_matches() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let alice = submission("alice", UPWARDS_BOT, &conn);
+        let bob = submission("bob", UPWARDS_BOT, &conn);
+        execute_match_queue(&conn, vec![(alice.clone(), bob.clone())], MatchKind::Manual);
+        assert_eq!(Match::list(&conn).len(), 1);
+
+        assert_eq!(delete_submission(&alice, &conn), 1);
+        assert!(Submission::by_id(&alice.id, &conn).is_none());
+        assert!(Bot::by_id(alice.bot.as_ref().unwrap(), &conn).is_none());
+        assert!(Match::list(&conn).is_empty());
+        assert!(Turn::list(&conn).is_empty());
+        assert!(Submission::by_id(&bob.id, &conn).is_some());
+    }
+}
@@ -0,0 +1,79 @@
+use clap::Subcommand;
+
+use crate::backend::models::{api_token_model::ApiToken, user_model::User};
+
+use super::{connect, fail, users::find};
+
+#[derive(Subcommand)]
+pub(super) enum TokenCommand {
+    /// List api tokens, newest first
+    List {
+        /// Only show tokens of this user, by id or username
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// Create a token a user submits bots with, it is only printed once
+    Create {
+        /// User id or username, the user is created if it doesn't exist
+        user: String,
+        /// What the token is used for
+        #[arg(long, default_value = "api")]
+        name: String,
+    },
+    /// Revoke a token, by id
+    Revoke { id: String },
+}
+
+pub(super) fn run(command: TokenCommand) {
+    match command {
+        TokenCommand::List { user } => list(user.as_deref()),
+        TokenCommand::Create { user, name } => create(&user, &name),
+        TokenCommand::Revoke { id } => {
+            let conn = connect();
+            let token = ApiToken::by_id(&id, &conn)
+                .unwrap_or_else(|| fail(&format!("Could not find token {}", id)));
+            token.delete(&conn);
+            println!("Revoked {}", token.id);
+        }
+    }
+}
+
+fn list(user: Option<&str>) {
+    let conn = connect();
+    let tokens = match user {
+        Some(user) => ApiToken::by_user(&find(user, &conn).id, &conn),
+        None => ApiToken::list(&conn),
+    };
+    for token in tokens {
+        let username = User::by_id(&token.user, &conn)
+            .map(|user| user.username)
+            .unwrap_or("<Unknown>".to_string());
+        println!(
+            "{} | {} | {} | created {} | last used {}",
+            token.id,
+            username,
+            token.name,
+            token.created_at.format("%Y-%m-%d %H:%M"),
+            token
+                .last_used_at
+                .map(|used| used.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or("never".to_string())
+        );
+    }
+}
+
+fn create(user: &str, name: &str) {
+    let conn = connect();
+    // Users are normally created by their first submission, token holders may not have one yet
+    let user = User::by_id(user, &conn)
+        .or_else(|| User::by_username(user, &conn))
+        .or_else(|| User::create(user, &conn))
+        .unwrap_or_else(|| fail(&format!("Could not create user {}", user)));
+    match ApiToken::create(&user.id, name, &conn) {
+        Some((token, secret)) => {
+            println!("Created token {} for {}", token.id, user.username);
+            println!("{}", secret);
+        }
+        None => fail("Could not create token"),
+    }
+}
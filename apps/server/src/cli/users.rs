@@ -0,0 +1,119 @@
+use clap::Subcommand;
+use diesel::SqliteConnection;
+
+use crate::backend::models::{
+    api_token_model::ApiToken, bot_model::Bot, submission_model::Submission, user_model::User,
+};
+
+use super::{
+    connect, fail,
+    submissions::{
+        delete_submission, disqualify_submission, format_submission, reinstate_submission,
+    },
+};
+
+#[derive(Subcommand)]
+pub(super) enum UserCommand {
+    /// List users
+    List,
+    /// Show a user with their bots and submissions, by id or username
+    Show { user: String },
+    /// Disqualify every active submission of a user
+    Disqualify {
+        user: String,
+        #[arg(long, default_value = "Disqualified by an admin")]
+        reason: String,
+    },
+    /// Lift the disqualifications of every submission of a user
+    Reinstate { user: String },
+    /// Delete a user together with their bots, submissions, matches and api tokens
+    Delete { user: String },
+}
+
+pub(super) fn run(command: UserCommand) {
+    match command {
+        UserCommand::List => list(),
+        UserCommand::Show { user } => show(&user),
+        UserCommand::Disqualify { user, reason } => {
+            let conn = connect();
+            let user = find(&user, &conn);
+            for submission in Submission::by_user(&user.id, &conn) {
+                if submission.disqualified >= 1 {
+                    continue;
+                }
+                match disqualify_submission(submission, &reason, &conn) {
+                    Ok(submission) => println!("Disqualified {}", submission.id),
+                    Err(error) => println!("{}", error),
+                }
+            }
+        }
+        UserCommand::Reinstate { user } => {
+            let conn = connect();
+            let user = find(&user, &conn);
+            for submission in Submission::by_user(&user.id, &conn) {
+                if submission.disqualified == 0 {
+                    continue;
+                }
+                match reinstate_submission(&submission, &conn) {
+                    Ok(submission) => println!("Reinstated {}", submission.id),
+                    Err(error) => println!("{}", error),
+                }
+            }
+        }
+        UserCommand::Delete { user } => {
+            let conn = connect();
+            let user = find(&user, &conn);
+            delete_user(&user, &conn);
+            println!("Deleted {}", user.username);
+        }
+    }
+}
+
+/// Users are looked up by id first, then by username
+pub(super) fn find(user: &str, conn: &SqliteConnection) -> User {
+    User::by_id(user, conn)
+        .or_else(|| User::by_username(user, conn))
+        .unwrap_or_else(|| fail(&format!("Could not find user {}", user)))
+}
+
+fn list() {
+    let conn = connect();
+    for user in User::list(&conn) {
+        println!(
+            "{} | {} | {} submissions",
+            user.id,
+            user.username,
+            Submission::by_user(&user.id, &conn).len()
+        );
+    }
+}
+
+fn show(user: &str) {
+    let conn = connect();
+    let user = find(user, &conn);
+    println!(
+        "{} | {} | joined {}",
+        user.id,
+        user.username,
+        user.created_at.format("%Y-%m-%d")
+    );
+    for bot in Bot::by_user(&user.id, &conn) {
+        println!("\nBot {} ({}):", bot.name, bot.id);
+        for version in bot.versions(&conn) {
+            println!("{}", format_submission(&version));
+        }
+    }
+}
+
+fn delete_user(user: &User, conn: &SqliteConnection) {
+    for submission in Submission::by_user(&user.id, conn) {
+        delete_submission(&submission, conn);
+    }
+    for bot in Bot::by_user(&user.id, conn) {
+        bot.delete(conn);
+    }
+    for token in ApiToken::by_user(&user.id, conn) {
+        token.delete(conn);
+    }
+    user.delete(conn);
+}
@@ -1,24 +1,352 @@
 /*
     Code always comes encapsulated in
-    markdown code blocks. This function
-    extracts the code from the block
+    markdown code blocks. This module
+    finds the fenced block containing the
+    bot and extracts the code from it
 */
-pub fn unwrap_code(raw_data: &str) -> Result<String, String> {
-    if raw_data.chars().take(6).collect::<String>() != "```lua" {
-        return Err("Could not parse code block, please check the documentation on how to format your submissions".to_string());
+
+const BOT_LANGUAGE: &str = "lua";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub code: String,
+    /// Lowercased language from the info string
+    pub language: Option<String>,
+    /// Optional `name=` metadata from the info string
+    pub name: Option<String>,
+    /// True if the info string contains `retire=true`, the previous
+    /// versions of the bot are then taken off the ladder
+    pub retire_previous: bool,
+    /// Line of the opening fence, starting at 1
+    pub line: usize,
+}
+
+struct Fence {
+    character: char,
+    length: usize,
+    indent: usize,
+    info: String,
+}
+
+struct InfoString {
+    language: Option<String>,
+    name: Option<String>,
+    retire_previous: bool,
+}
+
+/// Finds the single lua block of a submission. Blocks
+/// without a language are only used if there is no lua block.
+pub fn unwrap_code(raw_data: &str) -> Result<CodeBlock, String> {
+    let blocks = find_code_blocks(raw_data);
+
+    let lua_blocks: Vec<&CodeBlock> = blocks
+        .iter()
+        .filter(|block| block.language.as_deref() == Some(BOT_LANGUAGE))
+        .collect();
+    let candidates = if lua_blocks.is_empty() {
+        blocks
+            .iter()
+            .filter(|block| block.language.is_none())
+            .collect()
+    } else {
+        lua_blocks
+    };
+
+    match candidates.len() {
+        1 => Ok(candidates[0].clone()),
+        0 => Err(match blocks.first() {
+            Some(other) => format!(
+                "Found a `{}` code block on line {} but bots have to be written in lua, mark the block with ```lua",
+                other.language.as_deref().unwrap_or_default(),
+                other.line
+            ),
+            None => "Could not find a ```lua code block, please check the documentation on how to format your submissions".to_string(),
+        }),
+        count => Err(format!(
+            "Found {} code blocks that could contain the bot (on lines {}), a submission must contain exactly one",
+            count,
+            candidates
+                .iter()
+                .map(|block| block.line.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )),
     }
-    Ok(raw_data.chars().skip(6).take_while(|c| *c != '`').collect())
+}
+
+/// Every fenced code block of a markdown document, following the
+/// CommonMark rules for fences: at least three backticks or tildes
+/// indented by at most three spaces, closed by a fence of the same
+/// character that is at least as long. An unclosed block runs until
+/// the end of the document.
+pub fn find_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let normalized = markdown.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut blocks: Vec<CodeBlock> = Vec::new();
+    let mut open: Option<(Fence, usize, Vec<&str>)> = None;
+    for (index, line) in normalized.split('\n').enumerate() {
+        open = match open {
+            None => opening_fence(line).map(|fence| (fence, index + 1, Vec::new())),
+            Some((fence, start, lines)) if is_closing_fence(line, &fence) => {
+                blocks.push(to_code_block(&fence, start, &lines));
+                None
+            }
+            Some((fence, start, mut lines)) => {
+                lines.push(strip_indent(line, fence.indent));
+                Some((fence, start, lines))
+            }
+        };
+    }
+    if let Some((fence, start, mut lines)) = open {
+        // The document ended with a newline, that isn't part of the code
+        if lines.last() == Some(&"") {
+            lines.pop();
+        }
+        blocks.push(to_code_block(&fence, start, &lines));
+    }
+    blocks
+}
+
+fn opening_fence(line: &str) -> Option<Fence> {
+    let indent = line.chars().take_while(|c| *c == ' ').count();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let character = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = rest.chars().take_while(|c| *c == character).count();
+    if length < 3 {
+        return None;
+    }
+
+    let info = rest[length..].trim();
+    // Otherwise it would be inline code
+    if character == '`' && info.contains('`') {
+        return None;
+    }
+    Some(Fence {
+        character,
+        length,
+        indent,
+        info: info.to_string(),
+    })
+}
+
+fn is_closing_fence(line: &str, fence: &Fence) -> bool {
+    let indent = line.chars().take_while(|c| *c == ' ').count();
+    if indent > 3 {
+        return false;
+    }
+    let rest = &line[indent..];
+    let length = rest.chars().take_while(|c| *c == fence.character).count();
+    length >= fence.length && rest[length..].trim().is_empty()
+}
+
+/// Content lines lose as much indentation as the opening fence had
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let spaces = line.chars().take(indent).take_while(|c| *c == ' ').count();
+    &line[spaces..]
+}
+
+fn to_code_block(fence: &Fence, line: usize, lines: &[&str]) -> CodeBlock {
+    let info = parse_info(&fence.info);
+    CodeBlock {
+        code: lines.iter().map(|line| format!("{}\n", line)).collect(),
+        language: info.language,
+        name: info.name,
+        retire_previous: info.retire_previous,
+        line,
+    }
+}
+
+/// The first word of the info string is the language, the rest
+/// are `key=value` pairs where values may be double quoted, e.g.
+/// ```lua name="Wall builder" retire=true
+fn parse_info(info: &str) -> InfoString {
+    let mut parsed = InfoString {
+        language: None,
+        name: None,
+        retire_previous: false,
+    };
+
+    for (index, word) in split_info(info).into_iter().enumerate() {
+        match word.split_once('=') {
+            Some((key, value)) => match key.trim().to_lowercase().as_str() {
+                "name" if !value.is_empty() => parsed.name = Some(value.to_string()),
+                "lang" | "language" if !value.is_empty() => {
+                    parsed.language = Some(value.to_lowercase())
+                }
+                "retire" => parsed.retire_previous = value.eq_ignore_ascii_case("true"),
+                _ => (),
+            },
+            None if index == 0 => parsed.language = Some(word.to_lowercase()),
+            None => (),
+        }
+    }
+    parsed
+}
+
+/// Splits on whitespace outside of double quotes, the quotes are removed
+fn split_info(info: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in info.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::external_related::code_unwrapper::unwrap_code;
+    use crate::external_related::code_unwrapper::{find_code_blocks, unwrap_code};
 
     #[test]
     /// Test that code is unwrapped correctly
     fn code_unwrapper_test() {
         let raw_data = "```lua\nprint(\"Hello World\")\n```";
-        let code = unwrap_code(raw_data).unwrap();
-        assert_eq!(code, "\nprint(\"Hello World\")\n");
+        let code = unwrap_code(raw_data).unwrap().code;
+        assert_eq!(code, "print(\"Hello World\")\n");
+    }
+
+    #[test]
+    fn extracts_bot_from_issue_bodies() {
+        let bot = "function onTurn(context)\n    return \"0\"\nend\n";
+        let cases: Vec<(&str, String)> = vec![
+            ("fenced", format!("```lua\n{}```", bot)),
+            (
+                "leading prose",
+                format!("My first bot!\n\n```lua\n{}```\nGood luck", bot),
+            ),
+            ("crlf", format!("```lua\n{}```", bot).replace('\n', "\r\n")),
+            ("capitalised language", format!("```Lua\n{}```", bot)),
+            ("tildes", format!("~~~lua\n{}~~~", bot)),
+            ("longer fence", format!("````lua\n{}````", bot)),
+            ("closing fence longer", format!("```lua\n{}`````", bot)),
+            ("untagged block", format!("```\n{}```", bot)),
+            (
+                "indented fence",
+                format!("  ```lua\n  {}```", bot.replace("\n    ", "\n      ")),
+            ),
+            ("unclosed block", format!("```lua\n{}", bot)),
+            (
+                "other blocks around",
+                format!("```json\n{{}}\n```\n```lua\n{}```\n```\nlog\n```", bot),
+            ),
+        ];
+
+        for (description, body) in cases {
+            match unwrap_code(&body) {
+                Ok(block) => assert_eq!(block.code, bot, "{}", description),
+                Err(error) => panic!("{}: {}", description, error),
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_backticks_inside_code() {
+        let cases = vec![
+            (
+                "```lua\nlocal s = \"`quoted`\"\n```",
+                "local s = \"`quoted`\"\n",
+            ),
+            ("```lua\n-- ``` in a comment\n```", "-- ``` in a comment\n"),
+            ("~~~lua\nlocal s = \"```\"\n~~~", "local s = \"```\"\n"),
+            ("````lua\n```\n````", "```\n"),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(unwrap_code(body).unwrap().code, expected, "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn reads_info_string_metadata() {
+        let cases: Vec<(&str, Option<&str>, Option<&str>, bool)> = vec![
+            ("```lua\n", Some("lua"), None, false),
+            ("```lua name=Walls\n", Some("lua"), Some("Walls"), false),
+            (
+                "```lua name=\"Wall builder\"\n",
+                Some("lua"),
+                Some("Wall builder"),
+                false,
+            ),
+            (
+                "``` language=LUA name=bfs\n",
+                Some("lua"),
+                Some("bfs"),
+                false,
+            ),
+            ("```  Lua   extra\n", Some("lua"), None, false),
+            (
+                "```lua name=v2 retire=true\n",
+                Some("lua"),
+                Some("v2"),
+                true,
+            ),
+            ("```lua retire=TRUE\n", Some("lua"), None, true),
+            ("```lua retire=false\n", Some("lua"), None, false),
+            ("```\n", None, None, false),
+        ];
+        for (fence, language, name, retire_previous) in cases {
+            let block = &find_code_blocks(&format!("{}code\n```", fence))[0];
+            assert_eq!(block.language.as_deref(), language, "{:?}", fence);
+            assert_eq!(block.name.as_deref(), name, "{:?}", fence);
+            assert_eq!(block.retire_previous, retire_previous, "{:?}", fence);
+        }
+    }
+
+    #[test]
+    fn reports_precise_errors() {
+        let cases = vec![
+            (
+                "",
+                "Could not find a ```lua code block, please check the documentation on how to format your submissions",
+            ),
+            (
+                "function onTurn() return \"0\" end",
+                "Could not find a ```lua code block, please check the documentation on how to format your submissions",
+            ),
+            (
+                "Inline ```lua code``` doesn't count",
+                "Could not find a ```lua code block, please check the documentation on how to format your submissions",
+            ),
+            (
+                "    ```lua\n    indented four spaces is not a fence\n    ```",
+                "Could not find a ```lua code block, please check the documentation on how to format your submissions",
+            ),
+            (
+                "Bot:\n```python\nprint()\n```",
+                "Found a `python` code block on line 2 but bots have to be written in lua, mark the block with ```lua",
+            ),
+            (
+                "```lua\na\n```\n\n```lua\nb\n```",
+                "Found 2 code blocks that could contain the bot (on lines 1, 5), a submission must contain exactly one",
+            ),
+            (
+                "```\na\n```\n```\nb\n```",
+                "Found 2 code blocks that could contain the bot (on lines 1, 4), a submission must contain exactly one",
+            ),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(unwrap_code(body), Err(expected.to_string()), "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn reports_block_lines() {
+        let blocks = find_code_blocks("Intro\r\n\r\n```lua\r\na\r\n```\r\n~~~\r\nb\r\n~~~");
+        let lines: Vec<usize> = blocks.iter().map(|block| block.line).collect();
+        assert_eq!(lines, vec![3, 6]);
     }
 }
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use diesel::SqliteConnection;
+use serde::Deserialize;
+
+use crate::match_maker::regenerate_markdown_files::regen_markdown_files;
+
+use super::{github::github_client::GithubClient, repo_updater::is_live};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseType {
+    NotPlanned,
+    Completed,
+}
+
+/// A file changed by a pull request, `status` is
+/// one of "added", "modified", "renamed" or "removed"
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChangedFile {
+    pub filename: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckRunConclusion {
+    Success,
+    Failure,
+    Neutral,
+}
+
+/// Result shown on a pull request once its check run is completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckRunReport {
+    pub conclusion: CheckRunConclusion,
+    pub title: String,
+    /// Markdown
+    pub summary: String,
+}
+
+/// A forge call that failed even after the client's own retries.
+/// Jobs return it as their Err so that the queue retries them later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeError(pub String);
+
+impl fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for ForgeError {
+    fn from(error: String) -> Self {
+        ForgeError(error)
+    }
+}
+
+impl From<ForgeError> for String {
+    fn from(error: ForgeError) -> Self {
+        error.0
+    }
+}
+
+/// Everything the server does on the code forge hosting the
+/// challenger issues and the published match data. Jobs only
+/// talk to the forge through this trait which lets tests swap
+/// in a client that records the calls instead of sending them.
+pub(crate) trait ForgeClient {
+    fn create_issue_comment(&self, issue_number: i32, msg: &str) -> Result<(), ForgeError>;
+    fn close_issue(&self, state: CloseType, issue_number: i32) -> Result<(), ForgeError>;
+    fn add_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError>;
+    fn remove_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError>;
+    /// Regenerates the markdown files and publishes them to the repository
+    fn publish_repo(&self, conn: &SqliteConnection) -> Result<String, ForgeError>;
+    fn pull_request_files(&self, pull_number: i32) -> Result<Vec<ChangedFile>, ForgeError>;
+    /// Content of a file at a specific commit
+    fn file_content(&self, path: &str, git_ref: &str) -> Result<String, ForgeError>;
+    /// Creates an in progress check run for the commit and returns its id
+    fn create_check_run(&self, head_sha: &str, name: &str) -> Result<i64, ForgeError>;
+    fn complete_check_run(
+        &self,
+        check_run_id: i64,
+        report: &CheckRunReport,
+    ) -> Result<(), ForgeError>;
+}
+
+/// Picks the client matching the LIVE environment variable
+pub(crate) fn forge_from_env() -> Box<dyn ForgeClient> {
+    if is_live() {
+        Box::new(GithubClient::from_env())
+    } else {
+        Box::new(OfflineClient)
+    }
+}
+
+/// Used when the server isn't live, all calls are logged
+/// and the markdown files are only generated locally.
+pub(crate) struct OfflineClient;
+
+impl ForgeClient for OfflineClient {
+    fn create_issue_comment(&self, _issue_number: i32, msg: &str) -> Result<(), ForgeError> {
+        println!("[OFFLINE] Issue comment: {}", msg);
+        Ok(())
+    }
+
+    fn close_issue(&self, state: CloseType, _issue_number: i32) -> Result<(), ForgeError> {
+        println!("[OFFLINE] Close issue, reason: {:?}", state);
+        Ok(())
+    }
+
+    fn add_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        println!(
+            "[OFFLINE] Add label \"{}\" to issue {}",
+            label, issue_number
+        );
+        Ok(())
+    }
+
+    fn remove_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        println!(
+            "[OFFLINE] Remove label \"{}\" from issue {}",
+            label, issue_number
+        );
+        Ok(())
+    }
+
+    fn publish_repo(&self, conn: &SqliteConnection) -> Result<String, ForgeError> {
+        regen_markdown_files(conn)?;
+        println!("[OFFLINE] Skipping update_repo");
+        Ok("Successfully regenerated markdown files".to_string())
+    }
+
+    fn pull_request_files(&self, pull_number: i32) -> Result<Vec<ChangedFile>, ForgeError> {
+        Err(ForgeError(format!(
+            "[OFFLINE] Cannot list the files of pull request {}",
+            pull_number
+        )))
+    }
+
+    fn file_content(&self, path: &str, git_ref: &str) -> Result<String, ForgeError> {
+        Err(ForgeError(format!(
+            "[OFFLINE] Cannot fetch {} at {}",
+            path, git_ref
+        )))
+    }
+
+    fn create_check_run(&self, head_sha: &str, name: &str) -> Result<i64, ForgeError> {
+        println!("[OFFLINE] Create check run \"{}\" for {}", name, head_sha);
+        Ok(0)
+    }
+
+    fn complete_check_run(
+        &self,
+        _check_run_id: i64,
+        report: &CheckRunReport,
+    ) -> Result<(), ForgeError> {
+        println!(
+            "[OFFLINE] Check run {:?}: {}\n{}",
+            report.conclusion, report.title, report.summary
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request and response bodies of the GitHub REST api calls
+
+#[derive(Serialize)]
+pub struct CreateComment<'a> {
+    pub body: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct UpdateIssueState<'a> {
+    pub state: &'a str,
+    pub state_reason: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct AddLabels<'a> {
+    pub labels: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct CreateCheckRun<'a> {
+    pub name: &'a str,
+    pub head_sha: &'a str,
+    pub status: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct CompleteCheckRun<'a> {
+    pub status: &'a str,
+    pub conclusion: &'a str,
+    pub output: CheckRunOutput<'a>,
+}
+
+#[derive(Serialize)]
+pub struct CheckRunOutput<'a> {
+    pub title: &'a str,
+    pub summary: &'a str,
+}
+
+#[derive(Deserialize)]
+pub struct CheckRun {
+    pub id: i64,
+}
+
+/// Claims of the JWT a GitHub App authenticates itself with
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppClaims {
+    pub iat: i64,
+    pub exp: i64,
+    pub iss: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstallationToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,183 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+use super::{
+    api_schema::{AppClaims, InstallationToken},
+    constants::{GITHUB_API_URL, GITHUB_JSON_MEDIA_TYPE, TOKEN_REFRESH_MARGIN_SECONDS},
+};
+
+/// How the server authenticates against the GitHub api.
+///
+/// A GitHub App is used when GITHUB_APP_ID is set, otherwise
+/// we fall back to the personal token in GITHUB_POST_SECRET.
+pub(crate) enum GithubAuth {
+    PersonalToken(String),
+    App(AppInstallation),
+}
+
+pub(crate) struct AppInstallation {
+    app_id: String,
+    installation_id: String,
+    private_key: EncodingKey,
+    /// Cached installation token, replaced shortly before it expires
+    token: Mutex<Option<InstallationToken>>,
+}
+
+impl GithubAuth {
+    pub fn from_env() -> Self {
+        match std::env::var("GITHUB_APP_ID") {
+            Ok(app_id) if !app_id.is_empty() => {
+                let installation_id = std::env::var("GITHUB_APP_INSTALLATION_ID")
+                    .expect("GITHUB_APP_INSTALLATION_ID is required when GITHUB_APP_ID is set");
+                let key_path = std::env::var("GITHUB_APP_PRIVATE_KEY_PATH")
+                    .expect("GITHUB_APP_PRIVATE_KEY_PATH is required when GITHUB_APP_ID is set");
+                let pem = std::fs::read(&key_path).expect("Could not read GitHub App private key");
+                GithubAuth::App(
+                    AppInstallation::new(app_id, installation_id, &pem)
+                        .expect("Invalid GitHub App private key"),
+                )
+            }
+            _ => GithubAuth::PersonalToken(std::env::var("GITHUB_POST_SECRET").unwrap()),
+        }
+    }
+
+    /// Value of the Authorization header, fetches a new
+    /// installation token if the cached one is about to expire
+    pub fn authorization(&self, user_agent: &str) -> Result<String, String> {
+        match self {
+            GithubAuth::PersonalToken(token) => Ok(format!("token {}", token)),
+            GithubAuth::App(installation) => Ok(format!(
+                "token {}",
+                installation.installation_token(user_agent)?
+            )),
+        }
+    }
+
+    /// Drops the cached installation token, used when GitHub rejects it.
+    /// Returns false if there is nothing to refresh.
+    pub fn invalidate(&self) -> bool {
+        match self {
+            GithubAuth::PersonalToken(_) => false,
+            GithubAuth::App(installation) => {
+                *installation.token.lock().unwrap() = None;
+                true
+            }
+        }
+    }
+}
+
+impl AppInstallation {
+    pub fn new(app_id: String, installation_id: String, pem: &[u8]) -> Result<Self, String> {
+        let private_key = EncodingKey::from_rsa_pem(pem).map_err(|error| error.to_string())?;
+        Ok(AppInstallation {
+            app_id,
+            installation_id,
+            private_key,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Short lived JWT identifying the app itself, only used
+    /// to exchange it for an installation token
+    pub fn app_jwt(&self, now: DateTime<Utc>) -> Result<String, String> {
+        let claims = AppClaims {
+            // Backdated to allow for clock drift between us and GitHub
+            iat: now.timestamp() - 60,
+            // GitHub accepts at most ten minutes
+            exp: now.timestamp() + 9 * 60,
+            iss: self.app_id.clone(),
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &self.private_key)
+            .map_err(|error| format!("Could not sign GitHub App JWT: {}", error))
+    }
+
+    fn installation_token(&self, user_agent: &str) -> Result<String, String> {
+        let mut cached = self.token.lock().unwrap();
+        let now = Utc::now();
+        if let Some(token) = cached.as_ref() {
+            if !needs_refresh(token, now) {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let response = reqwest::blocking::Client::new()
+            .post(format!(
+                "{}/app/installations/{}/access_tokens",
+                GITHUB_API_URL, self.installation_id
+            ))
+            .header("User-Agent", user_agent)
+            .header("Accept", GITHUB_JSON_MEDIA_TYPE)
+            .header("Authorization", format!("Bearer {}", self.app_jwt(now)?))
+            .send()
+            .map_err(|error| format!("Could not request installation token: {}", error))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!(
+                "Could not request installation token: {} {}",
+                status,
+                response.text().unwrap_or_default()
+            ));
+        }
+
+        let token: InstallationToken = response
+            .json()
+            .map_err(|error| format!("Could not parse installation token: {}", error))?;
+        let value = token.token.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+}
+
+fn needs_refresh(token: &InstallationToken, now: DateTime<Utc>) -> bool {
+    now + chrono::Duration::seconds(TOKEN_REFRESH_MARGIN_SECONDS) >= token.expires_at
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+
+    use super::{needs_refresh, AppInstallation};
+    use crate::external_related::github::api_schema::{AppClaims, InstallationToken};
+
+    const PRIVATE_KEY: &[u8] = include_bytes!("tests/fixtures/test_app_key.pem");
+    const PUBLIC_KEY: &[u8] = include_bytes!("tests/fixtures/test_app_key.pub.pem");
+
+    #[test]
+    fn app_jwt_is_signed_for_the_app() {
+        let installation =
+            AppInstallation::new("1234".to_string(), "42".to_string(), PRIVATE_KEY).unwrap();
+        let now = Utc::now();
+        let jwt = installation.app_jwt(now).unwrap();
+
+        let decoded = jsonwebtoken::decode::<AppClaims>(
+            &jwt,
+            &DecodingKey::from_rsa_pem(PUBLIC_KEY).unwrap(),
+            &Validation::new(Algorithm::RS256),
+        )
+        .unwrap();
+        assert_eq!(decoded.claims.iss, "1234");
+        assert_eq!(decoded.claims.iat, now.timestamp() - 60);
+        assert!(decoded.claims.exp - now.timestamp() <= 10 * 60);
+    }
+
+    #[test]
+    fn rejects_invalid_private_key() {
+        assert!(AppInstallation::new("1".to_string(), "2".to_string(), b"not a key").is_err());
+    }
+
+    #[test]
+    fn refreshes_tokens_before_they_expire() {
+        let expires_at = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+        let token = InstallationToken {
+            token: "ghs_test".to_string(),
+            expires_at,
+        };
+        assert!(!needs_refresh(&token, expires_at - Duration::minutes(30)));
+        assert!(needs_refresh(&token, expires_at - Duration::minutes(2)));
+        assert!(needs_refresh(&token, expires_at + Duration::minutes(1)));
+    }
+}
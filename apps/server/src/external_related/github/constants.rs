@@ -0,0 +1,17 @@
+pub(crate) const GITHUB_API_URL: &str = "https://api.github.com";
+pub(crate) const GITHUB_JSON_MEDIA_TYPE: &str = "application/vnd.github.v3+json";
+/// Makes the contents api return the file itself instead of base64 inside json
+pub(crate) const GITHUB_RAW_MEDIA_TYPE: &str = "application/vnd.github.raw";
+/// Attempts per api request before the call is given up
+pub(crate) const MAX_REQUEST_ATTEMPTS: u32 = 5;
+pub(crate) const REQUEST_RETRY_BASE_DELAY_SECONDS: u64 = 2;
+/// Longest wait for a rate limit to reset, requests that would have to
+/// wait longer fail so that the job is retried later instead of
+/// blocking the worker
+pub(crate) const RATE_LIMIT_MAX_WAIT_SECONDS: u64 = 300;
+/// Longest a single api call may block the worker, counting
+/// every attempt and every wait between them
+pub(crate) const REQUEST_MAX_BLOCKING_SECONDS: u64 = 360;
+/// Installation tokens are valid for an hour, they are
+/// replaced this long before they expire
+pub(crate) const TOKEN_REFRESH_MARGIN_SECONDS: i64 = 300;
@@ -0,0 +1,473 @@
+use std::time::{Duration, Instant};
+
+use diesel::SqliteConnection;
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    header::HeaderMap,
+    Method, StatusCode,
+};
+use serde::Serialize;
+
+use crate::{
+    external_related::{
+        forge_client::{
+            ChangedFile, CheckRunConclusion, CheckRunReport, CloseType, ForgeClient, ForgeError,
+        },
+        repo_updater::{reset_to_master_branch, update_repo},
+    },
+    match_maker::regenerate_markdown_files::regen_markdown_files,
+};
+
+use super::{
+    api_schema::{
+        AddLabels, CheckRun, CheckRunOutput, CompleteCheckRun, CreateCheckRun, CreateComment,
+        UpdateIssueState,
+    },
+    app_auth::GithubAuth,
+    constants::{
+        GITHUB_API_URL, GITHUB_JSON_MEDIA_TYPE, GITHUB_RAW_MEDIA_TYPE, MAX_REQUEST_ATTEMPTS,
+        RATE_LIMIT_MAX_WAIT_SECONDS, REQUEST_MAX_BLOCKING_SECONDS,
+        REQUEST_RETRY_BASE_DELAY_SECONDS,
+    },
+};
+
+/// Talks to the GitHub REST api and pushes the
+/// generated files to the repository with git.
+pub(crate) struct GithubClient {
+    auth: GithubAuth,
+    user: String,
+    repo: String,
+    http: reqwest::blocking::Client,
+}
+
+impl GithubClient {
+    pub fn from_env() -> Self {
+        GithubClient {
+            auth: GithubAuth::from_env(),
+            user: std::env::var("GITHUB_USER").unwrap_or("hampfh".to_string()),
+            repo: std::env::var("GITHUB_REPO").unwrap_or("temp".to_string()),
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn repo_url(&self) -> String {
+        format!("{}/repos/{}/{}", GITHUB_API_URL, self.user, self.repo)
+    }
+
+    fn issue_url(&self, issue_number: i32) -> String {
+        format!("{}/issues/{}", self.repo_url(), issue_number)
+    }
+
+    fn send<B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<Response, String> {
+        self.send_as(method, url, body, GITHUB_JSON_MEDIA_TYPE)
+    }
+
+    /// Sends a request, waiting out rate limits and retrying
+    /// failed attempts. Any non-2xx response is logged.
+    fn send_as<B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        accept: &str,
+    ) -> Result<Response, String> {
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let response = self
+                .build_request(method.clone(), url, body, accept)
+                .and_then(|req| req.send().map_err(|error| error.to_string()));
+
+            let response = match response {
+                Ok(response) => response,
+                Err(error) => {
+                    println!(
+                        "[ERROR] GitHub {} {} attempt {} failed: {}",
+                        method, url, attempt, error
+                    );
+                    match within_budget(retry_delay(attempt), started.elapsed()) {
+                        Some(wait) if attempt < MAX_REQUEST_ATTEMPTS => {
+                            std::thread::sleep(wait);
+                            attempt += 1;
+                            continue;
+                        }
+                        _ => return Err(error),
+                    }
+                }
+            };
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let wait = match status {
+                // The installation token might have been revoked, fetch a new one
+                StatusCode::UNAUTHORIZED if attempt == 1 && self.auth.invalidate() => {
+                    Some(Duration::from_secs(0))
+                }
+                _ => rate_limit_wait(
+                    status,
+                    response.headers(),
+                    attempt,
+                    chrono::Utc::now().timestamp(),
+                ),
+            };
+            let text = response.text().unwrap_or_default();
+            println!(
+                "[ERROR] GitHub {} {} responded {}: {}",
+                method, url, status, text
+            );
+
+            match wait.and_then(|wait| within_budget(wait, started.elapsed())) {
+                Some(wait) if attempt < MAX_REQUEST_ATTEMPTS => {
+                    println!("Retrying in {} seconds", wait.as_secs());
+                    std::thread::sleep(wait);
+                    attempt += 1;
+                }
+                _ => return Err(format!("GitHub responded {}", status)),
+            }
+        }
+    }
+
+    fn build_request<B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        accept: &str,
+    ) -> Result<RequestBuilder, String> {
+        let req = self
+            .http
+            .request(method, url)
+            .header("User-Agent", &self.user)
+            .header("Accept", accept)
+            .header("Authorization", self.auth.authorization(&self.user)?);
+        Ok(match body {
+            Some(body) => req.json(body),
+            None => req,
+        })
+    }
+}
+
+impl ForgeClient for GithubClient {
+    fn create_issue_comment(&self, issue_number: i32, msg: &str) -> Result<(), ForgeError> {
+        let body = format!("[THIS MESSAGE IS AUTOMATIC]<br> {}", msg);
+        self.send(
+            Method::POST,
+            &format!("{}/comments", self.issue_url(issue_number)),
+            Some(&CreateComment { body: &body }),
+        )
+        .map_err(|e| {
+            ForgeError(format!(
+                "Could not post issue comment for {}: {}",
+                issue_number, e
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn close_issue(&self, state: CloseType, issue_number: i32) -> Result<(), ForgeError> {
+        let state_reason = match state {
+            CloseType::NotPlanned => "not_planned",
+            CloseType::Completed => "completed",
+        };
+        self.send(
+            Method::PATCH,
+            &self.issue_url(issue_number),
+            Some(&UpdateIssueState {
+                state: "closed",
+                state_reason,
+            }),
+        )
+        .map_err(|e| ForgeError(format!("Could not close issue {}: {}", issue_number, e)))?;
+        Ok(())
+    }
+
+    fn add_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        self.send(
+            Method::POST,
+            &format!("{}/labels", self.issue_url(issue_number)),
+            Some(&AddLabels {
+                labels: vec![label],
+            }),
+        )
+        .map_err(|e| {
+            ForgeError(format!(
+                "Could not add label {} to issue {}: {}",
+                label, issue_number, e
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn remove_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        self.send::<()>(
+            Method::DELETE,
+            &format!("{}/labels/{}", self.issue_url(issue_number), label),
+            None,
+        )
+        .map_err(|e| {
+            ForgeError(format!(
+                "Could not remove label {} from issue {}: {}",
+                label, issue_number, e
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn publish_repo(&self, conn: &SqliteConnection) -> Result<String, ForgeError> {
+        reset_to_master_branch();
+        regen_markdown_files(conn)?;
+        update_repo(format!(
+            "Match Maker update {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        ));
+        Ok("Successfully updated repository".to_string())
+    }
+
+    fn pull_request_files(&self, pull_number: i32) -> Result<Vec<ChangedFile>, ForgeError> {
+        // Bot pull requests are small, the first page is enough
+        self.send::<()>(
+            Method::GET,
+            &format!(
+                "{}/pulls/{}/files?per_page=100",
+                self.repo_url(),
+                pull_number
+            ),
+            None,
+        )?
+        .json()
+        .map_err(|error| ForgeError(format!("Could not parse pull request files: {}", error)))
+    }
+
+    fn file_content(&self, path: &str, git_ref: &str) -> Result<String, ForgeError> {
+        self.send_as::<()>(
+            Method::GET,
+            &format!("{}/contents/{}?ref={}", self.repo_url(), path, git_ref),
+            None,
+            GITHUB_RAW_MEDIA_TYPE,
+        )?
+        .text()
+        .map_err(|error| ForgeError(format!("Could not read {}: {}", path, error)))
+    }
+
+    fn create_check_run(&self, head_sha: &str, name: &str) -> Result<i64, ForgeError> {
+        let check_run: CheckRun = self
+            .send(
+                Method::POST,
+                &format!("{}/check-runs", self.repo_url()),
+                Some(&CreateCheckRun {
+                    name,
+                    head_sha,
+                    status: "in_progress",
+                }),
+            )?
+            .json()
+            .map_err(|error| ForgeError(format!("Could not parse check run: {}", error)))?;
+        Ok(check_run.id)
+    }
+
+    fn complete_check_run(
+        &self,
+        check_run_id: i64,
+        report: &CheckRunReport,
+    ) -> Result<(), ForgeError> {
+        self.send(
+            Method::PATCH,
+            &format!("{}/check-runs/{}", self.repo_url(), check_run_id),
+            Some(&CompleteCheckRun {
+                status: "completed",
+                conclusion: match report.conclusion {
+                    CheckRunConclusion::Success => "success",
+                    CheckRunConclusion::Failure => "failure",
+                    CheckRunConclusion::Neutral => "neutral",
+                },
+                output: CheckRunOutput {
+                    title: &report.title,
+                    summary: &report.summary,
+                },
+            }),
+        )?;
+        Ok(())
+    }
+}
+
+/// Decides if a failed response was caused by a rate limit
+/// and if so for how long we have to wait before trying again.
+///
+/// Primary rate limits report the reset time as a unix timestamp
+/// in X-RateLimit-Reset, secondary limits might send Retry-After.
+/// Limits that reset later than RATE_LIMIT_MAX_WAIT_SECONDS are
+/// not waited for.
+fn rate_limit_wait(
+    status: StatusCode,
+    headers: &HeaderMap,
+    attempt: u32,
+    now: i64,
+) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    };
+
+    let seconds = if let Some(retry_after) = header("Retry-After") {
+        retry_after
+    } else if header("X-RateLimit-Remaining") == Some(0) {
+        match header("X-RateLimit-Reset") {
+            // One extra second since the reset time is rounded down
+            Some(reset) => reset - now + 1,
+            None => return Some(retry_delay(attempt)),
+        }
+    } else if status == StatusCode::TOO_MANY_REQUESTS {
+        return Some(retry_delay(attempt));
+    } else {
+        // A regular 403, retrying won't change anything
+        return None;
+    };
+
+    let seconds = seconds.max(1) as u64;
+    if seconds > RATE_LIMIT_MAX_WAIT_SECONDS {
+        return None;
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// The wait before the next attempt, None if it would keep the
+/// caller blocked for longer than REQUEST_MAX_BLOCKING_SECONDS
+/// in total. The job is retried later instead.
+fn within_budget(wait: Duration, elapsed: Duration) -> Option<Duration> {
+    if elapsed + wait > Duration::from_secs(REQUEST_MAX_BLOCKING_SECONDS) {
+        return None;
+    }
+    Some(wait)
+}
+
+/// Exponential backoff for requests that failed without rate limit headers
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(REQUEST_RETRY_BASE_DELAY_SECONDS << (attempt.max(1) - 1).min(10))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        StatusCode,
+    };
+
+    use super::{rate_limit_wait, retry_delay, within_budget};
+    use crate::external_related::github::constants::{
+        RATE_LIMIT_MAX_WAIT_SECONDS, REQUEST_MAX_BLOCKING_SECONDS,
+    };
+
+    const NOW: i64 = 1_790_000_000;
+
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in values {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn waits_until_rate_limit_resets() {
+        let exhausted = headers(&[
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", &(NOW + 120).to_string()),
+        ]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &exhausted, 1, NOW),
+            Some(Duration::from_secs(121))
+        );
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &exhausted, 1, NOW),
+            Some(Duration::from_secs(121))
+        );
+    }
+
+    #[test]
+    fn prefers_retry_after() {
+        let secondary = headers(&[("Retry-After", "30"), ("X-RateLimit-Remaining", "12")]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &secondary, 1, NOW),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn does_not_wait_for_distant_resets() {
+        let past_reset = headers(&[
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", &(NOW - 10).to_string()),
+        ]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &past_reset, 1, NOW),
+            Some(Duration::from_secs(1))
+        );
+        let far_reset = headers(&[
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", &(NOW + 100_000).to_string()),
+        ]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &far_reset, 1, NOW),
+            None
+        );
+        let limit = headers(&[("Retry-After", &RATE_LIMIT_MAX_WAIT_SECONDS.to_string())]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &limit, 1, NOW),
+            Some(Duration::from_secs(RATE_LIMIT_MAX_WAIT_SECONDS))
+        );
+    }
+
+    #[test]
+    fn backs_off_on_429_without_headers() {
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), 3, NOW),
+            Some(retry_delay(3))
+        );
+        assert_eq!(retry_delay(3), retry_delay(1) * 4);
+    }
+
+    #[test]
+    fn does_not_retry_other_failures() {
+        let remaining = headers(&[("X-RateLimit-Remaining", "4000")]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &remaining, 1, NOW),
+            None
+        );
+        assert_eq!(
+            rate_limit_wait(StatusCode::NOT_FOUND, &HeaderMap::new(), 1, NOW),
+            None
+        );
+        assert_eq!(
+            rate_limit_wait(StatusCode::UNPROCESSABLE_ENTITY, &HeaderMap::new(), 1, NOW),
+            None
+        );
+    }
+
+    #[test]
+    fn gives_up_before_exceeding_the_blocking_budget() {
+        let budget = Duration::from_secs(REQUEST_MAX_BLOCKING_SECONDS);
+        let wait = Duration::from_secs(RATE_LIMIT_MAX_WAIT_SECONDS);
+        assert_eq!(within_budget(wait, Duration::ZERO), Some(wait));
+        assert_eq!(within_budget(wait, budget - wait), Some(wait));
+        assert_eq!(
+            within_budget(wait, budget - wait + Duration::from_secs(1)),
+            None
+        );
+        assert_eq!(within_budget(retry_delay(1), budget), None);
+    }
+}
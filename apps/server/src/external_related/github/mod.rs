@@ -1,3 +1,12 @@
-pub(crate) mod close_issue;
-pub(crate) mod create_issue_comment;
+pub(crate) mod api_schema;
+pub(crate) mod app_auth;
+pub(crate) mod constants;
+pub(crate) mod github_client;
+pub(crate) mod webhook_events;
 pub(crate) mod webhook_schema;
+pub(crate) mod webhook_signature;
+
+mod tests {
+    mod webhook_event_tests;
+    mod webhook_tests;
+}
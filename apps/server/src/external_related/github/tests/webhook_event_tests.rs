@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::external_related::github::{
+        webhook_events::{
+            classify_pull_request, classify_webhook, parse_command, IssueCommand, WebhookAction,
+        },
+        webhook_schema::{GithubPayload, PullRequestPayload},
+    };
+
+    fn load<T: serde::de::DeserializeOwned>(fixture: &str) -> T {
+        let raw = std::fs::read_to_string(format!(
+            "src/external_related/github/tests/fixtures/{}.json",
+            fixture
+        ))
+        .expect("Could not load fixture");
+        serde_json::from_str(&raw).expect("Could not parse fixture")
+    }
+
+    fn is_ignored(action: WebhookAction) -> bool {
+        matches!(action, WebhookAction::Ignore(_))
+    }
+
+    #[test]
+    fn classifies_recorded_events() {
+        assert_eq!(
+            classify_webhook("issues", &load("issues_opened")),
+            WebhookAction::Submit
+        );
+        assert_eq!(
+            classify_webhook("issues", &load("issues_edited")),
+            WebhookAction::Resubmit
+        );
+        assert_eq!(
+            classify_webhook("issues", &load("issues_reopened")),
+            WebhookAction::Resubmit
+        );
+        assert_eq!(
+            classify_webhook("issue_comment", &load("issue_comment_created")),
+            WebhookAction::Command(IssueCommand::Status)
+        );
+    }
+
+    #[test]
+    fn ignores_irrelevant_events() {
+        // Title only edits don't change the code
+        assert!(is_ignored(classify_webhook(
+            "issues",
+            &load("issues_edited_title")
+        )));
+        assert!(is_ignored(classify_webhook(
+            "issues",
+            &load("issues_labeled")
+        )));
+        // Only the issue author may run commands
+        assert!(is_ignored(classify_webhook(
+            "issue_comment",
+            &load("issue_comment_other_user")
+        )));
+        // Event header has to match the payload
+        assert!(is_ignored(classify_webhook(
+            "issue_comment",
+            &load("issues_opened")
+        )));
+    }
+
+    #[test]
+    fn ignores_issues_without_challenger_label() {
+        let mut payload: GithubPayload = load("issues_opened");
+        payload.issue.labels.clear();
+        assert!(is_ignored(classify_webhook("issues", &payload)));
+    }
+
+    #[test]
+    fn ignores_edits_by_others() {
+        let mut payload: GithubPayload = load("issues_edited");
+        payload.sender.login = "someone-else".to_string();
+        assert!(is_ignored(classify_webhook("issues", &payload)));
+    }
+
+    #[test]
+    fn accepts_null_body_and_description() {
+        let payload: GithubPayload = load("issues_opened_empty_body");
+        assert_eq!(payload.issue.body, "");
+        assert_eq!(payload.issue.labels[0].description, "");
+    }
+
+    #[test]
+    fn classifies_pull_requests() {
+        let opened: PullRequestPayload = load("pull_request_opened");
+        assert_eq!(
+            classify_pull_request(&opened),
+            WebhookAction::CheckPullRequest
+        );
+        let merged: PullRequestPayload = load("pull_request_merged");
+        assert_eq!(
+            classify_pull_request(&merged),
+            WebhookAction::MergePullRequest
+        );
+
+        let mut pushed: PullRequestPayload = load("pull_request_opened");
+        pushed.action = "synchronize".to_string();
+        assert_eq!(
+            classify_pull_request(&pushed),
+            WebhookAction::CheckPullRequest
+        );
+        // Closed without merging
+        let mut closed: PullRequestPayload = load("pull_request_merged");
+        closed.pull_request.merged = false;
+        assert!(is_ignored(classify_pull_request(&closed)));
+    }
+
+    #[test]
+    fn parses_commands() {
+        let cases: Vec<(&str, Option<Result<IssueCommand, String>>)> = vec![
+            ("/status", Some(Ok(IssueCommand::Status))),
+            ("/rematch", Some(Ok(IssueCommand::Rematch))),
+            ("/retire", Some(Ok(IssueCommand::Retire))),
+            ("/resubmit", Some(Ok(IssueCommand::Resubmit))),
+            ("  /STATUS please\r\n", Some(Ok(IssueCommand::Status))),
+            (
+                "/rematch\nthe last one was unlucky",
+                Some(Ok(IssueCommand::Rematch)),
+            ),
+            ("/dance", Some(Err("/dance".to_string()))),
+            ("nice bot! /status", None),
+            ("", None),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(parse_command(body), expected, "body: {:?}", body);
+        }
+    }
+}
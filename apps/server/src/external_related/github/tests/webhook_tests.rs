@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::{
+            db::establish_test_connection,
+            fixtures::{webhook_secret, WEBHOOK_SECRET as SECRET},
+            services::core::submit_challenge,
+        },
+        external_related::github::{
+            webhook_schema::GithubPayload, webhook_signature::verify_signature,
+        },
+    };
+    use actix_web::{http::StatusCode, test as actix_test, web::Data, App};
+
+    /**
+     * The payloads in the fixtures directory are recorded
+     * deliveries, the signatures below were computed over those
+     * exact bytes with the test secret, an HMAC-SHA256 like the
+     * one GitHub sends in X-Hub-Signature-256.
+     */
+    const ISSUES_OPENED: &[u8] = include_bytes!("fixtures/issues_opened.json");
+    const ISSUES_OPENED_SIGNATURE: &str =
+        "sha256=a1f04009aa349437a129711a86b896c16b1d61617d28f876e6eeef515669a2d7";
+    const ISSUES_LABELED: &[u8] = include_bytes!("fixtures/issues_labeled.json");
+    const ISSUES_LABELED_SIGNATURE: &str =
+        "sha256=dae115addd1e8b49a770f4de9cdef49cd11d4d431cacba6debb77a42c42ac650";
+
+    fn signed_request(
+        body: &'static [u8],
+        signature: &str,
+        delivery: &str,
+    ) -> actix_test::TestRequest {
+        actix_test::TestRequest::post()
+            .uri("/api/challenge")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("X-GitHub-Event", "issues"))
+            .insert_header(("X-GitHub-Delivery", delivery.to_string()))
+            .insert_header(("X-Hub-Signature-256", signature.to_string()))
+            .set_payload(body)
+    }
+
+    #[test]
+    fn recorded_payload_deserializes() {
+        let payload: GithubPayload = serde_json::from_slice(ISSUES_OPENED).unwrap();
+        assert_eq!(payload.action, "opened");
+        assert_eq!(payload.sender.login, "AbeFrato");
+        assert_eq!(payload.issue.number, 69);
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        assert!(verify_signature(SECRET, ISSUES_OPENED, Some(ISSUES_OPENED_SIGNATURE)).is_ok());
+        assert!(verify_signature(SECRET, ISSUES_LABELED, Some(ISSUES_LABELED_SIGNATURE)).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_signature() {
+        assert!(verify_signature(SECRET, ISSUES_OPENED, None).is_err());
+    }
+
+    #[test]
+    fn rejects_signature_for_other_payload() {
+        assert!(verify_signature(SECRET, ISSUES_OPENED, Some(ISSUES_LABELED_SIGNATURE)).is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_payload() {
+        let tampered = String::from_utf8(ISSUES_OPENED.to_vec())
+            .unwrap()
+            .replace("AbeFrato", "Hampfh");
+        assert!(
+            verify_signature(SECRET, tampered.as_bytes(), Some(ISSUES_OPENED_SIGNATURE)).is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_secret_and_malformed_header() {
+        assert!(verify_signature("wrong", ISSUES_OPENED, Some(ISSUES_OPENED_SIGNATURE)).is_err());
+        assert!(verify_signature(
+            SECRET,
+            ISSUES_OPENED,
+            Some(ISSUES_OPENED_SIGNATURE.trim_start_matches("sha256="))
+        )
+        .is_err());
+        assert!(verify_signature(SECRET, ISSUES_OPENED, Some("sha256=not-hex")).is_err());
+    }
+
+    #[actix_web::test]
+    async fn webhook_verifies_and_deduplicates_deliveries() {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(establish_test_connection()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        // Unsigned request
+        let req = actix_test::TestRequest::post()
+            .uri("/api/challenge")
+            .insert_header(("X-GitHub-Delivery", "unsigned"))
+            .set_payload(ISSUES_OPENED)
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        // Signature belonging to another payload
+        let req = signed_request(ISSUES_OPENED, ISSUES_LABELED_SIGNATURE, "forged").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        // Valid delivery is queued
+        let req = signed_request(ISSUES_OPENED, ISSUES_OPENED_SIGNATURE, "delivery-1").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+        // Redelivery of the same id is acknowledged but not queued again
+        let req = signed_request(ISSUES_OPENED, ISSUES_OPENED_SIGNATURE, "delivery-1").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = actix_test::read_body(res).await;
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("already been processed"));
+
+        // Signed but not an "opened" action
+        let req =
+            signed_request(ISSUES_LABELED, ISSUES_LABELED_SIGNATURE, "delivery-2").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,112 @@
+use super::webhook_schema::{GithubPayload, PullRequestPayload};
+
+pub(crate) const CHALLENGER_LABEL: &str = "challenger";
+/// Added to challenger issues whose submission has been retired
+pub(crate) const RETIRED_LABEL: &str = "retired";
+
+/// Commands the author of a challenger issue can post as a comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IssueCommand {
+    /// Play the placement matches again with the current submission
+    Rematch,
+    /// Remove the submission from the ladder
+    Retire,
+    /// Post the current rating and state of the submission
+    Status,
+    /// Replace the submission with the code currently in the issue
+    Resubmit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum WebhookAction {
+    /// A new challenger issue
+    Submit,
+    /// The code in an existing challenger issue should replace its submission
+    Resubmit,
+    Command(IssueCommand),
+    /// A comment that looks like a command but isn't one we know
+    UnknownCommand(String),
+    /// The bot in a pull request should be evaluated and reported as a check run
+    CheckPullRequest,
+    /// A pull request with a bot was merged, the bot joins the ladder
+    MergePullRequest,
+    Ignore(String),
+}
+
+pub(crate) fn command_usage() -> String {
+    "Available commands: `/status`, `/rematch`, `/resubmit`, `/retire`".to_string()
+}
+
+/// Decides what to do with a webhook delivery based on
+/// the X-GitHub-Event header and the payload action.
+pub(crate) fn classify_webhook(event: &str, payload: &GithubPayload) -> WebhookAction {
+    if !payload
+        .issue
+        .labels
+        .iter()
+        .any(|current| current.name == CHALLENGER_LABEL)
+    {
+        return WebhookAction::Ignore(format!(
+            "Issue must be marked with the \"{}\" label",
+            CHALLENGER_LABEL
+        ));
+    }
+
+    // Only the author of the issue may change its submission
+    let from_author = payload.sender.login == payload.issue.user.login;
+
+    match (event, payload.action.as_str()) {
+        ("issues", "opened") => WebhookAction::Submit,
+        ("issues", "edited") if from_author => match &payload.changes {
+            Some(changes) if changes.body.is_some() => WebhookAction::Resubmit,
+            _ => WebhookAction::Ignore("Only edits of the issue body are resubmitted".to_string()),
+        },
+        ("issues", "reopened") if from_author => WebhookAction::Resubmit,
+        ("issue_comment", "created") if from_author => {
+            let comment = match &payload.comment {
+                Some(comment) => comment,
+                None => return WebhookAction::Ignore("Missing comment".to_string()),
+            };
+            match parse_command(&comment.body) {
+                Some(Ok(command)) => WebhookAction::Command(command),
+                Some(Err(unknown)) => WebhookAction::UnknownCommand(unknown),
+                None => WebhookAction::Ignore("Comment is not a command".to_string()),
+            }
+        }
+        (_, action) => WebhookAction::Ignore(format!(
+            "Unsupported \"{}\" action \"{}\" from @{}",
+            event, action, payload.sender.login
+        )),
+    }
+}
+
+/// Decides what to do with a "pull_request" delivery. Every new
+/// commit is evaluated, merging is what submits the bot.
+pub(crate) fn classify_pull_request(payload: &PullRequestPayload) -> WebhookAction {
+    match payload.action.as_str() {
+        "opened" | "reopened" | "synchronize" => WebhookAction::CheckPullRequest,
+        "closed" if payload.pull_request.merged => WebhookAction::MergePullRequest,
+        action => WebhookAction::Ignore(format!(
+            "Unsupported \"pull_request\" action \"{}\" from @{}",
+            action, payload.sender.login
+        )),
+    }
+}
+
+/// A command is a comment starting with a slash, the first word
+/// decides the command and anything after it is ignored.
+/// Returns None if the comment isn't a command at all.
+pub(crate) fn parse_command(body: &str) -> Option<Result<IssueCommand, String>> {
+    let first_word = body.split_whitespace().next()?;
+    if !first_word.starts_with('/') {
+        return None;
+    }
+
+    Some(match first_word.to_lowercase().as_str() {
+        "/rematch" => Ok(IssueCommand::Rematch),
+        "/retire" => Ok(IssueCommand::Retire),
+        "/status" => Ok(IssueCommand::Status),
+        "/resubmit" => Ok(IssueCommand::Resubmit),
+        _ => Err(first_word.to_string()),
+    })
+}
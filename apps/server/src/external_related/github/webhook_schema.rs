@@ -1,11 +1,46 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+/// Payload of both "issues" and "issue_comment" events,
+/// `comment` is only present for the latter and `changes`
+/// only for "edited" actions.
 #[derive(Serialize, Deserialize)]
 pub struct GithubPayload {
     pub action: String,
     pub issue: Issue,
     pub repository: Repository,
     pub sender: User,
+    pub comment: Option<Comment>,
+    pub changes: Option<Changes>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Comment {
+    pub id: i64,
+    pub html_url: String,
+    pub body: String,
+    pub user: User,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Changes {
+    pub body: Option<ChangedValue>,
+    pub title: Option<ChangedValue>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChangedValue {
+    pub from: String,
+}
+
+/// GitHub sends null for empty strings in some fields
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 #[derive(Serialize, Deserialize)]
@@ -33,6 +68,7 @@ pub struct Issue {
     pub closed_at: Option<String>,
     pub author_association: String,
     //pub active_lock_reason: null,
+    #[serde(deserialize_with = "null_as_default")]
     pub body: String, // This is where the code will come from
     pub reactions: Reactions,
     pub timeline_url: String,
@@ -91,5 +127,37 @@ pub struct Label {
     pub name: String,
     pub color: String,
     pub default: bool,
+    #[serde(deserialize_with = "null_as_default")]
     pub description: String,
 }
+
+/// Payload of "pull_request" events, used when bots
+/// are submitted as files in a pull request
+#[derive(Serialize, Deserialize)]
+pub struct PullRequestPayload {
+    pub action: String,
+    pub number: i32,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+    pub sender: User,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PullRequest {
+    pub html_url: String,
+    pub number: i32,
+    pub state: String,
+    pub title: String,
+    pub user: User,
+    #[serde(default)]
+    pub merged: bool,
+    pub head: GitRef,
+    pub base: GitRef,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GitRef {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub sha: String,
+}
@@ -0,0 +1,54 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Verifies the X-Hub-Signature-256 header that GitHub attaches
+/// to every webhook delivery. The signature is an HMAC-SHA256
+/// of the raw request body keyed with the webhook secret.
+pub(crate) fn verify_signature(
+    secret: &str,
+    body: &[u8],
+    signature_header: Option<&str>,
+) -> Result<(), String> {
+    let signature_header = match signature_header {
+        Some(header) => header,
+        None => return Err("Missing X-Hub-Signature-256 header".to_string()),
+    };
+
+    let signature = match signature_header.strip_prefix(SIGNATURE_PREFIX) {
+        Some(signature) => signature,
+        None => return Err("Signature must be prefixed with \"sha256=\"".to_string()),
+    };
+
+    let signature = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Err("Signature is not valid hex".to_string()),
+    };
+
+    let mut mac = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(error) => return Err(format!("Invalid webhook secret: {}", error)),
+    };
+    mac.update(body);
+
+    // verify_slice compares in constant time
+    match mac.verify_slice(&signature) {
+        Ok(_) => Ok(()),
+        Err(_) => Err("Signature does not match payload".to_string()),
+    }
+}
+
+/// Secret that webhook deliveries are signed with, handed to
+/// the webhook route as app data. None if it isn't configured.
+#[derive(Debug, Clone)]
+pub(crate) struct WebhookSecret(pub Option<String>);
+
+impl WebhookSecret {
+    pub fn from_env() -> Self {
+        match std::env::var("GITHUB_WEBHOOK_SECRET") {
+            Ok(secret) if !secret.is_empty() => WebhookSecret(Some(secret)),
+            _ => WebhookSecret(None),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+use super::template_data::ReplayPageData;
+use super::templates::render;
+use crate::game::{
+    board::Tile,
+    evaluation::{evaluate_match, TurnEvaluation},
+    replay::{replay_from_boards, ReplayAction, ReplayFrame},
+};
+
+/// The replay as it is embedded in the page
+#[derive(Serialize)]
+struct ReplayData {
+    frames: Vec<FrameData>,
+}
+
+#[derive(Serialize)]
+struct FrameData {
+    caption: String,
+    /// start, move, jump or wall
+    action: &'static str,
+    /// The new wall or where the jump landed
+    highlight: Vec<i32>,
+    player_one: [i32; 2],
+    player_two: [i32; 2],
+    walls: Vec<[i32; 4]>,
+    walls_left: [i32; 2],
+    /// Shortest paths of player one and two
+    paths: [Vec<[i32; 2]>; 2],
+    /// Win probability of player one
+    evaluation: f32,
+    blunder: bool,
+}
+
+impl From<(&ReplayFrame, &TurnEvaluation)> for FrameData {
+    fn from((frame, evaluation): (&ReplayFrame, &TurnEvaluation)) -> Self {
+        let (action, highlight) = match &frame.action {
+            ReplayAction::Start => ("start", Vec::new()),
+            ReplayAction::Move { to, jump, .. } => {
+                (if *jump { "jump" } else { "move" }, vec![to.0, to.1])
+            }
+            ReplayAction::Wall { wall, .. } => ("wall", vec![wall.x1, wall.y1, wall.x2, wall.y2]),
+        };
+        let path = |player_one: bool| {
+            frame
+                .path(player_one)
+                .unwrap_or_default()
+                .iter()
+                .map(|pos| [pos.0, pos.1])
+                .collect()
+        };
+        FrameData {
+            caption: frame.caption(),
+            action,
+            highlight,
+            player_one: [frame.player_one.x, frame.player_one.y],
+            player_two: [frame.player_two.x, frame.player_two.y],
+            walls: frame
+                .walls
+                .iter()
+                .map(|wall| [wall.x1, wall.y1, wall.x2, wall.y2])
+                .collect(),
+            walls_left: [frame.player_one.wall_count, frame.player_two.wall_count],
+            paths: [path(true), path(false)],
+            evaluation: evaluation.win_probability,
+            blunder: evaluation.blunder,
+        }
+    }
+}
+
+/// A single html page that replays the match, with the replay
+/// embedded so it works when opened straight from the filesystem
+pub(crate) fn render_match_html(title: &str, description: &str, boards: &[Vec<Tile>]) -> String {
+    let frames = replay_from_boards(boards);
+    let data = ReplayData {
+        frames: frames
+            .iter()
+            .zip(evaluate_match(&frames).iter())
+            .map(FrameData::from)
+            .collect(),
+    };
+    // A closing script tag in a string would end the script early
+    let replay = serde_json::to_string(&data)
+        .expect("Could not serialize replay")
+        .replace("</", "<\\/");
+    render(
+        "replay.html",
+        &ReplayPageData {
+            title: escape_html(title),
+            description: escape_html(description),
+            replay,
+        },
+    )
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_match_html;
+    use crate::game::{board::populate_board, game::Wall, methods};
+
+    #[test]
+    fn embeds_the_replay() {
+        let mut game = methods::new(String::new());
+        let mut boards = Vec::new();
+        game.player_one.y = 7;
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        game.walls.push(Wall {
+            x1: 3,
+            y1: 6,
+            x2: 4,
+            y2: 6,
+        });
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+
+        let html = render_match_html("<script> vs bob", "alice won", &boards);
+        assert!(html.contains("<title>&lt;script&gt; vs bob</title>"));
+        assert!(html.contains("\"caption\":\"Turn 2: X places a wall at (3, 6) (4, 6)\""));
+        assert!(html.contains("\"action\":\"wall\",\"highlight\":[3,6,4,6]"));
+        assert!(html.contains("\"walls_left\":[10,9]"));
+        assert!(html.contains("\"blunder\":false"));
+        assert!(!html.contains("{{"));
+    }
+}
@@ -1,5 +1,12 @@
 pub(crate) mod code_unwrapper;
+pub(crate) mod forge_client;
 pub(crate) mod github;
+pub(crate) mod html_renderer;
 pub(crate) mod readme_factory;
+#[cfg(test)]
+pub(crate) mod recording_client;
 pub(crate) mod repo_updater;
-pub(crate) mod string_escape;
+pub(crate) mod site_generator;
+pub(crate) mod svg_renderer;
+pub(crate) mod template_data;
+pub(crate) mod templates;
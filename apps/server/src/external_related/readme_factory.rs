@@ -1,19 +1,29 @@
-use chrono::NaiveDateTime;
 use diesel::SqliteConnection;
 use gif::{Encoder, Frame, Repeat};
 
 use crate::backend;
+use crate::backend::models::bot_model::Bot;
 use crate::backend::models::match_model::Match;
 use crate::backend::models::submission_model::Submission;
 use crate::backend::models::turn_model::Turn;
 use crate::backend::models::user_model::User;
 use crate::game::board::{board_from_string, Tile};
+use crate::game::evaluation::evaluate_match;
 use crate::game::game::{GameResult, MAP_SIZE};
+use crate::game::replay::replay_from_boards;
+use crate::match_maker::leaderboard::bot_standings;
 
 use std::borrow::Cow;
 use std::fs::{self, File};
 
+use super::html_renderer::render_match_html;
 use super::repo_updater::get_issue_url;
+use super::svg_renderer::{render_match_svg, SvgLayout};
+use super::template_data::{
+    BlunderData, FeaturedMatch, LeaderboardEntry, MatchLogData, MatchLogEntry, MatchPageData,
+    MatchSide, ReadmeData, RoundData, SubmissionLogData, SubmissionLogEntry, VersionLink,
+};
+use super::templates::render;
 
 pub fn clear_match_dir() {
     if let Err(error) = std::fs::remove_dir_all("../../data") {
@@ -37,6 +47,7 @@ pub fn write_file(path: &str, data: String) -> std::io::Result<()> {
 pub fn generate_readme(
     players: Vec<User>,
     submissions: Vec<Submission>,
+    bots: Vec<Bot>,
     matches: Vec<Match>,
     turns: Vec<Turn>,
 ) -> String {
@@ -45,61 +56,35 @@ pub fn generate_readme(
 
     let image_scale: u16 = 50;
     let selected_matches = pick_front_page_matches(&matches, &turns);
-    let selection_count = selected_matches.len();
     render_matches_to_gif(&selected_matches, image_scale);
 
-    return format!(
-            "{}<br/>  <div align=\"center\">\n\n| {} | {} | {} |  \n| :--: | :--: | :--: |  \n|{}|{}|{}|  \n</div>{}{}{}",
-            get_readme_header(),
-            get_match_header(&selected_matches[0].0, &players, &submissions),
-            if selection_count > 1 { get_match_header(&selected_matches[1].0, &players, &submissions) } else { String::from("") },
-            if selection_count > 2 { get_match_header(&selected_matches[2].0, &players, &submissions) } else { String::from("") },
-            format!("<img style=\"margin: 10px\" src=\"{}/data/gifs/one.gif?raw=true\" width=\"{}\" height=\"{}\" />", url_prepend, 200, 200),
-            if selection_count > 1 { format!("<img style=\"margin: 10px\" src=\"{}/data/gifs/two.gif?raw=true\" width=\"{}\" height=\"{}\" />", url_prepend, 200, 200) } else { String::from("") },
-            if selection_count > 2 { format!("<img style=\"margin: 10px\" src=\"{}/data/gifs/three.gif?raw=true\" width=\"{}\" height=\"{}\" />", url_prepend, 200, 200) } else { String::from("") },
-    /*         get_last_turn_of_last_match(players.clone(), submissions.clone(), &matches, turns), */
-            generate_score_board(&submissions, &players),
-            format!(
-                "🕹 [Match log](./data/match_log.md) &#124; [Submission log](./data/submission_log.md) 🤖"
-            ),
-            credits(chrono::Local::now().naive_local())
-        );
-}
-
-fn get_readme_header() -> String {
-    return String::from(
-"<div align=\"center\">
-<img src=\"https://img.shields.io/badge/-BETA-yellow\"/>
-<img src=\"https://img.shields.io/github/issues-closed-raw/hampfh/hampfh/challenger?color=limegreen&label=Bots\"/>
-<img src=\"https://img.shields.io/badge/-lua-darkblue\">
-</div>
-
-<div align=\"center\"> 
-	<h3>🤖🧑‍💻🤖 <a href=\"https://github.com/Hampfh/Hampfh/issues/new?assignees=&labels=challenger&template=challenger-submission-template.md&title=%5BChallenger-submission%5D\">Create your challenger</a>  🤖🧑‍💻🤖</h3>
-</div>
-");
-}
+    let featured_matches = selected_matches
+        .iter()
+        .zip(["one", "two", "three"])
+        .filter_map(|((selected_match, _, _), gif_name)| {
+            let (winner, loser) = get_players_from_turn(selected_match, &players, &submissions)?;
+            Some(FeaturedMatch {
+                winner: winner.username,
+                loser: loser.username,
+                match_url: format!("./data/matches/{}.md", selected_match.id),
+                gif_url: format!("{}/data/gifs/{}.gif?raw=true", url_prepend, gif_name),
+            })
+        })
+        .collect();
 
-fn get_match_header(
-    selected_match: &Match,
-    players: &Vec<User>,
-    submissions: &Vec<Submission>,
-) -> String {
-    let m1_result = get_players_from_turn(&selected_match, &players, &submissions);
-    if let Some((user1, user2)) = m1_result {
-        return format!(
-            "<a href=\"{}\">{}</a> vs <a href=\"{}\">{}</a><br/>  <a href=\"{}\">Match</a>  ",
-            format!("https://github.com/{}", user1.username),
-            user1.username,
-            format!("https://github.com/{}", user2.username),
-            user2.username,
-            format!("./data/matches/{}.md", selected_match.id)
-        );
-    }
-    return String::new();
+    render(
+        "readme.md",
+        &ReadmeData {
+            featured_matches,
+            leaderboard: leaderboard_entries(&submissions, &bots, &players),
+            updated_at: chrono::Local::now()
+                .naive_local()
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        },
+    )
 }
 
-#[allow(dead_code)]
 fn get_players_from_turn(
     selected_match: &Match,
     players: &Vec<User>,
@@ -190,7 +175,12 @@ fn render_matches_to_gif(matches_to_render: &Vec<(Match, Vec<Turn>, i32)>, image
     }
 }
 
-fn render_match_gif(match_to_render: Match, turns: &Vec<Turn>, render_path: String, scale: u16) {
+pub(crate) fn render_match_gif(
+    match_to_render: Match,
+    turns: &Vec<Turn>,
+    render_path: String,
+    scale: u16,
+) {
     let last_match_turns = turns
         .iter()
         .filter(|turn| turn.match_id == match_to_render.id)
@@ -218,20 +208,19 @@ fn get_string_from_tile(tile: Tile) -> String {
 
 #[allow(dead_code)]
 fn generate_board(board: Vec<Tile>) -> String {
-    let mut output = String::from("\n<div align=\"center\">\n");
+    format!("\n<div align=\"center\">\n{}</div>\n", board_tiles(board))
+}
 
-    let mut count = 1;
-    for tile in board {
+/// The board as emoji, with a break after every row
+fn board_tiles(board: Vec<Tile>) -> String {
+    let mut output = String::new();
+    for (count, tile) in board.into_iter().enumerate() {
         output.push_str(&get_string_from_tile(tile));
-        if count % 9 == 0 {
+        if (count + 1) % MAP_SIZE as usize == 0 {
             output.push_str("<br>");
         }
-        count += 1;
     }
-
-    output.push_str("</div>\n");
-
-    return output;
+    output
 }
 
 pub(crate) fn build_match_log_wrapper() {
@@ -246,36 +235,43 @@ pub(crate) fn build_match_log_wrapper() {
 }
 
 fn create_match_log(conn: &SqliteConnection, matches: &Vec<Match>) -> String {
-    let mut output = format!("<div align=\"center\">\n\n# Matches\n");
     let mut matches = matches.clone();
     matches.reverse();
-    // Get matches
-    for current in matches {
-        let result = current.players(&conn);
-        if result.is_none() {
-            continue;
-        }
-        let ((winner, winner_submission), (loser, loser_submission)) = result.unwrap();
-        output.push_str(&format!(
-            "<p>\n\n{} vs {}</p>\n<p>@{} vs @{}</p>\n<p><a href=\"./matches/{}.md\">Match</a></p>\n<p>{}</p>\n\n---\n",
-            format!(
-                "[{}]({})",
-                winner_submission.id,
-                get_issue_url(winner_submission.issue_number)
-            ),
-            format!(
-                "[{}]({})",
-                loser_submission.id,
-                get_issue_url(loser_submission.issue_number)
-            ),
-            winner.username,
-            loser.username,
-            current.id,
-            current.created_at.format("%Y-%m-%d %H:%M:%S")
-        ));
-    }
-    output.push_str("</div>\n");
-    return output;
+    let matches = matches
+        .iter()
+        .filter_map(|current| {
+            let (winner, loser) = current.players(&conn)?;
+            let (winner, loser) = match_sides(current, winner, loser);
+            Some(MatchLogEntry {
+                id: current.id.clone(),
+                winner,
+                loser,
+                page_url: format!("./matches/{}.md", current.id),
+                created_at: current.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            })
+        })
+        .collect();
+    render("match_log.md", &MatchLogData { matches })
+}
+
+/// The winner and the loser of a match with their colors
+fn match_sides(
+    target_match: &Match,
+    winner: (User, Submission),
+    loser: (User, Submission),
+) -> (MatchSide, MatchSide) {
+    let (winner_color, loser_color) = if target_match.p1_is_winner == 1 {
+        ("🟩", "🟥")
+    } else {
+        ("🟥", "🟩")
+    };
+    let side = |(user, submission): (User, Submission), color: &str| MatchSide {
+        username: user.username,
+        issue_url: get_issue_url(submission.issue_number),
+        submission_id: submission.id,
+        color: color.to_string(),
+    };
+    (side(winner, winner_color), side(loser, loser_color))
 }
 
 pub(crate) fn build_submission_log_wrapper() {
@@ -289,87 +285,98 @@ pub(crate) fn build_submission_log_wrapper() {
     }
 }
 fn create_submission_log(conn: &SqliteConnection, submissions: &Vec<Submission>) -> String {
-    let mut output = format!("<div align=\"center\">\n\n# Submissions\n");
-
     let mut submissions = submissions.clone();
     submissions.sort_by(|a, b| (a.mmr.round() as i32).cmp(&(b.mmr.round() as i32)));
     submissions.reverse();
 
-    // Get submissions
-    for current in submissions {
-        let user = User::by_id(&current.user, &conn);
-        if user.is_none() {
-            continue;
-        };
-        output.push_str(&format!(
-            "<p>MMR: {} &#124; @{} &#124; <a href=\"{}\">{}</a> {} &#124; {}</p>  \n",
-            current.mmr.round(),
-            user.unwrap().username,
-            current.issue_url,
-            current.id,
-            if current.disqualified >= 1 {
-                "❌"
+    let submissions = submissions
+        .into_iter()
+        .filter_map(|current| {
+            let user = User::by_id(&current.user, &conn)?;
+            let (status, status_icon) = if current.disqualified >= 1 {
+                ("disqualified", "❌")
+            } else if current.retired >= 1 {
+                ("retired", "💤")
             } else {
-                "✅"
-            },
-            current.created_at.format("%Y-%m-%d %H:%M")
-        ));
-    }
-    output.push_str("</div>");
-    return output;
+                ("active", "✅")
+            };
+            Some(SubmissionLogEntry {
+                mmr: current.mmr.round() as i64,
+                username: user.username,
+                bot_name: current
+                    .bot
+                    .as_ref()
+                    .and_then(|bot_id| Bot::by_id(bot_id, conn))
+                    .map(|bot| bot.name)
+                    .unwrap_or_default(),
+                version: current.version,
+                status: status.to_string(),
+                status_icon: status_icon.to_string(),
+                created_at: current.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                issue_url: current.issue_url,
+                id: current.id,
+            })
+        })
+        .collect();
+    render("submission_log.md", &SubmissionLogData { submissions })
 }
 
-fn generate_score_board(submissions: &Vec<Submission>, players: &Vec<User>) -> String {
-    if submissions.len() <= 0 || players.len() <= 0 {
-        return format!("");
+fn leaderboard_entries(
+    submissions: &[Submission],
+    bots: &[Bot],
+    players: &[User],
+) -> Vec<LeaderboardEntry> {
+    if players.is_empty() {
+        return Vec::new();
     }
 
-    let mut sorted_submissions = submissions.clone();
-    sorted_submissions.sort_by(|a, b| (b.mmr.round() as i32).cmp(&(a.mmr.round() as i32)));
-    sorted_submissions = sorted_submissions
-        .into_iter()
-        .filter(|current| current.disqualified == 0)
-        .collect();
-
-    let mut output = format!(
-        "<div align=\"center\">\n\n| MMR | (Top 10) | Submission  |\n| :-- | --: | :--: |\n"
-    );
-
     // Limit to only top 10
-    for i in 0..std::cmp::min(10, sorted_submissions.len()) {
-        let user = players
-            .iter()
-            .find(|current| current.id == sorted_submissions[i].user);
-        output.push_str(&format!(
-            "| {} | {} | [Submission]({}) {} |\n",
-            sorted_submissions[i].mmr.round().to_string(),
-            match user {
-                Some(user) => user.username.clone(),
-                None => format!("<Unknown>"),
-            },
-            sorted_submissions[i].issue_url,
-            if sorted_submissions[i].disqualified >= 1 {
-                "❌"
-            } else {
-                "✅"
-            }
-        ))
-    }
-    output.push_str("\n");
-    return output;
+    bot_standings(bots, submissions)
+        .iter()
+        .take(10)
+        .map(|standing| LeaderboardEntry {
+            mmr: standing.current.mmr.round() as i64,
+            username: players
+                .iter()
+                .find(|current| current.id == standing.bot.user)
+                .map(|user| user.username.clone())
+                .unwrap_or("<Unknown>".to_string()),
+            bot_name: standing.bot.name.replace('|', "&#124;"),
+            issue_url: standing.current.issue_url.clone(),
+            versions: standing
+                .versions
+                .iter()
+                .map(|version| VersionLink {
+                    version: version.version,
+                    issue_url: version.issue_url.clone(),
+                    current: version.id == standing.current.id,
+                })
+                .collect(),
+            rating_delta: standing
+                .rating_delta()
+                .map(|delta| format!("{:+}", delta.round())),
+        })
+        .collect()
 }
 
 pub fn build_match_files_wrapper() {
     let conn = backend::db::establish_connection().get().unwrap();
     build_match_files(&conn, Match::list(&conn));
 }
-fn build_match_files(conn: &SqliteConnection, matches: Vec<Match>) {
+pub(crate) fn build_match_files(conn: &SqliteConnection, matches: Vec<Match>) {
+    if let Err(error) = fs::create_dir_all("../../data/svgs") {
+        println!("Could not create data/svgs dir, reason: {}", error);
+    }
     for current in matches {
-        let build_result = build_match_with_players(
-            Match::get_players(&current.id, &conn),
-            Match::get_turns(&current.id, &conn),
-            &current,
-        );
+        let players = Match::get_players(&current.id, &conn);
+        let turns = Match::get_turns(&current.id, &conn);
+        if let Some(turns) = turns.as_ref() {
+            let users = players
+                .as_ref()
+                .map(|((winner, _), (loser, _))| (winner, loser));
+            write_replay_files(&current, users, turns);
+        }
+        let build_result = build_match_with_players(players, turns, &current);
         match match build_result {
             Some(file) => write_file(&format!("../../data/matches/{}.md", current.id), file),
             None => Ok(()),
@@ -380,51 +387,102 @@ fn build_match_files(conn: &SqliteConnection, matches: Vec<Match>) {
     }
 }
 
+/// Writes the svg and the html replay of a match next to its markdown
+fn write_replay_files(target_match: &Match, users: Option<(&User, &User)>, turns: &[Turn]) {
+    let boards: Vec<Vec<Tile>> = turns
+        .iter()
+        .map(|turn| board_from_string(turn.board.clone()))
+        .collect();
+    if let Err(error) = write_file(
+        &format!("../../data/svgs/{}.svg", target_match.id),
+        render_match_svg(&boards, SvgLayout::Animated),
+    ) {
+        println!(
+            "Could not write svg of match {}, reason: {}",
+            target_match.id, error
+        );
+    }
+
+    let (title, mut description) = match users {
+        Some((winner, loser)) => {
+            let (p1, p2) = if target_match.p1_is_winner == 1 {
+                (winner, loser)
+            } else {
+                (loser, winner)
+            };
+            (
+                format!("{} (O) vs {} (X)", p1.username, p2.username),
+                format!("Winner: {}", winner.username),
+            )
+        }
+        None => (format!("Match {}", target_match.id), String::new()),
+    };
+    if let Some(error) = target_match.match_error.as_ref() {
+        description.push_str(&format!(", {}", error));
+    }
+    if let Err(error) = write_file(
+        &format!("../../data/matches/{}.html", target_match.id),
+        render_match_html(&title, &description, &boards),
+    ) {
+        println!(
+            "Could not write replay of match {}, reason: {}",
+            target_match.id, error
+        );
+    }
+}
+
 fn build_match_with_players(
     player_result: Option<((User, Submission), (User, Submission))>,
     turns_result: Option<Vec<Turn>>,
     target_match: &Match,
 ) -> Option<String> {
-    if player_result.is_none() {
-        return None;
-    }
-    let ((winner, win_sub), (loser, los_sub)) = player_result.unwrap();
+    let (winner, loser) = player_result?;
+    let (winner, loser) = match_sides(target_match, winner, loser);
 
-    if turns_result.is_none() {
-        return None;
-    }
-    let p1_is_winner = target_match.p1_is_winner;
-    let (winner_color, loser_color) = if p1_is_winner == 1 {
-        ("🟩", "🟥")
-    } else {
-        ("🟥", "🟩")
-    };
-
-    let mut file = format!(
-        "<div align=\"center\"><h1>{} vs {}</h1><p><a href=\"{}\">{} {}</a> vs <a href=\"{}\">{} {}</a></p>\n<p>Winner: {}</p></div>\n\n---\n",
-        winner.username, loser.username, win_sub.issue_url, winner_color, win_sub.id, los_sub.issue_url, los_sub.id, loser_color, winner_color,
-    );
+    let boards: Vec<Vec<Tile>> = turns_result?
+        .iter()
+        .map(|turn| board_from_string(turn.board.clone()))
+        .collect();
+    let evaluation = evaluate_match(&replay_from_boards(&boards));
+    let rounds = boards
+        .into_iter()
+        .enumerate()
+        .map(|(index, board)| RoundData {
+            number: index + 1,
+            board: board_tiles(board),
+        })
+        .collect();
 
-    if target_match.match_error.is_some() {
-        file.push_str(&format!(
-            "<div align=\"center\"><p>{}</p></div>\n\n",
-            target_match.match_error.as_ref().unwrap()
-        ));
-    }
-    file.push_str(
-        &get_match_from_tiles(
-            turns_result
-                .unwrap()
+    Some(render(
+        "match.md",
+        &MatchPageData {
+            id: target_match.id.clone(),
+            winner,
+            loser,
+            error: target_match.match_error.clone(),
+            svg_url: format!("../svgs/{}.svg", target_match.id),
+            replay_url: format!("{}.html", target_match.id),
+            rounds,
+            turn_count: evaluation.len() - 1,
+            evaluation: evaluation
                 .iter()
-                .map(|turn| board_from_string(turn.board.clone()))
+                .map(|turn| (turn.win_probability * 100.0).round() as u32)
                 .collect(),
-        )
-        .as_str(),
-    );
-
-    return Some(file);
+            blunders: evaluation
+                .iter()
+                .filter(|turn| turn.blunder)
+                .map(|turn| BlunderData {
+                    turn: turn.turn,
+                    // Player one, 🟩, moves on odd turns
+                    color: if turn.turn % 2 == 1 { "🟩" } else { "🟥" }.to_string(),
+                })
+                .collect(),
+        },
+    ))
 }
 
+/// Used for the test dumps
+#[allow(dead_code)]
 pub(crate) fn get_match_from_tiles(turns: Vec<Vec<Tile>>) -> String {
     let mut output = String::new();
     let mut round = 1;
@@ -547,15 +605,3 @@ pub(crate) fn create_and_encode_file(
         encoder.write_frame(&frame).unwrap();
     }
 }
-
-fn credits(last_updated: NaiveDateTime) -> String {
-    let spacing = format!("&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;");
-    return format!(
-        "<br/><div align=\"center\"><a href=\"https://www.craft.do/s/geS8o08lvJ4cfD\">What is this? </a> {}&#124;{} Hampus Hallkvist {}&#124;{} {}</div>",
-        spacing,
-        spacing,
-        spacing,
-        spacing,
-        &last_updated.format("%Y-%m-%d %H:%M")
-    );
-}
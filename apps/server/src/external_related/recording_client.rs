@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use diesel::SqliteConnection;
+
+use super::forge_client::{ChangedFile, CheckRunReport, CloseType, ForgeClient, ForgeError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ForgeCall {
+    Comment {
+        issue_number: i32,
+        body: String,
+    },
+    Close {
+        issue_number: i32,
+        state: CloseType,
+    },
+    AddLabel {
+        issue_number: i32,
+        label: String,
+    },
+    RemoveLabel {
+        issue_number: i32,
+        label: String,
+    },
+    Publish,
+    CreateCheckRun {
+        head_sha: String,
+        name: String,
+    },
+    CompleteCheckRun {
+        check_run_id: i64,
+        report: CheckRunReport,
+    },
+}
+
+/// In-memory forge for tests, every call is recorded in order
+/// and nothing is sent anywhere or written to disk.
+#[derive(Default)]
+pub(crate) struct RecordingClient {
+    calls: Mutex<Vec<ForgeCall>>,
+    pull_request_files: Mutex<HashMap<i32, Vec<ChangedFile>>>,
+    /// File contents by path, the same for every ref
+    files: Mutex<HashMap<String, String>>,
+    /// Makes the next publish panic, like a failing git push would
+    panic_on_publish: Mutex<bool>,
+    /// Makes the next comment fail, like an unreachable api would
+    fail_comment: Mutex<bool>,
+}
+
+impl RecordingClient {
+    pub fn new() -> Self {
+        RecordingClient::default()
+    }
+
+    /// Adds a file to the changes of a pull request, removed files have no content
+    pub fn add_pull_request_file(
+        &self,
+        pull_number: i32,
+        filename: &str,
+        status: &str,
+        content: Option<&str>,
+    ) {
+        self.pull_request_files
+            .lock()
+            .unwrap()
+            .entry(pull_number)
+            .or_default()
+            .push(ChangedFile {
+                filename: filename.to_string(),
+                status: status.to_string(),
+            });
+        if let Some(content) = content {
+            self.set_file_content(filename, content);
+        }
+    }
+
+    pub fn set_file_content(&self, path: &str, content: &str) {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), content.to_string());
+    }
+
+    pub fn panic_on_next_publish(&self) {
+        *self.panic_on_publish.lock().unwrap() = true;
+    }
+
+    pub fn fail_next_comment(&self) {
+        *self.fail_comment.lock().unwrap() = true;
+    }
+
+    pub fn calls(&self) -> Vec<ForgeCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Returns the recorded calls and starts over with an empty record
+    pub fn take_calls(&self) -> Vec<ForgeCall> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+
+    pub fn comments(&self, issue_number: i32) -> Vec<String> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                ForgeCall::Comment {
+                    issue_number: current,
+                    body,
+                } if current == issue_number => Some(body),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The latest state the issue was closed with, None if it's still open
+    pub fn issue_state(&self, issue_number: i32) -> Option<CloseType> {
+        self.calls().into_iter().rev().find_map(|call| match call {
+            ForgeCall::Close {
+                issue_number: current,
+                state,
+            } if current == issue_number => Some(state),
+            _ => None,
+        })
+    }
+
+    fn record(&self, call: ForgeCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl ForgeClient for RecordingClient {
+    fn create_issue_comment(&self, issue_number: i32, msg: &str) -> Result<(), ForgeError> {
+        if std::mem::take(&mut *self.fail_comment.lock().unwrap()) {
+            return Err(ForgeError(format!(
+                "Could not post issue comment for {}",
+                issue_number
+            )));
+        }
+        self.record(ForgeCall::Comment {
+            issue_number,
+            body: msg.to_string(),
+        });
+        Ok(())
+    }
+
+    fn close_issue(&self, state: CloseType, issue_number: i32) -> Result<(), ForgeError> {
+        self.record(ForgeCall::Close {
+            issue_number,
+            state,
+        });
+        Ok(())
+    }
+
+    fn add_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        self.record(ForgeCall::AddLabel {
+            issue_number,
+            label: label.to_string(),
+        });
+        Ok(())
+    }
+
+    fn remove_label(&self, issue_number: i32, label: &str) -> Result<(), ForgeError> {
+        self.record(ForgeCall::RemoveLabel {
+            issue_number,
+            label: label.to_string(),
+        });
+        Ok(())
+    }
+
+    fn publish_repo(&self, _conn: &SqliteConnection) -> Result<String, ForgeError> {
+        if std::mem::take(&mut *self.panic_on_publish.lock().unwrap()) {
+            panic!("Could not push the repository");
+        }
+        self.record(ForgeCall::Publish);
+        Ok("Recorded publish".to_string())
+    }
+
+    fn pull_request_files(&self, pull_number: i32) -> Result<Vec<ChangedFile>, ForgeError> {
+        Ok(self
+            .pull_request_files
+            .lock()
+            .unwrap()
+            .get(&pull_number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn file_content(&self, path: &str, _git_ref: &str) -> Result<String, ForgeError> {
+        match self.files.lock().unwrap().get(path) {
+            Some(content) => Ok(content.clone()),
+            None => Err(ForgeError(format!("{} does not exist", path))),
+        }
+    }
+
+    fn create_check_run(&self, head_sha: &str, name: &str) -> Result<i64, ForgeError> {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push(ForgeCall::CreateCheckRun {
+            head_sha: head_sha.to_string(),
+            name: name.to_string(),
+        });
+        // Ids are just the position of the call
+        Ok(calls.len() as i64)
+    }
+
+    fn complete_check_run(
+        &self,
+        check_run_id: i64,
+        report: &CheckRunReport,
+    ) -> Result<(), ForgeError> {
+        self.record(ForgeCall::CompleteCheckRun {
+            check_run_id,
+            report: report.clone(),
+        });
+        Ok(())
+    }
+}
@@ -8,6 +8,11 @@ pub fn is_plagiarism_enabled() -> bool {
     return std::env::var("PLAGIARISM_CHECK").unwrap() == "true";
 }
 
+/// Bots can be submitted as files in pull requests
+pub fn is_pull_request_submission_enabled() -> bool {
+    std::env::var("PULL_REQUEST_SUBMISSIONS").unwrap_or_default() == "true"
+}
+
 pub(crate) fn get_issue_url(issue_number: i32) -> String {
     format!(
         "https://github.com/{}/{}/issues/{}",
@@ -18,11 +23,6 @@ pub(crate) fn get_issue_url(issue_number: i32) -> String {
 }
 
 pub fn reset_to_master_branch() {
-    if !is_live() {
-        println!("[OFFLINE] Skipping reset_to_master_branch");
-        return;
-    }
-
     /*
        ! This action is very important since what we're doing
        ! here is destructive to the git history. Hence we need
@@ -44,11 +44,6 @@ pub fn reset_to_master_branch() {
 
 /// This function will push changes to the repository.
 pub fn update_repo(commit_msg: String) {
-    if !is_live() {
-        println!("[OFFLINE] Skipping update_repo");
-        return;
-    }
-
     Command::new("git")
         .arg("add")
         .arg("-A")
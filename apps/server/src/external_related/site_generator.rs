@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use diesel::SqliteConnection;
+
+use crate::analytics::user_stats::user_stats;
+use crate::backend::models::{
+    bot_model::Bot, match_model::Match, submission_model::Submission, turn_model::Turn,
+    user_model::User,
+};
+use crate::game::board::{board_from_string, Tile};
+use crate::match_maker::leaderboard::bot_standings;
+
+use super::html_renderer::{escape_html, render_match_html};
+use super::template_data::{
+    Pagination, SearchEntry, SiteBot, SiteLeaderboardData, SiteMatch, SiteMatchesData,
+    SiteOpponent, SitePlayer, SiteStanding, SiteSubmissionData, SiteSubmissionMatch, SiteUserData,
+    SiteVersion,
+};
+use super::templates::render;
+
+/// Where the site is written to and served from
+pub(crate) const SITE_DIR: &str = "../../site";
+/// Written with every site, a directory without it is not cleared
+const SITE_MARKER: &str = "search.json";
+const PAGE_SIZE: usize = 25;
+const RECENT_MATCHES: usize = 20;
+
+/// Rebuilds the site from the database, returns the number of files written
+pub(crate) fn generate_site(conn: &SqliteConnection, out_dir: &str) -> Result<usize, String> {
+    let files = site_files(
+        &User::list(conn),
+        &Submission::list(conn),
+        &Bot::list(conn),
+        &Match::list(conn),
+        &Turn::list(conn),
+    );
+
+    clear_site(out_dir)?;
+    for (path, content) in files.iter() {
+        let path = Path::new(out_dir).join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("Could not create {}: {}", parent.display(), error))?;
+        }
+        fs::write(&path, content)
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))?;
+    }
+    Ok(files.len())
+}
+
+/// Removes a previously generated site, refuses to touch
+/// directories that hold anything else
+fn clear_site(out_dir: &str) -> Result<(), String> {
+    let dir = Path::new(out_dir);
+    if !dir.exists() {
+        return Ok(());
+    }
+    let is_empty = fs::read_dir(dir)
+        .map_err(|error| format!("Could not read {}: {}", out_dir, error))?
+        .next()
+        .is_none();
+    if !is_empty && !dir.join(SITE_MARKER).is_file() {
+        return Err(format!(
+            "{} is not empty and does not contain a generated site, refusing to clear it",
+            out_dir
+        ));
+    }
+    fs::remove_dir_all(dir).map_err(|error| format!("Could not clear {}: {}", out_dir, error))
+}
+
+/// Every file of the site as a path relative to the site root and its content
+pub(crate) fn site_files(
+    users: &[User],
+    submissions: &[Submission],
+    bots: &[Bot],
+    matches: &[Match],
+    turns: &[Turn],
+) -> Vec<(String, String)> {
+    let site = Site::new(users, submissions, bots);
+    let mut files = Vec::new();
+
+    let standings = bot_standings(bots, submissions);
+    let standing_pages = paginate(&standings);
+    for (index, page) in standing_pages.iter().enumerate() {
+        let (path, root) = leaderboard_path(index + 1);
+        let standings = page
+            .iter()
+            .enumerate()
+            .map(|(rank, standing)| SiteStanding {
+                rank: index * PAGE_SIZE + rank + 1,
+                mmr: standing.current.mmr.round() as i64,
+                username: site.username(&standing.bot.user),
+                user_url: site.user_url(&standing.bot.user),
+                bot_name: escape_html(&standing.bot.name),
+                submission_url: submission_url(&standing.current.id),
+                version: standing.current.version,
+                wins: standing.current.wins,
+                matches_played: standing.current.matches_played,
+            })
+            .collect();
+        let data = SiteLeaderboardData {
+            title: "Leaderboard".to_string(),
+            pagination: pagination(index + 1, standing_pages.len(), &root, |page| {
+                leaderboard_path(page).0
+            }),
+            root,
+            standings,
+        };
+        files.push((path, render("site_leaderboard.html", &data)));
+    }
+
+    let mut newest_first = matches.to_vec();
+    newest_first.sort_by_key(|current| std::cmp::Reverse(current.created_at));
+    let match_pages = paginate(&newest_first);
+    for (index, page) in match_pages.iter().enumerate() {
+        let root = "../".to_string();
+        let matches = page
+            .iter()
+            .map(|current| SiteMatch {
+                winner: site.player(&current.winner),
+                loser: site.player(&current.loser),
+                replay_url: replay_url(&current.id),
+                error: current.match_error.as_deref().map(escape_html),
+                created_at: current.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            })
+            .collect();
+        let data = SiteMatchesData {
+            title: "Matches".to_string(),
+            pagination: pagination(index + 1, match_pages.len(), &root, |page| {
+                format!("matches/{}.html", page)
+            }),
+            root,
+            matches,
+        };
+        files.push((
+            format!("matches/{}.html", index + 1),
+            render("site_matches.html", &data),
+        ));
+    }
+
+    for user in users {
+        let stats = user_stats(user, users, submissions, bots, matches, turns);
+        let data = SiteUserData {
+            title: escape_html(&user.username),
+            root: "../".to_string(),
+            username: escape_html(&user.username),
+            bots: bots
+                .iter()
+                .filter(|bot| bot.user == user.id)
+                .map(|bot| SiteBot {
+                    name: escape_html(&bot.name),
+                    versions: site.versions(&bot.id, None),
+                })
+                .collect(),
+            best_rating: stats.best_rating.map(|mmr| mmr.round() as i64),
+            wins: stats.wins,
+            losses: stats.losses,
+            average_game_length: stats
+                .average_game_length
+                .map(|length| format!("{:.1}", length)),
+            average_walls_placed: stats
+                .average_walls_placed
+                .map(|walls| format!("{:.1}", walls)),
+            opponents: stats
+                .opponents
+                .iter()
+                .map(|record| SiteOpponent {
+                    username: escape_html(&record.username),
+                    user_url: site.user_url_by_name(&record.username),
+                    wins: record.wins,
+                    losses: record.losses,
+                })
+                .collect(),
+            recent_matches: stats
+                .recent_matches
+                .iter()
+                .map(|recent| SiteSubmissionMatch {
+                    won: recent.won,
+                    opponent: site.player(&recent.opponent_submission),
+                    replay_url: replay_url(&recent.id),
+                    created_at: recent.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                })
+                .collect(),
+        };
+        files.push((site.user_url(&user.id), render("site_user.html", &data)));
+    }
+
+    for submission in submissions {
+        let recent_matches = newest_first
+            .iter()
+            .filter(|current| current.winner == submission.id || current.loser == submission.id)
+            .take(RECENT_MATCHES)
+            .map(|current| {
+                let won = current.winner == submission.id;
+                SiteSubmissionMatch {
+                    won,
+                    opponent: site.player(if won { &current.loser } else { &current.winner }),
+                    replay_url: replay_url(&current.id),
+                    created_at: current.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                }
+            })
+            .collect();
+        let data = SiteSubmissionData {
+            title: format!(
+                "{} v{}",
+                site.player(&submission.id).bot_name,
+                submission.version
+            ),
+            root: "../".to_string(),
+            username: site.username(&submission.user),
+            user_url: site.user_url(&submission.user),
+            issue_url: submission.issue_url.clone(),
+            status: submission.status().to_string(),
+            mmr: submission.mmr.round() as i64,
+            wins: submission.wins,
+            matches_played: submission.matches_played,
+            rating_history: match submission.bot.as_ref() {
+                Some(bot_id) => site.versions(bot_id, Some(&submission.id)),
+                None => Vec::new(),
+            },
+            recent_matches,
+        };
+        files.push((
+            submission_url(&submission.id),
+            render("site_submission.html", &data),
+        ));
+    }
+
+    let mut boards: HashMap<&str, Vec<&Turn>> = HashMap::new();
+    for turn in turns {
+        boards.entry(turn.match_id.as_str()).or_default().push(turn);
+    }
+    for current in matches {
+        let mut match_turns = boards.remove(current.id.as_str()).unwrap_or_default();
+        match_turns.sort_by_key(|turn| turn.turn);
+        let tiles: Vec<Vec<Tile>> = match_turns
+            .iter()
+            .map(|turn| board_from_string(turn.board.clone()))
+            .collect();
+        // The names are escaped by the replay page itself
+        let winner = site.raw_username(&current.winner);
+        let loser = site.raw_username(&current.loser);
+        let mut description = format!("Winner: {}", winner);
+        if let Some(error) = current.match_error.as_ref() {
+            description.push_str(&format!(", {}", error));
+        }
+        files.push((
+            replay_url(&current.id),
+            render_match_html(&format!("{} vs {}", winner, loser), &description, &tiles),
+        ));
+    }
+
+    let mut index: Vec<SearchEntry> = users
+        .iter()
+        .map(|user| SearchEntry {
+            kind: "user".to_string(),
+            name: user.username.clone(),
+            url: site.user_url(&user.id),
+        })
+        .collect();
+    index.extend(standings.iter().map(|standing| SearchEntry {
+        kind: "bot".to_string(),
+        name: standing.bot.name.clone(),
+        url: submission_url(&standing.current.id),
+    }));
+    files.push((
+        "search.json".to_string(),
+        serde_json::to_string(&index).expect("Could not serialize search index"),
+    ));
+
+    files
+}
+
+/// Lookups shared by the pages
+struct Site<'a> {
+    users: HashMap<&'a str, &'a User>,
+    submissions: HashMap<&'a str, &'a Submission>,
+    bots: HashMap<&'a str, &'a Bot>,
+    all_submissions: &'a [Submission],
+}
+impl<'a> Site<'a> {
+    fn new(users: &'a [User], submissions: &'a [Submission], bots: &'a [Bot]) -> Self {
+        Site {
+            users: users.iter().map(|user| (user.id.as_str(), user)).collect(),
+            submissions: submissions
+                .iter()
+                .map(|submission| (submission.id.as_str(), submission))
+                .collect(),
+            bots: bots.iter().map(|bot| (bot.id.as_str(), bot)).collect(),
+            all_submissions: submissions,
+        }
+    }
+
+    fn username(&self, user_id: &str) -> String {
+        escape_html(
+            self.users
+                .get(user_id)
+                .map(|user| user.username.as_str())
+                .unwrap_or("<Unknown>"),
+        )
+    }
+
+    fn user_url(&self, user_id: &str) -> String {
+        match self.users.get(user_id) {
+            Some(user) => format!("users/{}.html", user.username),
+            None => "index.html".to_string(),
+        }
+    }
+
+    fn user_url_by_name(&self, username: &str) -> String {
+        match self.users.values().find(|user| user.username == username) {
+            Some(user) => self.user_url(&user.id),
+            None => "index.html".to_string(),
+        }
+    }
+
+    /// The username behind a submission, unescaped
+    fn raw_username(&self, submission_id: &str) -> String {
+        self.submissions
+            .get(submission_id)
+            .and_then(|submission| self.users.get(submission.user.as_str()))
+            .map(|user| user.username.clone())
+            .unwrap_or("<Unknown>".to_string())
+    }
+
+    fn player(&self, submission_id: &str) -> SitePlayer {
+        let submission = self.submissions.get(submission_id);
+        SitePlayer {
+            username: escape_html(&self.raw_username(submission_id)),
+            user_url: submission
+                .map(|submission| self.user_url(&submission.user))
+                .unwrap_or("index.html".to_string()),
+            bot_name: escape_html(
+                submission
+                    .and_then(|submission| submission.bot.as_deref())
+                    .and_then(|bot_id| self.bots.get(bot_id))
+                    .map(|bot| bot.name.as_str())
+                    .unwrap_or("Bot"),
+            ),
+            submission_url: submission_url(submission_id),
+        }
+    }
+
+    /// Every version of a bot, oldest first
+    fn versions(&self, bot_id: &str, current: Option<&str>) -> Vec<SiteVersion> {
+        let mut versions: Vec<&Submission> = self
+            .all_submissions
+            .iter()
+            .filter(|submission| submission.bot.as_deref() == Some(bot_id))
+            .collect();
+        versions.sort_by_key(|submission| submission.version);
+        versions
+            .into_iter()
+            .map(|submission| SiteVersion {
+                version: submission.version,
+                url: submission_url(&submission.id),
+                mmr: submission.mmr.round() as i64,
+                wins: submission.wins,
+                matches_played: submission.matches_played,
+                status: submission.status().to_string(),
+                created_at: submission.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                current: current == Some(submission.id.as_str()),
+            })
+            .collect()
+    }
+}
+
+fn submission_url(submission_id: &str) -> String {
+    format!("submissions/{}.html", submission_id)
+}
+
+fn replay_url(match_id: &str) -> String {
+    format!("replays/{}.html", match_id)
+}
+
+/// The first page of the leaderboard is the front page of the site
+fn leaderboard_path(page: usize) -> (String, String) {
+    if page == 1 {
+        ("index.html".to_string(), String::new())
+    } else {
+        (format!("leaderboard/{}.html", page), "../".to_string())
+    }
+}
+
+/// Splits into pages of PAGE_SIZE, there is always at least one page
+fn paginate<T>(items: &[T]) -> Vec<&[T]> {
+    if items.is_empty() {
+        return vec![items];
+    }
+    items.chunks(PAGE_SIZE).collect()
+}
+
+fn pagination(page: usize, pages: usize, root: &str, path: impl Fn(usize) -> String) -> Pagination {
+    Pagination {
+        page,
+        pages,
+        previous_url: (page > 1).then(|| format!("{}{}", root, path(page - 1))),
+        next_url: (page < pages).then(|| format!("{}{}", root, path(page + 1))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_site, site_files, PAGE_SIZE, SITE_MARKER};
+    use crate::backend::models::{
+        bot_model::Bot, match_model::Match, submission_model::Submission, user_model::User,
+    };
+
+    fn user(id: &str) -> User {
+        User {
+            id: id.to_string(),
+            username: id.to_string(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn bot(id: &str, user: &str) -> Bot {
+        Bot {
+            id: id.to_string(),
+            user: user.to_string(),
+            name: format!("<{}>", id),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn submission(id: &str, user: &str, bot: &str, version: i32, mmr: f32) -> Submission {
+        let mut submission = Submission::unsaved(user, "", None, "", 1);
+        submission.id = id.to_string();
+        submission.bot = Some(bot.to_string());
+        submission.version = version;
+        submission.mmr = mmr;
+        submission
+    }
+
+    #[test]
+    fn writes_every_page() {
+        let users = vec![user("alice"), user("bob")];
+        let mut bots = vec![bot("walls", "alice")];
+        let mut submissions = vec![
+            submission("walls-1", "alice", "walls", 1, 1400.0),
+            submission("walls-2", "alice", "walls", 2, 1500.0),
+        ];
+        for index in 0..PAGE_SIZE {
+            let id = format!("bot{}", index);
+            bots.push(bot(&id, "bob"));
+            submissions.push(submission(&id, "bob", &id, 1, 1000.0 + index as f32));
+        }
+        let matches = vec![Match {
+            id: "match".to_string(),
+            winner: "walls-2".to_string(),
+            loser: "bot0".to_string(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+            p1_is_winner: 1,
+            match_error: None,
+        }];
+
+        let files = site_files(&users, &submissions, &bots, &matches, &[]);
+        let file = |path: &str| {
+            files
+                .iter()
+                .find(|(file_path, _)| file_path == path)
+                .map(|(_, content)| content.as_str())
+                .unwrap_or_else(|| panic!("Missing {}", path))
+        };
+
+        // 26 bots are split over two pages
+        let front = file("index.html");
+        assert!(front.contains("<a href=\"submissions/walls-2.html\">&lt;walls&gt;</a>"));
+        assert!(front.contains("<a href=\"leaderboard/2.html\">"));
+        assert!(file("leaderboard/2.html").contains("<a href=\"../index.html\">"));
+
+        let profile = file("users/alice.html");
+        assert!(profile.contains("<a href=\"../submissions/walls-1.html\">v1</a>"));
+        assert!(profile.contains("Best MMR: 1500 &#124; 1 wins, 0 losses"));
+        assert!(
+            profile.contains("<td><a href=\"../users/bob.html\">bob</a></td><td>1</td><td>0</td>")
+        );
+        let submission_page = file("submissions/walls-2.html");
+        assert!(submission_page.contains("<b>v2</b>"));
+        assert!(submission_page.contains("<span class=\"won\">Won</span>"));
+        assert!(file("matches/1.html").contains("<a href=\"../replays/match.html\">Replay</a>"));
+        assert!(file("replays/match.html").contains("<title>alice vs bob</title>"));
+        assert!(file("search.json").contains(
+            "{\"kind\":\"bot\",\"name\":\"<walls>\",\"url\":\"submissions/walls-2.html\"}"
+        ));
+        assert!(files.iter().all(|(_, content)| !content.contains("{{")));
+    }
+
+    #[test]
+    fn only_clears_generated_sites() {
+        let dir = std::env::temp_dir().join(format!("site-{}", uuid::Uuid::new_v4()));
+        let out_dir = dir.to_str().unwrap();
+        assert!(clear_site(out_dir).is_ok());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(clear_site(out_dir).is_ok());
+        assert!(!dir.exists());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "keep me").unwrap();
+        assert!(clear_site(out_dir).is_err());
+        assert!(dir.join("notes.txt").exists());
+
+        std::fs::write(dir.join(SITE_MARKER), "[]").unwrap();
+        assert!(clear_site(out_dir).is_ok());
+        assert!(!dir.exists());
+    }
+}
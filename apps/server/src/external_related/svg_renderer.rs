@@ -0,0 +1,221 @@
+use crate::game::{
+    board::Tile,
+    game::MAP_SIZE,
+    replay::{replay_from_boards, ReplayAction, ReplayFrame},
+};
+
+const CELL: i32 = 24;
+const PADDING: i32 = 8;
+const CAPTION_HEIGHT: i32 = 40;
+const FRAME_WIDTH: i32 = MAP_SIZE * CELL + 2 * PADDING;
+const FRAME_HEIGHT: i32 = MAP_SIZE * CELL + 2 * PADDING + CAPTION_HEIGHT;
+/// Seconds every frame is shown in the animated layout
+const FRAME_DURATION: f32 = 0.5;
+
+// Same colors as the gifs
+const PLAYER_ONE_COLOR: &str = "#00AF00";
+const PLAYER_TWO_COLOR: &str = "#EF0108";
+const WALL_COLOR: &str = "#000000";
+const NEW_WALL_COLOR: &str = "#F0A000";
+
+pub(crate) enum SvgLayout {
+    /// One board that plays the match and loops
+    Animated,
+    /// Every turn next to each other, wrapped after a number of columns
+    Strip { columns: usize },
+}
+
+/// Draws a match as svg with the grid, the walls, the shortest path of
+/// each player, a caption of the move and the walls left. Svgs work in
+/// img tags, so the file can be linked from the markdown of the match.
+pub(crate) fn render_match_svg(boards: &[Vec<Tile>], layout: SvgLayout) -> String {
+    let frames = replay_from_boards(boards);
+    let (width, height, content) = match layout {
+        SvgLayout::Animated => {
+            let content = frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| {
+                    format!(
+                        "<g{}>{}{}</g>",
+                        if frames.len() > 1 {
+                            " opacity=\"0\""
+                        } else {
+                            ""
+                        },
+                        visibility_animation(index, frames.len()),
+                        render_frame(frame)
+                    )
+                })
+                .collect::<String>();
+            (FRAME_WIDTH, FRAME_HEIGHT, content)
+        }
+        SvgLayout::Strip { columns } => {
+            let columns = columns.max(1);
+            let rows = frames.len().div_ceil(columns);
+            let content = frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| {
+                    format!(
+                        "<g transform=\"translate({} {})\">{}</g>",
+                        (index % columns) as i32 * FRAME_WIDTH,
+                        (index / columns) as i32 * FRAME_HEIGHT,
+                        render_frame(frame)
+                    )
+                })
+                .collect::<String>();
+            (
+                columns.min(frames.len()) as i32 * FRAME_WIDTH,
+                rows as i32 * FRAME_HEIGHT,
+                content,
+            )
+        }
+    };
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" font-family=\"monospace\" font-size=\"12\">{2}</svg>\n",
+        width, height, content
+    )
+}
+
+/// Shows the frame during its slot of the loop
+fn visibility_animation(index: usize, frame_count: usize) -> String {
+    if frame_count <= 1 {
+        return String::new();
+    }
+    let at = |index: usize| format!("{:.4}", index as f32 / frame_count as f32);
+    let (values, key_times) = if index == 0 {
+        ("1;0".to_string(), format!("0;{}", at(1)))
+    } else if index == frame_count - 1 {
+        ("0;1".to_string(), format!("0;{}", at(index)))
+    } else {
+        (
+            "0;1;0".to_string(),
+            format!("0;{};{}", at(index), at(index + 1)),
+        )
+    };
+    format!(
+        "<animate attributeName=\"opacity\" values=\"{}\" keyTimes=\"{}\" dur=\"{}s\" calcMode=\"discrete\" repeatCount=\"indefinite\"/>",
+        values,
+        key_times,
+        frame_count as f32 * FRAME_DURATION
+    )
+}
+
+fn render_frame(frame: &ReplayFrame) -> String {
+    let mut output = format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"#FFFFFF\"/>",
+        FRAME_WIDTH, FRAME_HEIGHT
+    );
+
+    // Grid
+    let board_size = MAP_SIZE * CELL;
+    for i in 0..=MAP_SIZE {
+        let offset = PADDING + i * CELL;
+        output.push_str(&format!(
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#CCCCCC\"/><line x1=\"{1}\" y1=\"{0}\" x2=\"{2}\" y2=\"{0}\" stroke=\"#CCCCCC\"/>",
+            offset,
+            PADDING,
+            PADDING + board_size
+        ));
+    }
+
+    for wall in frame.walls.iter() {
+        let color = match &frame.action {
+            ReplayAction::Wall { wall: new_wall, .. } if new_wall == wall => NEW_WALL_COLOR,
+            _ => WALL_COLOR,
+        };
+        for (x, y) in [(wall.x1, wall.y1), (wall.x2, wall.y2)] {
+            output.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                PADDING + x * CELL,
+                PADDING + y * CELL,
+                CELL,
+                CELL,
+                color
+            ));
+        }
+    }
+
+    // The paths are shifted apart so both stay visible where they overlap
+    for (player_one, color, shift) in [(true, PLAYER_ONE_COLOR, -3), (false, PLAYER_TWO_COLOR, 3)] {
+        if let Some(path) = frame.path(player_one) {
+            let points = path
+                .iter()
+                .map(|pos| format!("{},{}", center(pos.0) + shift, center(pos.1) + shift))
+                .collect::<Vec<String>>()
+                .join(" ");
+            output.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\" stroke-opacity=\"0.6\" stroke-dasharray=\"4 2\"/>",
+                points, color
+            ));
+        }
+    }
+
+    for (player, color) in [
+        (&frame.player_one, PLAYER_ONE_COLOR),
+        (&frame.player_two, PLAYER_TWO_COLOR),
+    ] {
+        output.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+            center(player.x),
+            center(player.y),
+            CELL * 2 / 5,
+            color
+        ));
+    }
+
+    let text_y = PADDING + board_size + 18;
+    output.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\">{}</text><text x=\"{}\" y=\"{}\"><tspan fill=\"{}\">O</tspan> walls {} <tspan fill=\"{}\">X</tspan> walls {}</text>",
+        PADDING,
+        text_y,
+        frame.caption(),
+        PADDING,
+        text_y + 16,
+        PLAYER_ONE_COLOR,
+        frame.player_one.wall_count,
+        PLAYER_TWO_COLOR,
+        frame.player_two.wall_count
+    ));
+    output
+}
+
+fn center(coordinate: i32) -> i32 {
+    PADDING + coordinate * CELL + CELL / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_match_svg, SvgLayout, FRAME_HEIGHT, FRAME_WIDTH};
+    use crate::game::{board::populate_board, methods};
+
+    #[test]
+    fn renders_every_turn() {
+        let mut game = methods::new(String::new());
+        let mut boards = Vec::new();
+        for y in [7, 6, 5] {
+            game.player_one.y = y;
+            boards.push(populate_board(
+                &game.player_one,
+                &game.player_two,
+                &game.walls,
+            ));
+        }
+
+        let animated = render_match_svg(&boards, SvgLayout::Animated);
+        assert!(animated.starts_with("<svg"));
+        assert_eq!(animated.matches("<animate ").count(), 4);
+        assert!(animated.contains("Turn 3: O moves up"));
+        assert!(animated.contains("walls 10"));
+        assert_eq!(animated.matches("<polyline").count(), 8);
+
+        let strip = render_match_svg(&boards, SvgLayout::Strip { columns: 3 });
+        assert!(strip.contains(&format!(
+            "width=\"{}\" height=\"{}\"",
+            3 * FRAME_WIDTH,
+            2 * FRAME_HEIGHT
+        )));
+        assert!(!strip.contains("<animate"));
+    }
+}
@@ -0,0 +1,270 @@
+//! The data every template is rendered with. Values are inserted
+//! into the templates as they are, without html escaping, so the
+//! markdown renders the same way it did before the templates.
+
+use serde::Serialize;
+
+/// readme.md, the front page of the repository
+#[derive(Serialize)]
+pub(crate) struct ReadmeData {
+    /// Up to three recent matches that are rendered as gifs
+    pub(crate) featured_matches: Vec<FeaturedMatch>,
+    /// The top 10 bots
+    pub(crate) leaderboard: Vec<LeaderboardEntry>,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct FeaturedMatch {
+    /// Usernames of the players
+    pub(crate) winner: String,
+    pub(crate) loser: String,
+    pub(crate) match_url: String,
+    pub(crate) gif_url: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct LeaderboardEntry {
+    pub(crate) mmr: i64,
+    pub(crate) username: String,
+    /// Escaped for markdown tables
+    pub(crate) bot_name: String,
+    pub(crate) issue_url: String,
+    /// Every version of the bot, oldest first
+    pub(crate) versions: Vec<VersionLink>,
+    /// Like "+12", missing for the first version of a bot
+    pub(crate) rating_delta: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct VersionLink {
+    pub(crate) version: i32,
+    pub(crate) issue_url: String,
+    /// The version that plays for the bot
+    pub(crate) current: bool,
+}
+
+/// match_log.md, every match newest first
+#[derive(Serialize)]
+pub(crate) struct MatchLogData {
+    pub(crate) matches: Vec<MatchLogEntry>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MatchLogEntry {
+    pub(crate) id: String,
+    pub(crate) winner: MatchSide,
+    pub(crate) loser: MatchSide,
+    pub(crate) page_url: String,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MatchSide {
+    pub(crate) username: String,
+    pub(crate) submission_id: String,
+    pub(crate) issue_url: String,
+    /// 🟩 for player one and 🟥 for player two
+    pub(crate) color: String,
+}
+
+/// submission_log.md, every submission by rating
+#[derive(Serialize)]
+pub(crate) struct SubmissionLogData {
+    pub(crate) submissions: Vec<SubmissionLogEntry>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SubmissionLogEntry {
+    pub(crate) id: String,
+    pub(crate) mmr: i64,
+    pub(crate) username: String,
+    pub(crate) issue_url: String,
+    pub(crate) bot_name: String,
+    pub(crate) version: i32,
+    /// active, disqualified or retired
+    pub(crate) status: String,
+    pub(crate) status_icon: String,
+    pub(crate) created_at: String,
+}
+
+/// match.md, the page of a single match
+#[derive(Serialize)]
+pub(crate) struct MatchPageData {
+    pub(crate) id: String,
+    pub(crate) winner: MatchSide,
+    pub(crate) loser: MatchSide,
+    pub(crate) error: Option<String>,
+    pub(crate) svg_url: String,
+    pub(crate) replay_url: String,
+    pub(crate) rounds: Vec<RoundData>,
+    pub(crate) turn_count: usize,
+    /// Win probability of 🟩 in percent, from the start position on
+    pub(crate) evaluation: Vec<u32>,
+    pub(crate) blunders: Vec<BlunderData>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct BlunderData {
+    pub(crate) turn: usize,
+    /// The color of the player who blundered
+    pub(crate) color: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RoundData {
+    pub(crate) number: usize,
+    /// The board as emoji tiles, one line per row
+    pub(crate) board: String,
+}
+
+/// replay.html, the interactive replay of a match
+#[derive(Serialize)]
+pub(crate) struct ReplayPageData {
+    /// Escaped for html
+    pub(crate) title: String,
+    pub(crate) description: String,
+    /// The frames of the replay as json
+    pub(crate) replay: String,
+}
+
+/// The page links of a paginated site page
+#[derive(Serialize)]
+pub(crate) struct Pagination {
+    pub(crate) page: usize,
+    pub(crate) pages: usize,
+    pub(crate) previous_url: Option<String>,
+    pub(crate) next_url: Option<String>,
+}
+
+/// site_leaderboard.html, every bot by rating
+#[derive(Serialize)]
+pub(crate) struct SiteLeaderboardData {
+    pub(crate) title: String,
+    /// Links in the site pages are relative, root leads from
+    /// the page to the top of the site, like "../"
+    pub(crate) root: String,
+    pub(crate) standings: Vec<SiteStanding>,
+    pub(crate) pagination: Pagination,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteStanding {
+    pub(crate) rank: usize,
+    pub(crate) mmr: i64,
+    /// Escaped for html, as every name in the site pages
+    pub(crate) username: String,
+    pub(crate) user_url: String,
+    pub(crate) bot_name: String,
+    pub(crate) submission_url: String,
+    pub(crate) version: i32,
+    pub(crate) wins: i32,
+    pub(crate) matches_played: i32,
+}
+
+/// site_matches.html, every match newest first
+#[derive(Serialize)]
+pub(crate) struct SiteMatchesData {
+    pub(crate) title: String,
+    pub(crate) root: String,
+    pub(crate) matches: Vec<SiteMatch>,
+    pub(crate) pagination: Pagination,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteMatch {
+    pub(crate) winner: SitePlayer,
+    pub(crate) loser: SitePlayer,
+    pub(crate) replay_url: String,
+    pub(crate) error: Option<String>,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub(crate) struct SitePlayer {
+    pub(crate) username: String,
+    pub(crate) user_url: String,
+    pub(crate) bot_name: String,
+    pub(crate) submission_url: String,
+}
+
+/// site_user.html, every bot of a user with its versions
+#[derive(Serialize)]
+pub(crate) struct SiteUserData {
+    pub(crate) title: String,
+    pub(crate) root: String,
+    pub(crate) username: String,
+    pub(crate) bots: Vec<SiteBot>,
+    pub(crate) best_rating: Option<i64>,
+    pub(crate) wins: usize,
+    pub(crate) losses: usize,
+    /// Rounded to one decimal, missing without stored turns
+    pub(crate) average_game_length: Option<String>,
+    pub(crate) average_walls_placed: Option<String>,
+    /// Most played opponent first
+    pub(crate) opponents: Vec<SiteOpponent>,
+    pub(crate) recent_matches: Vec<SiteSubmissionMatch>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteOpponent {
+    pub(crate) username: String,
+    pub(crate) user_url: String,
+    pub(crate) wins: usize,
+    pub(crate) losses: usize,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteBot {
+    pub(crate) name: String,
+    /// Oldest first
+    pub(crate) versions: Vec<SiteVersion>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteVersion {
+    pub(crate) version: i32,
+    pub(crate) url: String,
+    pub(crate) mmr: i64,
+    pub(crate) wins: i32,
+    pub(crate) matches_played: i32,
+    /// active, disqualified or retired
+    pub(crate) status: String,
+    pub(crate) created_at: String,
+    /// The page being rendered is this version
+    pub(crate) current: bool,
+}
+
+/// site_submission.html, a single version of a bot
+#[derive(Serialize)]
+pub(crate) struct SiteSubmissionData {
+    pub(crate) title: String,
+    pub(crate) root: String,
+    pub(crate) username: String,
+    pub(crate) user_url: String,
+    pub(crate) issue_url: String,
+    pub(crate) status: String,
+    pub(crate) mmr: i64,
+    pub(crate) wins: i32,
+    pub(crate) matches_played: i32,
+    /// The rating every version of the bot ended up with
+    pub(crate) rating_history: Vec<SiteVersion>,
+    pub(crate) recent_matches: Vec<SiteSubmissionMatch>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SiteSubmissionMatch {
+    pub(crate) won: bool,
+    pub(crate) opponent: SitePlayer,
+    pub(crate) replay_url: String,
+    pub(crate) created_at: String,
+}
+
+/// An entry of search.json
+#[derive(Serialize)]
+pub(crate) struct SearchEntry {
+    /// user or bot
+    pub(crate) kind: String,
+    pub(crate) name: String,
+    pub(crate) url: String,
+}
@@ -0,0 +1,165 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use handlebars::Handlebars;
+use serde::Serialize;
+
+/// The templates shipped with the server, see apps/server/templates
+const DEFAULT_TEMPLATES: [(&str, &str); 10] = [
+    ("readme.md", include_str!("../../templates/readme.md.hbs")),
+    (
+        "match_log.md",
+        include_str!("../../templates/match_log.md.hbs"),
+    ),
+    (
+        "submission_log.md",
+        include_str!("../../templates/submission_log.md.hbs"),
+    ),
+    ("match.md", include_str!("../../templates/match.md.hbs")),
+    (
+        "replay.html",
+        include_str!("../../templates/replay.html.hbs"),
+    ),
+    (
+        "site_layout.html",
+        include_str!("../../templates/site_layout.html.hbs"),
+    ),
+    (
+        "site_leaderboard.html",
+        include_str!("../../templates/site_leaderboard.html.hbs"),
+    ),
+    (
+        "site_matches.html",
+        include_str!("../../templates/site_matches.html.hbs"),
+    ),
+    (
+        "site_user.html",
+        include_str!("../../templates/site_user.html.hbs"),
+    ),
+    (
+        "site_submission.html",
+        include_str!("../../templates/site_submission.html.hbs"),
+    ),
+];
+
+pub(crate) struct Templates {
+    registry: Handlebars<'static>,
+}
+impl Templates {
+    /// The default templates, where a file with the same name in the
+    /// override dir, like readme.md.hbs, replaces the default one
+    pub(crate) fn load(override_dir: Option<&str>) -> Result<Self, String> {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        for (name, default) in DEFAULT_TEMPLATES {
+            let path = override_dir.map(|dir| Path::new(dir).join(format!("{}.hbs", name)));
+            let source = match path {
+                Some(path) if path.exists() => std::fs::read_to_string(&path)
+                    .map_err(|error| format!("Could not read {}: {}", path.display(), error))?,
+                _ => default.to_string(),
+            };
+            registry
+                .register_template_string(name, source)
+                .map_err(|error| format!("Invalid template {}: {}", name, error))?;
+        }
+        Ok(Templates { registry })
+    }
+
+    pub(crate) fn render<T: Serialize>(&self, name: &str, data: &T) -> Result<String, String> {
+        self.registry
+            .render(name, data)
+            .map_err(|error| format!("Could not render {}: {}", name, error))
+    }
+}
+
+fn defaults() -> &'static Templates {
+    static DEFAULTS: OnceLock<Templates> = OnceLock::new();
+    DEFAULTS.get_or_init(|| Templates::load(None).expect("The default templates are invalid"))
+}
+
+/// The templates with the overrides in TEMPLATE_DIR, broken overrides
+/// are reported and the defaults are used instead
+fn configured() -> &'static Templates {
+    static CONFIGURED: OnceLock<Templates> = OnceLock::new();
+    CONFIGURED.get_or_init(|| {
+        let dir = std::env::var("TEMPLATE_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty());
+        Templates::load(dir.as_deref()).unwrap_or_else(|error| {
+            println!("{}, using the default templates", error);
+            Templates::load(None).expect("The default templates are invalid")
+        })
+    })
+}
+
+pub(crate) fn render<T: Serialize>(name: &str, data: &T) -> String {
+    configured().render(name, data).unwrap_or_else(|error| {
+        println!("{}, using the default template", error);
+        defaults()
+            .render(name, data)
+            .expect("The default templates are invalid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Templates;
+    use crate::external_related::template_data::{SubmissionLogData, SubmissionLogEntry};
+
+    fn log() -> SubmissionLogData {
+        SubmissionLogData {
+            submissions: vec![SubmissionLogEntry {
+                id: "abc".to_string(),
+                mmr: 1012,
+                username: "alice".to_string(),
+                issue_url: "https://github.com/Hampfh/Hampfh/issues/3".to_string(),
+                bot_name: "wall<e>".to_string(),
+                version: 2,
+                status: "active".to_string(),
+                status_icon: "✅".to_string(),
+                created_at: "2026-10-19 12:00".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn renders_the_defaults() {
+        let output = Templates::load(None)
+            .unwrap()
+            .render("submission_log.md", &log())
+            .unwrap();
+        assert_eq!(
+            output,
+            "<div align=\"center\">\n\n# Submissions\n<p>MMR: 1012 &#124; @alice &#124; <a href=\"https://github.com/Hampfh/Hampfh/issues/3\">abc</a> wall<e> v2 ✅ &#124; 2026-10-19 12:00</p>  \n</div>\n"
+        );
+    }
+
+    #[test]
+    fn overrides_replace_single_templates() {
+        let dir = std::env::temp_dir().join(format!("templates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("submission_log.md.hbs"),
+            "{{#each submissions}}{{username}}: {{mmr}} ({{status}})\n{{/each}}",
+        )
+        .unwrap();
+
+        let templates = Templates::load(dir.to_str()).unwrap();
+        assert_eq!(
+            templates.render("submission_log.md", &log()).unwrap(),
+            "alice: 1012 (active)\n"
+        );
+        // Templates without an override keep the default
+        assert!(templates
+            .render(
+                "match_log.md",
+                &crate::external_related::template_data::MatchLogData { matches: vec![] }
+            )
+            .unwrap()
+            .contains("# Matches"));
+
+        std::fs::write(dir.join("match.md.hbs"), "{{#each}}").unwrap();
+        assert!(Templates::load(dir.to_str()).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
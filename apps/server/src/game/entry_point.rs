@@ -1,4 +1,6 @@
-use crate::game::game::GameResult;
+use std::time::Duration;
+
+use crate::game::game::{Game, GameResult, TurnTrace};
 use crate::game::methods;
 
 use super::board::Tile;
@@ -7,13 +9,40 @@ pub(crate) fn initialize_game_session(
     script_1: &str,
     script_2: &str,
 ) -> (GameResult, Vec<Vec<Tile>>) {
-    let std =
-        std::fs::read_to_string("../scripts/std.lua").expect("Could not load standard library");
+    let (result, turns, _) = initialize_traced_game_session(script_1, script_2);
+    (result, turns)
+}
+
+/// Plays a game and also returns a trace of every turn, see TurnTrace
+pub(crate) fn initialize_traced_game_session(
+    script_1: &str,
+    script_2: &str,
+) -> (GameResult, Vec<Vec<Tile>>, Vec<TurnTrace>) {
+    let mut game_session = methods::new(load_std());
+    let (result, turns) = methods::start(
+        &mut game_session,
+        script_1.to_string(),
+        script_2.to_string(),
+    );
+    (result, turns, game_session.traces)
+}
 
-    let mut game_session = methods::new(std);
-    return methods::start(
+/// Like initialize_traced_game_session, `on_turn` follows the game while it is played
+pub(crate) fn initialize_observed_game_session(
+    script_1: &str,
+    script_2: &str,
+    on_turn: &mut dyn FnMut(&Game, Duration),
+) -> (GameResult, Vec<Vec<Tile>>, Vec<TurnTrace>) {
+    let mut game_session = methods::new(load_std());
+    let (result, turns) = methods::start_observed(
         &mut game_session,
         script_1.to_string(),
         script_2.to_string(),
+        on_turn,
     );
+    (result, turns, game_session.traces)
+}
+
+fn load_std() -> String {
+    std::fs::read_to_string("../scripts/std.lua").expect("Could not load standard library")
 }
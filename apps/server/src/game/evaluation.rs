@@ -0,0 +1,150 @@
+use serde::Serialize;
+
+use super::replay::ReplayFrame;
+
+/// What a wall in hand is worth, in steps
+const WALL_VALUE: f32 = 0.5;
+/// What having the next move is worth, in steps
+const TEMPO_VALUE: f32 = 0.5;
+/// Scores are squashed into a win probability, a lead of this
+/// many steps gives player one about a 73% chance to win
+const PROBABILITY_SCALE: f32 = 2.0;
+/// A move that costs the player who made it this many steps is a
+/// blunder. A plain wall already costs 1.5 with the tempo it gives away
+const BLUNDER_SWING: f32 = 3.0;
+
+/// How a position looks for player one, who walks to y = 0
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TurnEvaluation {
+    pub turn: usize,
+    /// Positive when player one is ahead, in steps
+    pub score: f32,
+    /// Between 0 and 1, for player one
+    pub win_probability: f32,
+    /// The move that led here was a blunder by the player who made it
+    pub blunder: bool,
+}
+
+/// Scores a position from the difference of the shortest paths, the
+/// walls left and who moves next. A player without a path counts as
+/// far away as a player can be.
+pub(crate) fn evaluate(frame: &ReplayFrame) -> f32 {
+    let distance = |player_one: bool| match frame.path(player_one) {
+        Some(path) => path.len() as f32 - 1.0,
+        None => 81.0,
+    };
+    // Player one moves on odd turns, so next after an even turn
+    let tempo = if frame.turn.is_multiple_of(2) {
+        TEMPO_VALUE
+    } else {
+        -TEMPO_VALUE
+    };
+    distance(false) - distance(true)
+        + (frame.player_one.wall_count - frame.player_two.wall_count) as f32 * WALL_VALUE
+        + tempo
+}
+
+pub(crate) fn win_probability(score: f32) -> f32 {
+    1.0 / (1.0 + (-score / PROBABILITY_SCALE).exp())
+}
+
+/// The evaluation of every frame of a match
+pub(crate) fn evaluate_match(frames: &[ReplayFrame]) -> Vec<TurnEvaluation> {
+    let mut evaluations: Vec<TurnEvaluation> = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let score = evaluate(frame);
+        let probability = win_probability(score);
+        let blunder = match evaluations.last() {
+            // Player one made the move on odd turns
+            Some(previous) if !frame.turn.is_multiple_of(2) => {
+                previous.score - score >= BLUNDER_SWING
+            }
+            Some(previous) => score - previous.score >= BLUNDER_SWING,
+            None => false,
+        };
+        evaluations.push(TurnEvaluation {
+            turn: frame.turn,
+            score,
+            win_probability: probability,
+            blunder,
+        });
+    }
+    evaluations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_match, win_probability};
+    use crate::game::{board::populate_board, game::Wall, methods, replay::replay_from_boards};
+
+    #[test]
+    fn start_favours_the_side_to_move() {
+        let evaluation = evaluate_match(&replay_from_boards(&[]));
+        assert_eq!(evaluation.len(), 1);
+        assert_eq!(evaluation[0].score, 0.5);
+        assert!(evaluation[0].win_probability > 0.5);
+        assert_eq!(win_probability(0.0), 0.5);
+    }
+
+    #[test]
+    fn flags_moves_that_throw_the_game() {
+        let mut game = methods::new(String::new());
+        let mut boards = Vec::new();
+        // Player one walls itself in, player two walks on
+        game.player_one.y = 7;
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        game.player_two.y = 1;
+        boards.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        for wall in [
+            Wall {
+                x1: 3,
+                y1: 6,
+                x2: 4,
+                y2: 6,
+            },
+            Wall {
+                x1: 5,
+                y1: 6,
+                x2: 5,
+                y2: 7,
+            },
+            Wall {
+                x1: 3,
+                y1: 7,
+                x2: 3,
+                y2: 8,
+            },
+        ] {
+            game.walls.push(wall);
+            boards.push(populate_board(
+                &game.player_one,
+                &game.player_two,
+                &game.walls,
+            ));
+            game.player_two.y += 1;
+            boards.push(populate_board(
+                &game.player_one,
+                &game.player_two,
+                &game.walls,
+            ));
+        }
+
+        let evaluation = evaluate_match(&replay_from_boards(&boards));
+        assert_eq!(evaluation.len(), boards.len() + 1);
+        assert!(evaluation.last().unwrap().win_probability < 0.1);
+        let blunders: Vec<usize> = evaluation
+            .iter()
+            .filter(|turn| turn.blunder)
+            .map(|turn| turn.turn)
+            .collect();
+        assert_eq!(blunders, vec![7]);
+    }
+}
@@ -1,15 +1,20 @@
 use std::sync::{Arc, Mutex};
 
+use serde::Serialize;
+
 use super::{
     board::Tile,
     player::{Player, PlayerType},
+    sandbox::sandbox_executor::create_lua_game_object,
 };
 
 pub const MAP_SIZE: i32 = 9;
 pub const INITIAL_WALL_COUNT: i32 = 10;
 pub const MAX_TURNS: i32 = 400;
+/// How long a script may think about a move
+pub const TURN_TIME_LIMIT_MS: u64 = 500;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub(crate) struct Wall {
     pub x1: i32,
     pub y1: i32,
@@ -41,8 +46,21 @@ pub(crate) enum ErrorType {
     /// The game doesn't progress anymore
     GameDeadlock,
 }
+impl ErrorType {
+    /// Why the game ended, without the player at fault
+    pub(crate) fn reason(&self) -> String {
+        match self {
+            ErrorType::GameError { reason, .. } | ErrorType::RuntimeError { reason, .. } => {
+                reason.clone()
+            }
+            ErrorType::TurnTimeout { .. } => "The bot took too long to move".to_string(),
+            ErrorType::GameDeadlock => "Game deadlock".to_string(),
+        }
+    }
+}
 
-#[derive(Debug)]
+/// Cloned games share the sandboxes of the players
+#[derive(Debug, Clone)]
 pub(crate) struct Game {
     pub(crate) running: bool,
     pub(crate) game_result: Option<GameResult>,
@@ -57,6 +75,44 @@ pub(crate) struct Game {
     pub(crate) last_move: Option<Move>,
     pub(crate) std: String, // Standard library
     pub(crate) turns: Vec<Vec<Tile>>,
+    /// One trace per turn the scripts played, a turn that
+    /// ended the game with an error has a trace but no board
+    pub(crate) traces: Vec<TurnTrace>,
+}
+
+/// The position a script was asked to move in and what it logged
+#[derive(Debug, Clone)]
+pub(crate) struct TurnTrace {
+    pub(crate) player_one_turn: bool,
+    pub(crate) player_one: Player,
+    pub(crate) player_two: Player,
+    pub(crate) walls: Vec<Wall>,
+    /// The move that was played, None if the turn failed
+    pub(crate) played: Option<Move>,
+    /// Everything printed with log() in onTurn and onJump
+    pub(crate) log: Vec<String>,
+}
+impl TurnTrace {
+    pub(crate) fn new(game: &Game) -> Self {
+        TurnTrace {
+            player_one_turn: game.player_one_turn,
+            player_one: game.player_one.clone(),
+            player_two: game.player_two.clone(),
+            walls: game.walls.clone(),
+            played: None,
+            log: Vec::new(),
+        }
+    }
+
+    /// The context table onTurn received, as lua source
+    pub(crate) fn context(&self) -> String {
+        create_lua_game_object(
+            self.walls.clone(),
+            self.player_one_turn,
+            self.player_one.clone(),
+            self.player_two.clone(),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
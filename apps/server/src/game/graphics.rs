@@ -1,46 +1,47 @@
 use super::board::{populate_board, Tile};
-use super::game::{Game, MAP_SIZE};
+use super::game::{Game, Wall, MAP_SIZE};
+use super::player::Player;
 
 pub(crate) fn draw_game(game: &Game) {
-    let buffer = populate_board(&game.player_one, &game.player_two, &game.walls);
-
-    let mut count = 0;
-
     println!("Last move: {:?}", game.last_move);
+    print!("{}", render_board(game));
+}
+
+/// The board as text, player one is O and player two is X
+pub(crate) fn render_board(game: &Game) -> String {
+    render_position(&game.player_one, &game.player_two, &game.walls)
+}
 
-    print!(" ");
+pub(crate) fn render_position(player_one: &Player, player_two: &Player, walls: &[Wall]) -> String {
+    let buffer = populate_board(player_one, player_two, &walls.to_vec());
+    let mut output = String::from(" ");
     for i in 0..MAP_SIZE {
-        print!("{}", i);
+        output.push_str(&i.to_string());
     }
-    println!();
+    output.push('\n');
     // Upper wall
-    for _ in 0..MAP_SIZE + 2 {
-        print!("#");
-    }
-    println!();
-    print!("#");
-    let mut line = 0;
-    for tile in buffer.iter() {
-        match tile {
-            Tile::Empty => print!(" "),
-            Tile::P1 => print!("O"),
-            Tile::P2 => print!("X"),
-            Tile::Wall => print!("#"),
-        }
+    output.push_str(&"#".repeat(MAP_SIZE as usize + 2));
+    output.push('\n');
 
-        count += 1;
-        // Side wall
-        if count >= MAP_SIZE {
-            println!("# {}", line);
-            count = 0;
-            print!("#");
-            line += 1;
+    for (line, row) in buffer.chunks(MAP_SIZE as usize).enumerate() {
+        output.push('#');
+        for tile in row {
+            output.push(match tile {
+                Tile::Empty => ' ',
+                Tile::P1 => 'O',
+                Tile::P2 => 'X',
+                Tile::Wall => '#',
+            });
         }
+        // Side wall
+        output.push_str(&format!("# {}\n", line));
     }
 
     // Lower wall
-    for _ in 0..MAP_SIZE + 1 {
-        print!("#");
-    }
-    println!();
+    output.push_str(&"#".repeat(MAP_SIZE as usize + 2));
+    output.push_str(&format!(
+        "\nWalls left: O {}, X {}\n",
+        player_one.wall_count, player_two.wall_count
+    ));
+    output
 }
@@ -5,7 +5,7 @@ use crate::game::graphics::draw_game;
 use crate::game::player::{Player, PlayerType};
 use crate::game::turn;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use super::board::Tile;
 use super::game::GameResult;
@@ -41,6 +41,7 @@ pub(crate) fn custom_new(
         last_move: None,
         std,
         turns: Vec::new(),
+        traces: Vec::new(),
     };
 }
 
@@ -48,6 +49,17 @@ pub(crate) fn start(
     game: &mut Game,
     program1: String,
     program2: String,
+) -> (GameResult, Vec<Vec<Tile>>) {
+    start_observed(game, program1, program2, &mut |_, _| ())
+}
+
+/// Like start, `on_turn` is called after every turn with the game
+/// and how long the turn took, while the game is still running
+pub(crate) fn start_observed(
+    game: &mut Game,
+    program1: String,
+    program2: String,
+    on_turn: &mut dyn FnMut(&Game, Duration),
 ) -> (GameResult, Vec<Vec<Tile>>) {
     let std = game.std.clone();
 
@@ -139,7 +151,7 @@ pub(crate) fn start(
     drop(first);
     drop(second);
 
-    game_loop(game);
+    game_loop(game, on_turn);
 
     return match game.game_result.clone() {
         Some(game_result) => (game_result, game.turns.clone()),
@@ -153,11 +165,46 @@ pub(crate) fn start(
     };
 }
 
-pub(crate) fn game_loop(game: &mut Game) {
+/// Loads a program and the standard library into the sandbox of one player,
+/// for games that are played turn by turn instead of through start.
+/// The program gets the same 100 milliseconds to load as in a match.
+pub(crate) fn load_program(game: &Game, player_one: bool, program: &str) -> Result<(), String> {
+    let sandbox = if player_one {
+        game.player_one_sandbox.clone()
+    } else {
+        game.player_two_sandbox.clone()
+    };
+    let program = program.to_string();
+    let std = game.std.clone();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<Option<usize>, String>>();
+    std::thread::spawn(move || {
+        tx.send(Ok(Some(thread_id::get()))).unwrap();
+        let sandbox = sandbox.lock().unwrap();
+        let result = sandbox
+            .context(|ctx| ctx.load(&program).exec())
+            .map_err(|error| format!("The bot could not be loaded, reason: {}", error));
+        sandbox.context(|ctx| ctx.load(&std).exec()).unwrap();
+        tx.send(result.map(|_| None)).unwrap();
+    });
+
+    let thread_id = rx.recv().unwrap().unwrap().unwrap();
+    match rx.recv_timeout(Duration::from_millis(100)) {
+        Ok(result) => result.map(|_| ()),
+        Err(_) => {
+            terminate_thread(thread_id);
+            Err("The bot took too long to load".to_string())
+        }
+    }
+}
+
+pub(crate) fn game_loop(game: &mut Game, on_turn: &mut dyn FnMut(&Game, Duration)) {
     let mut round = 1;
     while game.running {
+        let turn_start = Instant::now();
         update(game);
         winner(game);
+        on_turn(game, turn_start.elapsed());
         if round >= MAX_TURNS {
             game.running = false;
             game.game_result = Some(GameResult::Error(ErrorType::GameDeadlock));
@@ -1,5 +1,6 @@
 pub(crate) mod board;
 pub(crate) mod entry_point;
+pub(crate) mod evaluation;
 pub(crate) mod execute_move;
 pub(crate) mod game;
 pub(crate) mod graphics;
@@ -7,14 +8,18 @@ pub(crate) mod map_mirroring;
 pub(crate) mod methods;
 pub(crate) mod path_find;
 pub(crate) mod player;
+pub(crate) mod replay;
 pub(crate) mod sandbox;
+pub(crate) mod scenario;
 pub(crate) mod turn;
 pub(crate) mod validation;
 
 mod tests {
     mod game_tests;
     mod on_jump_tests;
+    mod scenario_tests;
     mod security_tests;
     mod std_tests;
+    mod trace_tests;
     mod util;
 }
@@ -133,3 +133,26 @@ fn path_exists(walls: &Vec<Wall>, p1: &Player, p2: &Player, player: &Player, tar
         None => false,
     }
 }
+
+/// The shortest walk of a player to its goal row, including the tile it
+/// stands on. Players don't block each other, same as in path_exists.
+/// Player one walks to y = 0 and player two to y = MAP_SIZE - 1.
+pub(crate) fn shortest_path(
+    walls: &Vec<Wall>,
+    p1: &Player,
+    p2: &Player,
+    player_one: bool,
+) -> Option<Vec<Pos>> {
+    let (player, goal) = if player_one {
+        (p1, 0)
+    } else {
+        (p2, MAP_SIZE - 1)
+    };
+    astar(
+        &Pos(player.x, player.y),
+        |pos| pos.successors(walls, p1, p2),
+        |pos| pos.1.abs_diff(goal),
+        |pos| pos.1 == goal,
+    )
+    .map(|(path, _)| path)
+}
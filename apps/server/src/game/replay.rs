@@ -0,0 +1,274 @@
+use serde::Serialize;
+
+use super::board::Tile;
+use super::game::{Wall, MAP_SIZE};
+use super::methods;
+use super::path_find::{shortest_path, Pos};
+use super::player::Player;
+
+/**
+ * Matches are stored as one board per turn, this rebuilds what
+ * happened from them. Walls are paired from the two tiles that
+ * appeared in the same turn and the wall counters from who placed
+ * them, player one always moves on odd turns.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct ReplayFrame {
+    /// 0 is the starting position
+    pub(crate) turn: usize,
+    pub(crate) player_one: Player,
+    pub(crate) player_two: Player,
+    pub(crate) walls: Vec<Wall>,
+    /// The move that led to this position
+    pub(crate) action: ReplayAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ReplayAction {
+    Start,
+    Move {
+        player_one: bool,
+        from: (i32, i32),
+        to: (i32, i32),
+        jump: bool,
+    },
+    Wall {
+        player_one: bool,
+        wall: Wall,
+    },
+}
+
+impl ReplayFrame {
+    /// The shortest way to the goal row for a player, if any
+    pub(crate) fn path(&self, player_one: bool) -> Option<Vec<Pos>> {
+        shortest_path(&self.walls, &self.player_one, &self.player_two, player_one)
+    }
+
+    /// The move as a sentence, like "Turn 3: O moves up"
+    pub(crate) fn caption(&self) -> String {
+        let name = |player_one: bool| if player_one { "O" } else { "X" };
+        match &self.action {
+            ReplayAction::Start => "Start".to_string(),
+            ReplayAction::Move {
+                player_one,
+                from,
+                to,
+                jump: false,
+            } => format!(
+                "Turn {}: {} moves {}",
+                self.turn,
+                name(*player_one),
+                direction(*from, *to)
+            ),
+            ReplayAction::Move {
+                player_one,
+                to,
+                jump: true,
+                ..
+            } => format!(
+                "Turn {}: {} jumps to ({}, {})",
+                self.turn,
+                name(*player_one),
+                to.0,
+                to.1
+            ),
+            ReplayAction::Wall { player_one, wall } => format!(
+                "Turn {}: {} places a wall at ({}, {}) ({}, {})",
+                self.turn,
+                name(*player_one),
+                wall.x1,
+                wall.y1,
+                wall.x2,
+                wall.y2
+            ),
+        }
+    }
+}
+
+fn direction(from: (i32, i32), to: (i32, i32)) -> &'static str {
+    match (to.0 - from.0, to.1 - from.1) {
+        (0, dy) if dy < 0 => "up",
+        (0, _) => "down",
+        (dx, _) if dx > 0 => "right",
+        _ => "left",
+    }
+}
+
+/// The frames of a match, starting with the starting position
+pub(crate) fn replay_from_boards(boards: &[Vec<Tile>]) -> Vec<ReplayFrame> {
+    let mut frames = vec![start_frame()];
+
+    for board in boards {
+        let next = next_frame(frames.last().unwrap(), board);
+        frames.push(next);
+    }
+    frames
+}
+
+/// The starting position, before anyone moved
+pub(crate) fn start_frame() -> ReplayFrame {
+    let start = methods::new(String::new());
+    ReplayFrame {
+        turn: 0,
+        player_one: start.player_one,
+        player_two: start.player_two,
+        walls: Vec::new(),
+        action: ReplayAction::Start,
+    }
+}
+
+/// The frame after `previous`, for the board of the next turn
+pub(crate) fn next_frame(previous: &ReplayFrame, board: &[Tile]) -> ReplayFrame {
+    let player_one_turn = previous.turn.is_multiple_of(2);
+    let mut frame = ReplayFrame {
+        turn: previous.turn + 1,
+        player_one: locate(board, Tile::P1).map_or(previous.player_one.clone(), |(x, y)| {
+            moved(&previous.player_one, x, y)
+        }),
+        player_two: locate(board, Tile::P2).map_or(previous.player_two.clone(), |(x, y)| {
+            moved(&previous.player_two, x, y)
+        }),
+        walls: previous.walls.clone(),
+        action: ReplayAction::Start,
+    };
+
+    let new_tiles: Vec<(i32, i32)> = tiles(board, Tile::Wall)
+        .into_iter()
+        .filter(|(x, y)| {
+            !previous
+                .walls
+                .iter()
+                .any(|wall| (wall.x1, wall.y1) == (*x, *y) || (wall.x2, wall.y2) == (*x, *y))
+        })
+        .collect();
+
+    frame.action = if let [(x1, y1), (x2, y2)] = new_tiles.as_slice() {
+        let wall = Wall {
+            x1: *x1,
+            y1: *y1,
+            x2: *x2,
+            y2: *y2,
+        };
+        frame.walls.push(wall.clone());
+        if player_one_turn {
+            frame.player_one.wall_count -= 1;
+        } else {
+            frame.player_two.wall_count -= 1;
+        }
+        ReplayAction::Wall {
+            player_one: player_one_turn,
+            wall,
+        }
+    } else {
+        let (before, after) = if player_one_turn {
+            (&previous.player_one, &frame.player_one)
+        } else {
+            (&previous.player_two, &frame.player_two)
+        };
+        ReplayAction::Move {
+            player_one: player_one_turn,
+            from: (before.x, before.y),
+            to: (after.x, after.y),
+            jump: before.x.abs_diff(after.x) + before.y.abs_diff(after.y) > 1,
+        }
+    };
+    frame
+}
+
+fn moved(player: &Player, x: i32, y: i32) -> Player {
+    Player::new(x, y, player.wall_count, player.player_type.clone())
+}
+
+fn tiles(board: &[Tile], tile: Tile) -> Vec<(i32, i32)> {
+    board
+        .iter()
+        .enumerate()
+        .filter(|(_, current)| **current == tile)
+        .map(|(index, _)| (index as i32 % MAP_SIZE, index as i32 / MAP_SIZE))
+        .collect()
+}
+
+/// A player that jumped past its goal row is not on the last board
+fn locate(board: &[Tile], tile: Tile) -> Option<(i32, i32)> {
+    tiles(board, tile).into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay_from_boards, ReplayAction};
+    use crate::game::{
+        board::populate_board,
+        game::{ErrorType, Game, Move, Wall},
+        methods,
+        player::{Player, PlayerType},
+        turn::{play_turn, MoveSource},
+    };
+
+    struct Moves(Vec<Move>);
+    impl MoveSource for Moves {
+        fn on_turn(&mut self, _: &Game) -> Result<Move, ErrorType> {
+            Ok(self.0.remove(0))
+        }
+        fn on_jump(&mut self, _: &Game) -> Result<Move, ErrorType> {
+            Ok(self.0.remove(0))
+        }
+    }
+
+    #[test]
+    fn rebuilds_moves_walls_and_counters() {
+        let mut game = methods::new(String::new());
+        let mut moves = Moves(vec![
+            Move::Up,
+            Move::Wall(Wall {
+                x1: 3,
+                y1: 6,
+                x2: 4,
+                y2: 6,
+            }),
+            Move::Left,
+        ]);
+        for _ in 0..3 {
+            play_turn(&mut game, &mut moves).unwrap();
+        }
+
+        let frames = replay_from_boards(&game.turns);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].action, ReplayAction::Start);
+        assert_eq!(frames[1].caption(), "Turn 1: O moves up");
+        assert_eq!(
+            frames[2].caption(),
+            "Turn 2: X places a wall at (3, 6) (4, 6)"
+        );
+        assert_eq!(frames[3].caption(), "Turn 3: O moves left");
+        assert_eq!(
+            (
+                frames[3].player_one.wall_count,
+                frames[3].player_two.wall_count
+            ),
+            (10, 9)
+        );
+
+        // The wall sends player one around it
+        let path = frames[3].path(true).unwrap();
+        assert_eq!((path[0].0, path[0].1), (3, 7));
+        assert_eq!(path.len(), 9);
+        assert_eq!(frames[3].path(false).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn detects_jumps() {
+        let boards = vec![populate_board(
+            &Player::new(4, 6, 10, PlayerType::Flipped),
+            &Player::new(4, 7, 10, PlayerType::Regular),
+            &Vec::new(),
+        )];
+
+        let frames = replay_from_boards(&boards);
+        assert!(matches!(
+            frames[1].action,
+            ReplayAction::Move { jump: true, .. }
+        ));
+        assert_eq!(frames[1].caption(), "Turn 1: O jumps to (4, 6)");
+    }
+}
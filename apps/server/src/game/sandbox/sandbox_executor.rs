@@ -9,14 +9,19 @@ use crate::game::{
 
 use crate::game::{
     board::{populate_board, serialize_board},
-    game::Wall,
+    game::{Wall, TURN_TIME_LIMIT_MS},
     map_mirroring::{conditionally_reverse_player, conditionally_reverse_walls},
     player::Player,
 };
 
+/// Lines a bot can log per call and their length, anything above is dropped
+const MAX_LOG_LINES: usize = 100;
+const MAX_LOG_LINE_LENGTH: usize = 500;
+
 pub struct ThreadReturn {
     thread_id: Option<usize>,
     player_move: Result<String, rlua::Error>,
+    log: Vec<String>,
 }
 
 pub(crate) fn execute_lua_in_sandbox(
@@ -28,6 +33,30 @@ pub(crate) fn execute_lua_in_sandbox(
     player_one_turn: bool,
     lua_function: String,
 ) -> Result<String, ErrorType> {
+    execute_lua_in_sandbox_with_log(
+        player_one_sandbox_mutex,
+        player_two_sandbox_mutex,
+        walls,
+        player_one,
+        player_two,
+        player_one_turn,
+        lua_function,
+    )
+    .0
+}
+
+/// Same as execute_lua_in_sandbox but also returns what the bot printed
+/// with log() during the call. The log is kept when the bot fails,
+/// except on timeouts where the thread is killed along with it.
+pub(crate) fn execute_lua_in_sandbox_with_log(
+    player_one_sandbox_mutex: Arc<Mutex<rlua::Lua>>,
+    player_two_sandbox_mutex: Arc<Mutex<rlua::Lua>>,
+    walls: Vec<Wall>,
+    player_one: Player,
+    player_two: Player,
+    player_one_turn: bool,
+    lua_function: String,
+) -> (Result<String, ErrorType>, Vec<String>) {
     let (tx, rx) = std::sync::mpsc::channel::<ThreadReturn>();
     // Sandbox execution of script
     // Limit execution time to 1 second
@@ -35,6 +64,7 @@ pub(crate) fn execute_lua_in_sandbox(
         tx.send(ThreadReturn {
             thread_id: Some(thread_id::get()),
             player_move: Ok(String::new()),
+            log: Vec::new(),
         })
         .unwrap();
 
@@ -49,10 +79,23 @@ pub(crate) fn execute_lua_in_sandbox(
             active_sandbox = player_two_sandbox_mutex.lock().unwrap();
         }
 
-        if let Err(err) = active_sandbox.context(|ctx| ctx.load(&starting_script).exec()) {
+        let result = active_sandbox.context(|ctx| ctx.load(&starting_script).exec());
+        let log: Vec<String> = active_sandbox.context(|ctx| {
+            ctx.globals()
+                .get::<_, rlua::Table>("ExternalGlobalVarLog")
+                .and_then(|log| {
+                    log.sequence_values::<String>()
+                        .take(MAX_LOG_LINES)
+                        .map(|line| line.map(truncate_log_line))
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        if let Err(err) = result {
             tx.send(ThreadReturn {
                 thread_id: None,
                 player_move: Err(err),
+                log: log.clone(),
             })
             .unwrap();
         }
@@ -64,6 +107,7 @@ pub(crate) fn execute_lua_in_sandbox(
         tx.send(ThreadReturn {
             thread_id: None,
             player_move: raw_player_move,
+            log,
         })
         .unwrap();
     });
@@ -77,32 +121,58 @@ pub(crate) fn execute_lua_in_sandbox(
     };
 
     // Second time we either get the result or a timeout error
-    let player_move = match rx.recv_timeout(Duration::from_millis(500)) {
+    match rx.recv_timeout(Duration::from_millis(TURN_TIME_LIMIT_MS)) {
         Ok(returned) => match returned.player_move {
-            Ok(move_string) => move_string,
-            Err(error) => {
-                return Err(ErrorType::RuntimeError {
+            Ok(move_string) => (Ok(move_string), returned.log),
+            Err(error) => (
+                Err(ErrorType::RuntimeError {
                     reason: error.to_string(),
                     fault: Some(get_active_player_type(player_one_turn)),
-                })
-            }
+                }),
+                returned.log,
+            ),
         },
         Err(_) => {
             println!("Timed out");
             terminate_thread(sandbox_thread_id);
-            return Err(ErrorType::TurnTimeout {
-                fault: Some(get_active_player_type(player_one_turn)),
-            });
+            (
+                Err(ErrorType::TurnTimeout {
+                    fault: Some(get_active_player_type(player_one_turn)),
+                }),
+                Vec::new(),
+            )
         }
-    };
+    }
+}
 
-    Ok(player_move)
+/// The log function already limits the lines, but bots can write
+/// to the table directly, so the limit is enforced here as well
+fn truncate_log_line(mut line: String) -> String {
+    if line.len() > MAX_LOG_LINE_LENGTH {
+        let mut end = MAX_LOG_LINE_LENGTH;
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        line.truncate(end);
+    }
+    line
 }
 
+/// The log function is defined anew for every call so
+/// each call starts with an empty log
 fn get_lua_script(function_name: String, game_object: String) -> String {
     return format!(
-        "ExternalGlobalVarResult = {}({})",
-        function_name, game_object
+        "ExternalGlobalVarLog = {{}}
+function log(...)
+    if #ExternalGlobalVarLog >= {} then return end
+    local parts = {{}}
+    for i = 1, select(\"#\", ...) do
+        parts[i] = tostring((select(i, ...)))
+    end
+    ExternalGlobalVarLog[#ExternalGlobalVarLog + 1] = string.sub(table.concat(parts, \" \"), 1, {})
+end
+ExternalGlobalVarResult = {}({})",
+        MAX_LOG_LINES, MAX_LOG_LINE_LENGTH, function_name, game_object
     );
 }
 
@@ -0,0 +1,198 @@
+use serde::Deserialize;
+
+use super::game::{Game, Move, Wall, INITIAL_WALL_COUNT};
+use super::map_mirroring::conditionally_reverse_move;
+use super::methods::{custom_new, load_program};
+use super::player::{Player, PlayerType};
+use super::sandbox::sandbox_executor::execute_lua_in_sandbox;
+use super::turn::convert_player_move_from_string_to_object;
+use super::validation::valid_move;
+
+/**
+ * A scenario is a single position a bot is asked to move in, like a puzzle.
+ * Scenario files are json lists of scenarios. Coordinates are those of the
+ * board as it is rendered, player one starts at the bottom and walls are
+ * given as [x1, y1, x2, y2]. Expected moves are what the bot should return,
+ * they are seen from the perspective of the bot like every move it returns.
+ *
+ * {
+ *     "name": "walks around a wall",
+ *     "player_one": { "x": 4, "y": 5, "wall_count": 10 },
+ *     "player_two": { "x": 4, "y": 0 },
+ *     "walls": [[4, 4, 5, 4]],
+ *     "to_move": "player_one",
+ *     "jump": false,
+ *     "expect": ["3"],
+ *     "forbid": ["1"]
+ * }
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Scenario {
+    pub(crate) name: String,
+    pub(crate) player_one: ScenarioPlayer,
+    pub(crate) player_two: ScenarioPlayer,
+    #[serde(default)]
+    pub(crate) walls: Vec<[i32; 4]>,
+    #[serde(default)]
+    pub(crate) to_move: Side,
+    /// Call onJump instead of onTurn
+    #[serde(default)]
+    pub(crate) jump: bool,
+    /// The scenario passes if the bot returns any of these moves,
+    /// any legal move passes if empty
+    #[serde(default)]
+    pub(crate) expect: Vec<String>,
+    #[serde(default)]
+    pub(crate) forbid: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ScenarioPlayer {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    #[serde(default = "initial_wall_count")]
+    pub(crate) wall_count: i32,
+}
+
+fn initial_wall_count() -> i32 {
+    INITIAL_WALL_COUNT
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Side {
+    #[default]
+    PlayerOne,
+    PlayerTwo,
+}
+
+pub(crate) struct ScenarioResult {
+    pub(crate) name: String,
+    /// The move the bot returned, None if it failed to return one
+    pub(crate) returned: Option<String>,
+    /// Why the scenario failed, None if it passed
+    pub(crate) failure: Option<String>,
+}
+impl ScenarioResult {
+    pub(crate) fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+pub(crate) fn parse_scenarios(json: &str) -> Result<Vec<Scenario>, String> {
+    serde_json::from_str(json).map_err(|error| format!("Invalid scenario file: {}", error))
+}
+
+/// Asks the bot for its move in every scenario, each scenario
+/// gets a fresh sandbox so they don't influence each other.
+pub(crate) fn run_scenarios(
+    script: &str,
+    std: &str,
+    scenarios: &[Scenario],
+) -> Vec<ScenarioResult> {
+    scenarios
+        .iter()
+        .map(|scenario| {
+            let (returned, failure) = match run_scenario(script, std, scenario) {
+                Ok(returned) => {
+                    let failure = check_move(scenario, &returned);
+                    (Some(returned), failure)
+                }
+                Err(error) => (None, Some(error)),
+            };
+            ScenarioResult {
+                name: scenario.name.clone(),
+                returned,
+                failure,
+            }
+        })
+        .collect()
+}
+
+fn run_scenario(script: &str, std: &str, scenario: &Scenario) -> Result<String, String> {
+    let mut game = custom_new(
+        player(&scenario.player_one, PlayerType::Flipped),
+        player(&scenario.player_two, PlayerType::Regular),
+        scenario
+            .walls
+            .iter()
+            .map(|[x1, y1, x2, y2]| Wall {
+                x1: *x1,
+                y1: *y1,
+                x2: *x2,
+                y2: *y2,
+            })
+            .collect(),
+        std.to_string(),
+    );
+    game.player_one_turn = scenario.to_move == Side::PlayerOne;
+    load_program(&game, game.player_one_turn, script)?;
+
+    let returned = execute_lua_in_sandbox(
+        game.player_one_sandbox.clone(),
+        game.player_two_sandbox.clone(),
+        game.walls.clone(),
+        game.player_one.clone(),
+        game.player_two.clone(),
+        game.player_one_turn,
+        if scenario.jump { "onJump" } else { "onTurn" }.to_string(),
+    )
+    .map_err(|error| error.reason())?;
+
+    if !scenario.jump {
+        check_legal(&game, &returned)?;
+    }
+    Ok(returned)
+}
+
+fn player(scenario_player: &ScenarioPlayer, player_type: PlayerType) -> Player {
+    Player::new(
+        scenario_player.x,
+        scenario_player.y,
+        scenario_player.wall_count,
+        player_type,
+    )
+}
+
+/// The move is validated the same way as during a match
+fn check_legal(game: &Game, returned: &str) -> Result<(), String> {
+    let player_move = match convert_player_move_from_string_to_object(Some(returned.to_string())) {
+        Some(Move::Invalid { reason }) => {
+            return Err(format!("Invalid move {}: {}", returned, reason))
+        }
+        Some(player_move) => conditionally_reverse_move(player_move, !game.player_one_turn),
+        None => return Err("The bot did not return a move".to_string()),
+    };
+    let (active, other) = if game.player_one_turn {
+        (&game.player_one, &game.player_two)
+    } else {
+        (&game.player_two, &game.player_one)
+    };
+    valid_move(
+        game.player_one_turn,
+        active,
+        other,
+        &game.walls,
+        player_move,
+    )
+    .map(|_| ())
+    .map_err(|error| format!("Illegal move {}: {}", returned, error.reason()))
+}
+
+fn check_move(scenario: &Scenario, returned: &str) -> Option<String> {
+    if scenario
+        .forbid
+        .iter()
+        .any(|forbidden| forbidden == returned)
+    {
+        return Some(format!("Returned {}, which is forbidden", returned));
+    }
+    if !scenario.expect.is_empty() && !scenario.expect.iter().any(|expected| expected == returned) {
+        return Some(format!(
+            "Returned {}, expected {}",
+            returned,
+            scenario.expect.join(" or ")
+        ));
+    }
+    None
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use crate::game::{
+        scenario::{parse_scenarios, run_scenarios},
+        tests::util::{aj, at, load_script, load_std},
+    };
+
+    #[test]
+    /// The example scenarios pass for the bfs bot
+    fn bfs_passes_its_scenarios() {
+        let scenarios =
+            parse_scenarios(&std::fs::read_to_string("../scripts/scenarios/bfs.json").unwrap())
+                .unwrap();
+        let results = run_scenarios(&load_script("bfs"), &load_std(), &scenarios);
+
+        assert_eq!(results.len(), 4);
+        for result in results {
+            assert!(result.passed(), "{}: {:?}", result.name, result.failure);
+        }
+    }
+
+    #[test]
+    /// Moves are checked against the expectations and the rules
+    fn reports_unexpected_and_illegal_moves() {
+        let scenarios = parse_scenarios(
+            r#"[
+                {
+                    "name": "expects a wall",
+                    "player_one": { "x": 4, "y": 8 },
+                    "player_two": { "x": 4, "y": 0 },
+                    "expect": ["4,4,5,4"]
+                },
+                {
+                    "name": "walks into a wall",
+                    "player_one": { "x": 4, "y": 8 },
+                    "player_two": { "x": 4, "y": 0 },
+                    "walls": [[4, 7, 5, 7]]
+                },
+                {
+                    "name": "any legal move",
+                    "player_one": { "x": 4, "y": 8 },
+                    "player_two": { "x": 4, "y": 0 }
+                }
+            ]"#,
+        )
+        .unwrap();
+        let results = run_scenarios(&aj(at(String::new())), &load_std(), &scenarios);
+
+        assert_eq!(results[0].returned.as_deref(), Some("0"));
+        assert!(results[0]
+            .failure
+            .as_ref()
+            .unwrap()
+            .contains("expected 4,4,5,4"));
+        assert!(results[1]
+            .failure
+            .as_ref()
+            .unwrap()
+            .contains("Illegal move"));
+        assert!(results[2].passed());
+    }
+
+    #[test]
+    /// Errors of the bot fail the scenario instead of the run
+    fn reports_errors_of_the_bot() {
+        let scenarios = parse_scenarios(
+            r#"[{ "name": "start", "player_one": { "x": 4, "y": 8 }, "player_two": { "x": 4, "y": 0 } }]"#,
+        )
+        .unwrap();
+        let crashing = aj("function onTurn()\n    error(\"boom\")\nend".to_string());
+        let results = run_scenarios(&crashing, &load_std(), &scenarios);
+
+        assert_eq!(results[0].returned, None);
+        assert!(results[0].failure.as_ref().unwrap().contains("boom"));
+        assert!(parse_scenarios("[{}]").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::game::{
+        game::{ErrorType, GameResult, Move},
+        methods,
+        tests::util::{aj, load_std},
+    };
+
+    #[test]
+    /// Every turn keeps what its script logged
+    /// and the position it was asked to move in
+    fn logs_are_traced_per_turn() {
+        let script = aj("
+			count = 0
+			function onTurn(context)
+				count = count + 1
+				log(\"turn\", count, context.player.y)
+				log(nil, true)
+				return \"0\"
+			end
+		"
+        .to_string());
+
+        let mut game = methods::new(load_std());
+        let (result, turns) = methods::start(&mut game, script.clone(), script);
+
+        assert!(!matches!(result, GameResult::Error(_)));
+        assert_eq!(game.traces.len(), turns.len());
+        assert_eq!(game.traces[0].log, vec!["turn 1 8", "nil true"]);
+        assert!(game.traces[0].player_one_turn);
+        assert_eq!(game.traces[0].played, Some(Move::Up));
+        // Player two sees the board mirrored
+        assert_eq!(game.traces[1].log, vec!["turn 1 8", "nil true"]);
+        assert!(game.traces[1].context().starts_with("{player={x=4, y=8"));
+        assert_eq!(game.traces[2].log[0], "turn 2 7");
+    }
+
+    #[test]
+    /// The log of a turn that fails is kept
+    fn log_is_kept_when_the_script_fails() {
+        let script = aj("
+			function onTurn()
+				log(\"about to fail\")
+				error(\"boom\")
+			end
+		"
+        .to_string());
+
+        let mut game = methods::new(load_std());
+        let (result, turns) = methods::start(&mut game, script.clone(), script);
+
+        assert!(matches!(
+            result,
+            GameResult::Error(ErrorType::RuntimeError { .. })
+        ));
+        assert!(turns.is_empty());
+        assert_eq!(game.traces.len(), 1);
+        assert_eq!(game.traces[0].log, vec!["about to fail"]);
+        assert_eq!(game.traces[0].played, None);
+    }
+
+    #[test]
+    /// Scripts can't fill the memory with their logs
+    fn log_is_capped() {
+        let script = aj("
+			function onTurn()
+				for i = 1, 1000 do
+					log(string.rep(\"x\", 1000))
+				end
+				return \"0\"
+			end
+		"
+        .to_string());
+
+        let mut game = methods::new(load_std());
+        methods::start(&mut game, script.clone(), script);
+
+        assert_eq!(game.traces[0].log.len(), 100);
+        assert_eq!(game.traces[0].log[0].len(), 500);
+    }
+
+    #[test]
+    /// Writing to the log table directly doesn't get around the limit
+    fn log_table_is_capped() {
+        let script = aj("
+			function onTurn()
+				for i = 1, 1000 do
+					ExternalGlobalVarLog[i] = string.rep(\"x\", 1000)
+				end
+				return \"0\"
+			end
+		"
+        .to_string());
+
+        let mut game = methods::new(load_std());
+        methods::start(&mut game, script.clone(), script);
+
+        assert_eq!(game.traces[0].log.len(), 100);
+        assert_eq!(game.traces[0].log[0].len(), 500);
+    }
+}
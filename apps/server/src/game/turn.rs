@@ -2,65 +2,95 @@ use crate::game::methods::{self, get_active_player_type};
 
 use super::board::populate_board;
 use super::execute_move::{execute_move, execute_move_jump};
-use super::game::{ErrorType, Game, Move};
+use super::game::{ErrorType, Game, Move, TurnTrace};
 use super::map_mirroring::reverse_move;
-use super::sandbox::sandbox_executor::execute_lua_in_sandbox;
+use super::sandbox::sandbox_executor::execute_lua_in_sandbox_with_log;
 use super::validation::valid_move;
 
-pub(super) fn on_turn(game: &mut Game) -> Result<(), ErrorType> {
-    let player_one_sandbox_mutex = game.player_one_sandbox.clone();
-    let player_two_sandbox_mutex = game.player_two_sandbox.clone();
-
-    let player_one = game.player_one.clone();
-    let player_two = game.player_two.clone();
-    let walls = game.walls.clone();
-    let player_one_turn = game.player_one_turn;
-
-    let player_move = match execute_lua_in_sandbox(
-        player_one_sandbox_mutex.clone(),
-        player_two_sandbox_mutex.clone(),
-        walls.clone(),
-        player_one.clone(),
-        player_two.clone(),
-        player_one_turn,
-        "onTurn".to_string(),
-    ) {
-        Ok(player_move) => player_move,
-        Err(error) => return Err(error),
-    };
+/// Where the moves of the active player come from. Moves are returned
+/// in board coordinates, sources mirror them for player two themselves.
+pub(crate) trait MoveSource {
+    fn on_turn(&mut self, game: &Game) -> Result<Move, ErrorType>;
+    fn on_jump(&mut self, game: &Game) -> Result<Move, ErrorType>;
+}
 
-    let debugging_enabled = std::env::var("DEBUG")
-        .unwrap_or(String::from("false"))
-        .to_lowercase()
-        == "true";
+/// The scripts of the players, run in their sandboxes
+#[derive(Default)]
+struct LuaMoves {
+    log: Vec<String>,
+}
+impl LuaMoves {
+    fn run_lua_function(&mut self, game: &Game, lua_function: &str) -> Result<String, ErrorType> {
+        let (player_move, log) = execute_lua_in_sandbox_with_log(
+            game.player_one_sandbox.clone(),
+            game.player_two_sandbox.clone(),
+            game.walls.clone(),
+            game.player_one.clone(),
+            game.player_two.clone(),
+            game.player_one_turn,
+            lua_function.to_string(),
+        );
+        self.log.extend(log);
+        player_move
+    }
+}
+impl MoveSource for LuaMoves {
+    fn on_turn(&mut self, game: &Game) -> Result<Move, ErrorType> {
+        let player_move = self.run_lua_function(game, "onTurn")?;
+
+        let debugging_enabled = std::env::var("DEBUG")
+            .unwrap_or(String::from("false"))
+            .to_lowercase()
+            == "true";
+
+        // Check for debug flag
+        let split = player_move.split(" ");
+        if debugging_enabled
+            && split.clone().count() > 0
+            && split.clone().next().unwrap() == "#debug"
+        {
+            println!("Incoming {}", player_move);
+            return Err(ErrorType::GameError {
+                reason: format!(
+                    "Player: {:?}\n<br/>Opponent: {:?}\n<br/>Walls: {:?}\n<br/>Bot ({}) debugging log:\n```\n{}\n```\n<br/>",
+                    game.player_one, game.player_two, game.walls, if game.player_one_turn {"🟩"} else {"🟥"}, split.skip(1).collect::<Vec<&str>>().join(" ")
+                ),
+                fault: None,
+            });
+        }
 
-    // Check for debug flag
-    let split = player_move.split(" ");
-    if debugging_enabled && split.clone().count() > 0 && split.clone().next().unwrap() == "#debug" {
-        println!("Incoming {}", player_move);
-        return Err(ErrorType::GameError {
-            reason: format!(
-                "Player: {:?}\n<br/>Opponent: {:?}\n<br/>Walls: {:?}\n<br/>Bot ({}) debugging log:\n```\n{}\n```\n<br/>",
-                player_one, player_two, walls, if player_one_turn {"🟩"} else {"🟥"}, split.skip(1).collect::<Vec<&str>>().join(" ")
-            ),
-            fault: None,
-        });
+        // onTurn fail if: not 1 and not 7
+        if player_move.len() != 1 && player_move.len() != 7 {
+            return Err(ErrorType::RuntimeError {
+                reason: format!("Invalid input: {}", player_move),
+                fault: Some(get_active_player_type(game.player_one_turn)),
+            });
+        }
+        convert_lua_move(game.player_one_turn, player_move)
     }
 
-    // onTurn fail if: not 1 and not 7
-    // onJump fail if: not 1
-    if player_move.len() != 1 && player_move.len() != 7 {
-        return Err(ErrorType::RuntimeError {
-            reason: format!("Invalid input: {}", player_move),
-            fault: Some(get_active_player_type(game.player_one_turn)),
-        });
+    fn on_jump(&mut self, game: &Game) -> Result<Move, ErrorType> {
+        let player_move = self.run_lua_function(game, "onJump")?;
+
+        // onJump fail if: not 1
+        if player_move.len() != 1 {
+            return Err(ErrorType::GameError {
+                reason: format!(
+                    "Invalid return format from onJump, return can only be a number between 0-3"
+                ),
+                fault: Some(get_active_player_type(game.player_one_turn)),
+            });
+        }
+        convert_lua_move(game.player_one_turn, player_move)
     }
+}
 
+fn convert_lua_move(player_one_turn: bool, player_move: String) -> Result<Move, ErrorType> {
     let mut player_move = convert_player_move_from_string_to_object(Some(player_move));
     if let Some(Move::Invalid { reason }) = player_move {
         return Err(ErrorType::GameError {
             reason,
-            fault: Some(get_active_player_type(game.player_one_turn)),
+            fault: Some(get_active_player_type(player_one_turn)),
         });
     }
 
@@ -68,83 +98,57 @@ pub(super) fn on_turn(game: &mut Game) -> Result<(), ErrorType> {
         player_move = Some(reverse_move(player_move.unwrap()));
     }
 
-    if player_move.is_none() {
-        return Err(ErrorType::GameError {
+    match player_move {
+        Some(player_move) => Ok(player_move),
+        None => Err(ErrorType::GameError {
             reason: "Player did not return a move".to_string(),
-            fault: Some(get_active_player_type(game.player_one_turn)),
-        });
+            fault: Some(get_active_player_type(player_one_turn)),
+        }),
     }
+}
 
-    let (active_player, opponent) = match player_one_turn {
-        true => (player_one.clone(), player_two.clone()),
-        false => (player_two.clone(), player_one.clone()),
+/// Plays the turn of the active script and keeps a trace of it
+pub(crate) fn on_turn(game: &mut Game) -> Result<(), ErrorType> {
+    let mut trace = TurnTrace::new(game);
+    let mut source = LuaMoves::default();
+    let result = play_turn(game, &mut source);
+    if result.is_ok() {
+        trace.played = game.last_move.clone();
+    }
+    trace.log = source.log;
+    game.traces.push(trace);
+    result
+}
+
+/// Plays the turn of the active player with a move from the source,
+/// the move is validated and applied to the game.
+pub(crate) fn play_turn(game: &mut Game, source: &mut dyn MoveSource) -> Result<(), ErrorType> {
+    let player_move = source.on_turn(game)?;
+
+    let (active_player, opponent) = match game.player_one_turn {
+        true => (game.player_one.clone(), game.player_two.clone()),
+        false => (game.player_two.clone(), game.player_one.clone()),
     };
 
     let run_on_jump = match valid_move(
-        player_one_turn,
+        game.player_one_turn,
         &active_player,
         &opponent,
-        &walls,
-        player_move.clone().unwrap(),
+        &game.walls,
+        player_move.clone(),
     ) {
         Ok(value) => !value,
         Err(error) => return Err(error),
     };
 
-    let mut mutable_walls = game.walls.clone();
-    let (first, other) = methods::get_active_player(game);
-
     if run_on_jump {
         // TODO refactor this, this should recursivly call on turn again, instead of this code repeat
-        let on_jump_player_move = match execute_lua_in_sandbox(
-            player_one_sandbox_mutex,
-            player_two_sandbox_mutex,
-            walls.clone(),
-            player_one.clone(),
-            player_two.clone(),
-            player_one_turn,
-            "onJump".to_string(),
-        ) {
-            Ok(player_move) => player_move,
-            Err(error) => return Err(error),
-        };
-
-        if on_jump_player_move.len() != 1 {
-            return Err(ErrorType::GameError {
-                reason: format!(
-                    "Invalid return format from onJump, return can only be a number between 0-3"
-                ),
-                fault: Some(get_active_player_type(game.player_one_turn)),
-            });
-        }
-        let mut converted_on_jump_player_move =
-            convert_player_move_from_string_to_object(Some(on_jump_player_move));
-        if let Some(Move::Invalid { reason }) = converted_on_jump_player_move {
-            return Err(ErrorType::GameError {
-                reason,
-                fault: Some(get_active_player_type(game.player_one_turn)),
-            });
-        }
-
-        if should_reverse_player_move(player_one_turn, &converted_on_jump_player_move) {
-            converted_on_jump_player_move =
-                Some(reverse_move(converted_on_jump_player_move.unwrap()));
-        }
+        let on_jump_player_move = source.on_jump(game)?;
 
-        if converted_on_jump_player_move.is_none() {
-            return Err(ErrorType::GameError {
-                reason: "Player did not return a move".to_string(),
-                fault: Some(get_active_player_type(game.player_one_turn)),
-            });
-        }
-
-        if let Err(error) = execute_move_jump(
-            first,
-            other,
-            &converted_on_jump_player_move.clone().unwrap(),
-        ) {
-            return Err(error);
-        }
+        let mutable_walls = game.walls.clone();
+        let player_one_turn = game.player_one_turn;
+        let (first, other) = methods::get_active_player(game);
+        execute_move_jump(first, other, &on_jump_player_move)?;
 
         // Check that move was correct
         if first.x == other.x && first.y == other.y {
@@ -153,7 +157,7 @@ pub(super) fn on_turn(game: &mut Game) -> Result<(), ErrorType> {
                     "Player ended up on top of opponent in jump at ({}, {})",
                     first.x, first.y
                 ),
-                fault: Some(get_active_player_type(game.player_one_turn)),
+                fault: Some(get_active_player_type(player_one_turn)),
             });
         }
 
@@ -165,16 +169,19 @@ pub(super) fn on_turn(game: &mut Game) -> Result<(), ErrorType> {
                         "Player tried to jump into a wall at ({}, {})",
                         first.x, first.y
                     ),
-                    fault: Some(get_active_player_type(game.player_one_turn)),
+                    fault: Some(get_active_player_type(player_one_turn)),
                 });
             }
         }
     } else {
-        execute_move(&mut mutable_walls, first, &player_move.clone().unwrap()).unwrap();
+        let mut mutable_walls = game.walls.clone();
+        let (first, _) = methods::get_active_player(game);
+        execute_move(&mut mutable_walls, first, &player_move).unwrap();
         // Reassign walls
         game.walls = mutable_walls;
     }
 
+    game.last_move = Some(player_move);
     game.player_one_turn = !game.player_one_turn;
 
     game.turns.push(populate_board(
@@ -192,7 +199,9 @@ fn should_reverse_player_move(player_one_turn: bool, player_move: &Option<Move>)
 		std::mem::discriminant(&player_move.clone().unwrap()) != std::mem::discriminant(&Move::Invalid { reason: String::new() });
 }
 
-fn convert_player_move_from_string_to_object(raw_player_move: Option<String>) -> Option<Move> {
+pub(super) fn convert_player_move_from_string_to_object(
+    raw_player_move: Option<String>,
+) -> Option<Move> {
     return match raw_player_move {
         Some(value) => match value.as_str() {
             "0" => Some(Move::Up),
@@ -0,0 +1,38 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::{job_model::Job, submission_model::Submission};
+use crate::external_related::forge_client::ForgeClient;
+
+use super::submission_job::{place_once, retire_previous_versions};
+
+pub(crate) const API_SUBMISSION_JOB: &str = "api_submission";
+
+/// Plays the placement matches of a bot submitted through the http api,
+/// the payload is the id of the submission. The new version replaces the
+/// previous versions of the bot. The submitter follows the progress
+/// through the api, only the opponents' issues are notified.
+pub(crate) fn process_api_submission(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let challenger = match Submission::by_id(&job.payload, conn) {
+        Some(submission) => submission,
+        None => return Ok(format!("Submission {} no longer exists", job.payload)),
+    };
+    if !challenger.is_active() {
+        return Ok(format!(
+            "Submission {} is {} and will not be matched",
+            challenger.id,
+            challenger.status()
+        ));
+    }
+
+    retire_previous_versions(&challenger, conn, forge)?;
+    place_once(job, &challenger, conn)?;
+
+    match forge.publish_repo(conn) {
+        Ok(msg) => Ok(msg),
+        Err(error) => Ok(error.to_string()),
+    }
+}
@@ -0,0 +1,127 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::{job_model::Job, submission_model::Submission};
+use crate::external_related::forge_client::{CloseType, ForgeClient, ForgeError};
+use crate::external_related::github::webhook_events::{
+    command_usage, parse_command, IssueCommand, RETIRED_LABEL,
+};
+
+use super::submission_job::{parse_payload, place_once, process_resubmission};
+
+pub(crate) const COMMAND_JOB: &str = "command";
+
+/// Executes a command posted as a comment on a challenger issue,
+/// the result is always posted back to the issue.
+pub(crate) fn process_command(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let webhook_post = parse_payload(&job.payload)?;
+    let issue_number = webhook_post.issue.number;
+    let comment = match &webhook_post.comment {
+        Some(comment) => comment,
+        None => return Err("Command job without a comment".to_string()),
+    };
+
+    let reply = match parse_command(&comment.body) {
+        Some(Ok(IssueCommand::Status)) => status(issue_number, conn),
+        Some(Ok(IssueCommand::Rematch)) => rematch(issue_number, job, conn, forge)?,
+        Some(Ok(IssueCommand::Retire)) => retire(issue_number, conn, forge)?,
+        Some(Ok(IssueCommand::Resubmit)) => return process_resubmission(job, conn, forge),
+        Some(Err(unknown)) => format!("Unknown command `{}`<br>{}", unknown, command_usage()),
+        None => return Ok("Comment is not a command".to_string()),
+    };
+
+    forge.create_issue_comment(issue_number, &reply)?;
+    Ok(reply)
+}
+
+fn status(issue_number: i32, conn: &SqliteConnection) -> String {
+    let submission = match Submission::latest_by_issue_number(issue_number, conn) {
+        Some(submission) => submission,
+        None => return "There is no submission for this issue".to_string(),
+    };
+
+    let state = if submission.disqualified >= 1 {
+        "Disqualified ❌"
+    } else if submission.retired >= 1 {
+        "Retired 💤"
+    } else {
+        "Active ✅"
+    };
+
+    let active_submissions: Vec<Submission> = Submission::list(conn)
+        .into_iter()
+        .filter(|current| current.disqualified == 0 && current.retired == 0)
+        .collect();
+    let rank = if state.starts_with("Active") {
+        let better = active_submissions
+            .iter()
+            .filter(|current| current.mmr > submission.mmr)
+            .count();
+        format!("{} of {}", better + 1, active_submissions.len())
+    } else {
+        "-".to_string()
+    };
+
+    format!(
+        "Script-id: {}<br>State: {}<br>MMR: {}<br>Rank: {}<br>Wins: {} &#124; Matches played: {}",
+        submission.id,
+        state,
+        submission.mmr.round(),
+        rank,
+        submission.wins,
+        submission.matches_played
+    )
+}
+
+fn rematch(
+    issue_number: i32,
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let submission = match Submission::by_issue_number(issue_number, conn) {
+        Some(submission) => submission,
+        None => return Ok("There is no active submission for this issue".to_string()),
+    };
+    if submission.disqualified >= 1 {
+        return Ok(format!(
+            "Submission {} is disqualified and cannot be rematched, use `/resubmit` after changing your code",
+            submission.id
+        ));
+    }
+
+    place_once(job, &submission, conn)?;
+    match forge.publish_repo(conn) {
+        Ok(_) => (),
+        Err(error) => println!("{}", error),
+    }
+    Ok(format!("Rematch of submission {} finished", submission.id))
+}
+
+fn retire(
+    issue_number: i32,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, ForgeError> {
+    let mut submission = match Submission::by_issue_number(issue_number, conn) {
+        Some(submission) => submission,
+        None => return Ok("There is no active submission for this issue".to_string()),
+    };
+
+    // Retired only once the issue shows it, a retry finds it still active
+    forge.add_label(issue_number, RETIRED_LABEL)?;
+    forge.close_issue(CloseType::Completed, issue_number)?;
+    submission.retired = 1;
+    submission.save(conn);
+    match forge.publish_repo(conn) {
+        Ok(_) => (),
+        Err(error) => println!("{}", error),
+    }
+    Ok(format!(
+        "Submission {} has been retired and will no longer be matched<br>Reopen the issue or use `/resubmit` to compete again",
+        submission.id
+    ))
+}
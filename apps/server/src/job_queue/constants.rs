@@ -0,0 +1,8 @@
+pub(crate) const MAX_JOB_ATTEMPTS: i32 = 5;
+pub(crate) const JOB_RETRY_BASE_DELAY_SECONDS: i64 = 30;
+pub(crate) const JOB_RETRY_MAX_DELAY_SECONDS: i64 = 60 * 60;
+pub(crate) const JOB_POLL_INTERVAL_MS: u64 = 2000;
+/// Checkpoint of jobs whose placement matches have been played
+pub(crate) const PLACED_CHECKPOINT: &str = "placed";
+/// Checkpoint of scheduled matchmaking jobs whose round has been played
+pub(crate) const PLAYED_CHECKPOINT: &str = "played";
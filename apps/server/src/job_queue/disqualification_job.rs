@@ -0,0 +1,77 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::{
+    disqualification_model::Disqualification, submission_model::Submission,
+};
+use crate::external_related::forge_client::ForgeClient;
+use crate::match_maker::disqualifications::{rerun, RerunOutcome};
+
+use super::issue_comment_job::{queue_issue_comments, queue_match_reports, IssueComment};
+
+pub(crate) const DISQUALIFICATION_RERUN_JOB: &str = "disqualification_rerun";
+
+/// Replays a match that ended in a turn timeout, the payload is the id
+/// of the disqualification. The outcome is posted to the submission's
+/// issue through a queued comment, since a retry of this job finds the
+/// disqualification already resolved.
+pub(crate) fn process_disqualification_rerun(
+    payload: &str,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let mut record = match Disqualification::by_id(payload, conn) {
+        Some(record) => record,
+        None => return Ok(format!("Disqualification {} no longer exists", payload)),
+    };
+    let submission = match Submission::by_id(&record.submission, conn) {
+        Some(submission) => submission,
+        None => return Ok(format!("Submission {} no longer exists", record.submission)),
+    };
+    let opponent = record.opponent.clone().unwrap_or_default();
+
+    match rerun(&mut record, conn)? {
+        RerunOutcome::AlreadyResolved => Ok(format!(
+            "Disqualification {} has already been resolved",
+            record.id
+        )),
+        RerunOutcome::Reproduced => {
+            let msg = format!(
+                "The match against {} was played again to rule out a server issue, submission {} failed the same way so the disqualification stands",
+                opponent, record.submission
+            );
+            queue_outcome(&submission, &msg, conn)?;
+            Ok(msg)
+        }
+        RerunOutcome::Reinstated(reports) => {
+            let msg = format!(
+                "Submission {} has been reinstated, the {} against {} was caused by the server. The match has been played again",
+                record.submission,
+                record.reason.to_lowercase(),
+                opponent
+            );
+            queue_outcome(&submission, &msg, conn)?;
+            queue_match_reports(&reports, conn)?;
+            if let Err(error) = forge.publish_repo(conn) {
+                println!("Could not update README.md: {}", error);
+            }
+            Ok(msg)
+        }
+    }
+}
+
+fn queue_outcome(
+    submission: &Submission,
+    msg: &str,
+    conn: &SqliteConnection,
+) -> Result<(), String> {
+    if !submission.has_issue() {
+        return Ok(());
+    }
+    queue_issue_comments(
+        vec![IssueComment {
+            issue_number: submission.issue_number,
+            body: msg.to_string(),
+        }],
+        conn,
+    )
+}
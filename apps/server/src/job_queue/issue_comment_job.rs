@@ -0,0 +1,72 @@
+use diesel::SqliteConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::models::job_model::Job;
+use crate::external_related::forge_client::ForgeClient;
+use crate::match_maker::match_executor::MatchReport;
+
+pub(crate) const ISSUE_COMMENTS_JOB: &str = "issue_comments";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IssueComment {
+    pub issue_number: i32,
+    pub body: String,
+}
+
+/// Queues comments that the worker posts in order. Used for outcomes
+/// that are already saved, like played matches, so that a comment
+/// that can't be posted is retried without playing the matches again.
+pub(crate) fn queue_issue_comments(
+    comments: Vec<IssueComment>,
+    conn: &SqliteConnection,
+) -> Result<(), String> {
+    if comments.is_empty() {
+        return Ok(());
+    }
+    let payload = serde_json::to_string(&comments)
+        .map_err(|error| format!("Could not serialize comments: {}", error))?;
+    match Job::create(ISSUE_COMMENTS_JOB, &payload, conn) {
+        Some(_) => Ok(()),
+        None => Err("Could not queue issue comments".to_string()),
+    }
+}
+
+/// Queues the reports of the matches that were played for an issue
+pub(crate) fn queue_match_reports(
+    match_reports: &[(MatchReport, MatchReport)],
+    conn: &SqliteConnection,
+) -> Result<(), String> {
+    let comments = match_reports
+        .iter()
+        .flat_map(|(report1, report2)| [report1, report2])
+        .filter(|report| report.has_issue())
+        .map(|report| IssueComment {
+            issue_number: report.issue_number,
+            body: report.report.clone(),
+        })
+        .collect();
+    queue_issue_comments(comments, conn)
+}
+
+/// Posts queued comments, the checkpoint is the number of comments
+/// that have been posted so a retry continues with the next one.
+pub(crate) fn process_issue_comments(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let comments: Vec<IssueComment> = serde_json::from_str(&job.payload)
+        .map_err(|error| format!("Could not parse job payload: {}", error))?;
+
+    let mut posted = job
+        .checkpoint
+        .as_deref()
+        .and_then(|checkpoint| checkpoint.parse::<usize>().ok())
+        .unwrap_or(0);
+    for comment in comments.iter().skip(posted) {
+        forge.create_issue_comment(comment.issue_number, &comment.body)?;
+        posted += 1;
+        job.save_checkpoint(&posted.to_string(), conn);
+    }
+    Ok(format!("Posted {} comment(s)", comments.len()))
+}
@@ -0,0 +1,30 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::job_model::Job;
+use crate::external_related::forge_client::ForgeClient;
+use crate::match_maker::scheduler::run_scheduled_matchmaking;
+
+use super::constants::PLAYED_CHECKPOINT;
+
+pub(crate) const SCHEDULED_MATCHMAKING_JOB: &str = "scheduled_matchmaking";
+
+/// Plays a round of matchmaking, queued by the `schedule-matchmaking`
+/// command. It runs on the worker of the server so that spectators can
+/// follow the matches live, a retry doesn't play the round again.
+pub(crate) fn process_scheduled_matchmaking(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    if job.checkpoint.as_deref() != Some(PLAYED_CHECKPOINT) {
+        if !run_scheduled_matchmaking(conn)? {
+            return Ok("Not enough submissions to execute match-queue".to_string());
+        }
+        job.save_checkpoint(PLAYED_CHECKPOINT, conn);
+    }
+
+    match forge.publish_repo(conn) {
+        Ok(msg) => Ok(msg),
+        Err(error) => Ok(error.to_string()),
+    }
+}
@@ -0,0 +1,13 @@
+pub(crate) mod api_submission_job;
+pub(crate) mod command_job;
+pub(crate) mod constants;
+pub(crate) mod disqualification_job;
+pub(crate) mod issue_comment_job;
+pub(crate) mod matchmaking_job;
+pub(crate) mod pull_request_job;
+pub(crate) mod submission_job;
+pub(crate) mod worker;
+
+mod tests {
+    mod pipeline_tests;
+}
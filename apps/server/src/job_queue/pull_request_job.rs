@@ -0,0 +1,355 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::bot_model::Bot;
+use crate::backend::models::job_model::Job;
+use crate::backend::models::submission_model::Submission;
+use crate::backend::models::user_model::User;
+use crate::external_related::forge_client::{
+    ChangedFile, CheckRunConclusion, CheckRunReport, ForgeClient,
+};
+use crate::external_related::github::webhook_schema::{PullRequest, PullRequestPayload};
+use crate::external_related::repo_updater::is_plagiarism_enabled;
+use crate::match_maker::placements::{preview_placements, PlacementPreview};
+use crate::plagiarism::similarity_check::{check_similarity, Verdict};
+
+use super::submission_job::{flagged_notice, place_once, retire_previous_versions};
+
+pub(crate) const PULL_REQUEST_CHECK_JOB: &str = "pull_request_check";
+pub(crate) const PULL_REQUEST_MERGE_JOB: &str = "pull_request_merge";
+pub(crate) const CHECK_RUN_NAME: &str = "Placement matches";
+/// Bots are submitted as lua files in this directory
+pub(crate) const BOTS_DIRECTORY: &str = "bots/";
+
+/// Plays the placement matches of the bot in a pull request without
+/// adding it to the ladder and reports the outcome as a check run.
+pub(crate) fn process_pull_request_check(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let event = parse_pull_request_payload(&job.payload)?;
+    let pull_request = &event.pull_request;
+
+    // The checkpoint is the id of the check run, a retry completes
+    // the run created by the earlier attempt instead of adding one
+    let check_run_id = match job
+        .checkpoint
+        .as_deref()
+        .and_then(|checkpoint| checkpoint.parse::<i64>().ok())
+    {
+        Some(check_run_id) => check_run_id,
+        None => {
+            let check_run_id = forge.create_check_run(&pull_request.head.sha, CHECK_RUN_NAME)?;
+            job.save_checkpoint(&check_run_id.to_string(), conn);
+            check_run_id
+        }
+    };
+    let files = forge.pull_request_files(pull_request.number)?;
+    let report = match select_bot_file(&files) {
+        Ok(Some(file)) => {
+            let script = forge.file_content(&file.filename, &pull_request.head.sha)?;
+            evaluate_bot(&file.filename, &script, pull_request, conn)
+        }
+        Ok(None) => CheckRunReport {
+            conclusion: CheckRunConclusion::Neutral,
+            title: "No bot in this pull request".to_string(),
+            summary: format!("No lua files were added or changed in `{}`", BOTS_DIRECTORY),
+        },
+        Err(reason) => CheckRunReport {
+            conclusion: CheckRunConclusion::Failure,
+            title: "Invalid bot submission".to_string(),
+            summary: reason,
+        },
+    };
+
+    forge.complete_check_run(check_run_id, &report)?;
+    Ok(report.title)
+}
+
+/// Adds the bot of a merged pull request to the ladder, the
+/// placement results are posted as comments on the pull request.
+pub(crate) fn process_pull_request_merge(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let event = parse_pull_request_payload(&job.payload)?;
+    let pull_request = &event.pull_request;
+
+    // A previous attempt of this job might already have registered the bot
+    let submission = match Submission::by_issue_number(pull_request.number, conn) {
+        Some(submission) => submission,
+        None => {
+            let files = forge.pull_request_files(pull_request.number)?;
+            let file = match select_bot_file(&files) {
+                Ok(Some(file)) => file,
+                Ok(None) => return Ok("Pull request does not contain a bot".to_string()),
+                Err(reason) => {
+                    forge.create_issue_comment(pull_request.number, &reason)?;
+                    return Ok(reason);
+                }
+            };
+            let script = forge.file_content(&file.filename, &pull_request.head.sha)?;
+            match register_bot(&file.filename, &script, pull_request, conn, forge)? {
+                Some(submission) => submission,
+                None => return Ok("This bot has already been submitted before".to_string()),
+            }
+        }
+    };
+
+    place_once(job, &submission, conn)?;
+    match forge.publish_repo(conn) {
+        Ok(msg) => Ok(msg),
+        Err(error) => Ok(error.to_string()),
+    }
+}
+
+/// Picks the bot out of the files changed by a pull request.
+/// Returns None if no bot was changed and an error if there
+/// is more than one, each pull request submits a single bot.
+pub(crate) fn select_bot_file(files: &[ChangedFile]) -> Result<Option<&ChangedFile>, String> {
+    let bots: Vec<&ChangedFile> = files
+        .iter()
+        .filter(|file| {
+            file.filename.starts_with(BOTS_DIRECTORY)
+                && file.filename.ends_with(".lua")
+                && file.status != "removed"
+        })
+        .collect();
+
+    match bots.len() {
+        0 => Ok(None),
+        1 => Ok(Some(bots[0])),
+        _ => Err(format!(
+            "A pull request may only add or change one bot, found: {}",
+            bots.iter()
+                .map(|file| format!("`{}`", file.filename))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn evaluate_bot(
+    path: &str,
+    script: &str,
+    pull_request: &PullRequest,
+    conn: &SqliteConnection,
+) -> CheckRunReport {
+    // The author's other bots are excluded from the placements and the similarity check
+    let user_id = User::by_username(&pull_request.user.login, conn)
+        .map(|user| user.id)
+        .unwrap_or_default();
+
+    let similarity = if is_plagiarism_enabled() {
+        let report = check_similarity(script, &user_id, conn);
+        if report.verdict() == Verdict::Rejected {
+            return CheckRunReport {
+                conclusion: CheckRunConclusion::Failure,
+                title: "Bot is too similar to an existing bot".to_string(),
+                summary: format!(
+                    "This bot is too similar to a bot submitted by another user\n\n{}",
+                    report.to_markdown()
+                ),
+            };
+        }
+        Some(report)
+    } else {
+        None
+    };
+
+    let candidate = Submission::unsaved(
+        &user_id,
+        script,
+        Some(&pull_request.title),
+        &pull_request.html_url,
+        pull_request.number,
+    );
+    let preview = preview_placements(&candidate, conn);
+    let mut report = placement_report(path, &preview);
+    if let Some(similarity) = similarity.filter(|report| report.verdict() == Verdict::Flagged) {
+        report.summary += &flagged_notice(&similarity);
+    }
+    report
+}
+
+fn placement_report(path: &str, preview: &PlacementPreview) -> CheckRunReport {
+    let mut summary = format!("Bot: `{}`\n\n", path);
+    if preview.matches.is_empty() {
+        summary += "There are no bots in the ladder to play against yet";
+        return CheckRunReport {
+            conclusion: CheckRunConclusion::Neutral,
+            title: "No opponents".to_string(),
+            summary,
+        };
+    }
+
+    summary += "| Opponent | Result |\n| :-- | :-- |\n";
+    for current in preview.matches.iter() {
+        let result = match (current.won, &current.error_msg) {
+            (Some(true), _) => "✅ Win".to_string(),
+            (Some(false), None) => "❌ Loss".to_string(),
+            (Some(false), Some(error)) | (None, Some(error)) => {
+                format!("⚠️ {}", error.replace('\n', " ").replace('|', "&#124;"))
+            }
+            (None, None) => "Draw".to_string(),
+        };
+        summary += &format!(
+            "| [{}]({}) | {} |\n",
+            current.opponent.id, current.opponent.issue_url, result
+        );
+    }
+
+    let wins = preview
+        .matches
+        .iter()
+        .filter(|current| current.won == Some(true))
+        .count();
+    let losses = preview
+        .matches
+        .iter()
+        .filter(|current| current.won == Some(false))
+        .count();
+    summary += &format!("\nProjected MMR: {}", preview.mmr.round());
+
+    if let Some(disqualifying) = preview.matches.iter().find(|current| current.disqualified) {
+        return CheckRunReport {
+            conclusion: CheckRunConclusion::Failure,
+            title: "Bot would be disqualified".to_string(),
+            summary: format!(
+                "{}\n\nThe bot crashed or timed out against {} and would be disqualified",
+                summary, disqualifying.opponent.id
+            ),
+        };
+    }
+
+    CheckRunReport {
+        conclusion: CheckRunConclusion::Success,
+        title: format!("{} wins, {} losses", wins, losses),
+        summary,
+    }
+}
+
+/// Returns None if the bot is a duplicate, the reason
+/// is posted to the pull request in that case
+fn register_bot(
+    path: &str,
+    script: &str,
+    pull_request: &PullRequest,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<Option<Submission>, String> {
+    let login = &pull_request.user.login;
+    let user = match User::by_username(login, conn) {
+        Some(user) => user,
+        None => match User::create(login, conn) {
+            Some(user) => user,
+            None => return Err(format!("Could not create user {}", login)),
+        },
+    };
+
+    // The file name identifies the bot, a pull request changing
+    // the same file again submits a new version of it
+    let bot = match Bot::for_submission(&user.id, &bot_name(path), pull_request.number, conn) {
+        Some(bot) => bot,
+        None => return Err(format!("Could not create bot {}", path)),
+    };
+
+    let submission = match Submission::create(
+        &user.id,
+        &bot.id,
+        script,
+        Some(&pull_request.title),
+        0,
+        &pull_request.html_url,
+        pull_request.number,
+        conn,
+    ) {
+        Ok(Some(submission)) => submission,
+        Ok(None) => return Err("Could not create submission".to_string()),
+        Err(_) => {
+            forge.create_issue_comment(
+                pull_request.number,
+                "This bot has already been submitted before",
+            )?;
+            return Ok(None);
+        }
+    };
+
+    retire_previous_versions(&submission, conn, forge)?;
+    forge.create_issue_comment(
+        pull_request.number,
+        &format!(
+            "User: {}<br>Script-id: {}<br>`{}` has joined the ladder!<br>Your placement matches are being played...",
+            login, submission.id, path
+        ),
+    )?;
+    Ok(Some(submission))
+}
+
+/// bots/wall_builder.lua is the bot wall_builder
+fn bot_name(path: &str) -> String {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name
+        .strip_suffix(".lua")
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+fn parse_pull_request_payload(payload: &str) -> Result<PullRequestPayload, String> {
+    serde_json::from_str(payload).map_err(|error| format!("Could not parse job payload: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bot_name, select_bot_file};
+    use crate::external_related::forge_client::ChangedFile;
+
+    fn file(filename: &str, status: &str) -> ChangedFile {
+        ChangedFile {
+            filename: filename.to_string(),
+            status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn selects_the_changed_bot() {
+        let files = vec![
+            file("README.md", "modified"),
+            file("bots/upwards.lua", "added"),
+            file("scripts/helper.lua", "added"),
+        ];
+        assert_eq!(
+            select_bot_file(&files),
+            Ok(Some(&file("bots/upwards.lua", "added")))
+        );
+    }
+
+    #[test]
+    fn ignores_removed_and_non_lua_files() {
+        let files = vec![
+            file("bots/old.lua", "removed"),
+            file("bots/notes.md", "added"),
+        ];
+        assert_eq!(select_bot_file(&files), Ok(None));
+        assert_eq!(select_bot_file(&[]), Ok(None));
+    }
+
+    #[test]
+    fn rejects_multiple_bots() {
+        let files = vec![
+            file("bots/one.lua", "added"),
+            file("bots/two.lua", "modified"),
+        ];
+        assert_eq!(
+            select_bot_file(&files),
+            Err("A pull request may only add or change one bot, found: `bots/one.lua`, `bots/two.lua`".to_string())
+        );
+    }
+
+    #[test]
+    fn names_bots_after_their_file() {
+        assert_eq!(bot_name("bots/wall_builder.lua"), "wall_builder");
+        assert_eq!(bot_name("bots/nested/v2.lua"), "v2");
+    }
+}
@@ -0,0 +1,351 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::bot_model::Bot;
+use crate::backend::models::job_model::Job;
+use crate::backend::models::submission_model::Submission;
+use crate::backend::models::user_model::User;
+use crate::external_related::code_unwrapper::unwrap_code;
+use crate::external_related::forge_client::{CloseType, ForgeClient, ForgeError};
+use crate::external_related::github::webhook_events::RETIRED_LABEL;
+use crate::external_related::github::webhook_schema::GithubPayload;
+use crate::external_related::repo_updater::is_plagiarism_enabled;
+use crate::match_maker::placements::run_placements;
+use crate::plagiarism::similarity_check::{check_similarity, SimilarityReport, Verdict};
+
+use super::constants::PLACED_CHECKPOINT;
+use super::issue_comment_job::{queue_issue_comments, IssueComment};
+
+pub(crate) const SUBMISSION_JOB: &str = "submission";
+pub(crate) const RESUBMISSION_JOB: &str = "resubmission";
+
+/// Runs the full placement pipeline for a challenger issue.
+///
+/// Outcomes that are reported back to the submitter (invalid code,
+/// duplicates etc.) are considered handled and return Ok, an Err
+/// means the job should be retried.
+pub(crate) fn process_submission(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let webhook_post = parse_payload(&job.payload)?;
+
+    // A previous attempt of this job might already have registered
+    // the submission, in that case we continue where it left off
+    let challenger = match Submission::by_issue_number(webhook_post.issue.number, conn) {
+        Some(submission) => submission,
+        None => match register_submission(&webhook_post, conn, forge)? {
+            Registration::Registered(submission) => submission,
+            Registration::Rejected(msg) => return Ok(msg),
+        },
+    };
+
+    if challenger.disqualified >= 1 {
+        let msg = format!(
+            "Submission {} is disqualified and will not be matched again, change your code to resubmit",
+            challenger.id
+        );
+        forge.create_issue_comment(webhook_post.issue.number, &msg)?;
+        forge.close_issue(CloseType::NotPlanned, webhook_post.issue.number)?;
+        return Ok(msg);
+    }
+
+    place_once(job, &challenger, conn)?;
+
+    forge.close_issue(CloseType::Completed, webhook_post.issue.number)?;
+
+    match forge.publish_repo(conn) {
+        Ok(msg) => Ok(msg),
+        Err(error) => Ok(error.to_string()),
+    }
+}
+
+/// Replaces the active submission of an edited or reopened issue.
+/// If the code hasn't changed the active submission is kept, or
+/// brought back when the issue was retired, and its placement
+/// matches are played again.
+pub(crate) fn process_resubmission(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    let webhook_post = parse_payload(&job.payload)?;
+    let issue_number = webhook_post.issue.number;
+
+    let code = match unwrap_code(&webhook_post.issue.body) {
+        Ok(block) => block.code,
+        Err(e) => {
+            forge.create_issue_comment(issue_number, &e)?;
+            return Ok(e);
+        }
+    };
+
+    match Submission::by_issue_number(issue_number, conn) {
+        Some(mut active) if active.script != code => {
+            forge.create_issue_comment(
+                issue_number,
+                &format!(
+                    "Submission {} has been retired and replaced by the updated code",
+                    active.id
+                ),
+            )?;
+            active.retired = 1;
+            active.save(conn);
+        }
+        Some(_) => (),
+        // The issue was retired with the /retire command, it competes again
+        None => {
+            forge.remove_label(issue_number, RETIRED_LABEL)?;
+            // With unchanged code the retired submission returns with its rating
+            if let Some(mut retired) = Submission::latest_by_issue_number(issue_number, conn)
+                .filter(|retired| retired.script == code)
+            {
+                retired.retired = 0;
+                retired.save(conn);
+            }
+        }
+    }
+
+    process_submission(job, conn, forge)
+}
+
+/// Plays the placements of a job unless an earlier attempt of it
+/// already did, so that a retry doesn't record the matches twice
+pub(crate) fn place_once(
+    job: &Job,
+    challenger: &Submission,
+    conn: &SqliteConnection,
+) -> Result<(), String> {
+    if job.checkpoint.as_deref() == Some(PLACED_CHECKPOINT) {
+        return Ok(());
+    }
+    run_and_queue_placements(challenger, conn)?;
+    job.save_checkpoint(PLACED_CHECKPOINT, conn);
+    Ok(())
+}
+
+/// Plays the placement matches of a submission and queues the
+/// results for its issue as well as for the opponents' issues
+fn run_and_queue_placements(
+    challenger: &Submission,
+    conn: &SqliteConnection,
+) -> Result<(), String> {
+    let reports = run_placements(challenger, conn);
+
+    let mut comments = Vec::new();
+    if !challenger.has_issue() {
+        // Submitted through the api, the results are read from there
+    } else if reports.is_empty() {
+        comments.push(IssueComment {
+            issue_number: challenger.issue_number,
+            body: "Bot has been registered but could not be match-maked against another bot, wait for someone else to create a bot...".to_string(),
+        });
+    } else {
+        let mut output = String::new();
+        for (challenger_report, _) in reports.iter() {
+            output += &challenger_report.report;
+            output += "<br>";
+        }
+        comments.push(IssueComment {
+            issue_number: challenger.issue_number,
+            body: output,
+        });
+    }
+
+    // Notify opponents too
+    for (_, opponent) in reports.iter() {
+        if opponent.has_issue() {
+            comments.push(IssueComment {
+                issue_number: opponent.issue_number,
+                body: opponent.report.clone(),
+            });
+        }
+    }
+    queue_issue_comments(comments, conn)
+}
+
+pub(crate) fn parse_payload(payload: &str) -> Result<GithubPayload, String> {
+    match serde_json::from_str(payload) {
+        Ok(webhook_post) => Ok(webhook_post),
+        Err(error) => Err(format!("Could not parse job payload: {}", error)),
+    }
+}
+
+/// Outcome of registering the submission of an issue
+enum Registration {
+    Registered(Submission),
+    /// The issue has been told why and closed
+    Rejected(String),
+}
+
+/// Creates the user (if needed) and the submission for the issue,
+/// an Err means the forge couldn't be reached and the job is retried.
+fn register_submission(
+    webhook_post: &GithubPayload,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<Registration, ForgeError> {
+    let issue_number = webhook_post.issue.number;
+
+    // If user doesn't exist we create it
+    let mut user = User::by_username(&webhook_post.sender.login, conn);
+    if user.is_none() {
+        // Create user
+        user = User::create(&webhook_post.sender.login, conn);
+
+        if user.is_none() {
+            println!("Error: Could not create user");
+            return reject(
+                issue_number,
+                "Internal error, please try again later",
+                forge,
+            );
+        }
+    }
+
+    // Get lua code from issue body
+    let block = match unwrap_code(&webhook_post.issue.body) {
+        Ok(block) => block,
+        Err(e) => return reject(issue_number, &e, forge),
+    };
+
+    // Near-copies of other users' bots are rejected, less similar ones are reported
+    let similarity = if is_plagiarism_enabled() {
+        let report = check_similarity(&block.code, &user.as_ref().unwrap().id, conn);
+        if report.verdict() == Verdict::Rejected {
+            let msg = format!(
+                "This bot is too similar to a bot submitted by another user\n\n{}",
+                report.to_markdown()
+            );
+            return reject(issue_number, &msg, forge);
+        }
+        Some(report)
+    } else {
+        None
+    };
+
+    // A name in the code block takes precedence over the issue title. Only
+    // an explicit name continues a bot of another issue, titles are often
+    // left at the issue template's default and don't identify a bot.
+    let user_id = &user.as_ref().unwrap().id;
+    let name = block.name.as_deref().unwrap_or(&webhook_post.issue.title);
+    let bot = match block.name {
+        Some(_) => Bot::for_submission(user_id, name, issue_number, conn),
+        None => {
+            Bot::by_issue(user_id, issue_number, conn).or_else(|| Bot::create(user_id, name, conn))
+        }
+    };
+    let bot = match bot {
+        Some(bot) => bot,
+        None => {
+            return reject(
+                issue_number,
+                "Error: Internal error, could not create bot...<br>Try again later",
+                forge,
+            )
+        }
+    };
+
+    let challenger = match Submission::create(
+        user_id,
+        &bot.id,
+        &block.code,
+        Some(name),
+        0,
+        &webhook_post.issue.html_url,
+        issue_number,
+        conn,
+    ) {
+        Ok(submission) => submission,
+        Err(_) => {
+            return reject(
+                issue_number,
+                "This submission has already been submitted before",
+                forge,
+            )
+        }
+    };
+
+    let challenger = match challenger {
+        Some(challenger) => challenger,
+        None => {
+            return reject(
+                issue_number,
+                "Error: Internal error, could not create submission...<br>Try again later",
+                forge,
+            )
+        }
+    };
+
+    // Retired before anything is posted, a retry of the job
+    // continues with the registered submission
+    if block.retire_previous {
+        retire_previous_versions(&challenger, conn, forge)?;
+    }
+
+    let mut msg = format!(
+        "User: {}<br>Script-id: {}<br>Thanks for submitting!<br>Your code is being processed...",
+        webhook_post.sender.login, challenger.id
+    );
+    if let Some(report) = similarity.filter(|report| report.verdict() == Verdict::Flagged) {
+        msg += &flagged_notice(&report);
+    }
+    forge.create_issue_comment(issue_number, &msg)?;
+
+    Ok(Registration::Registered(challenger))
+}
+
+/// Tells the issue why it can't be registered and closes it
+fn reject(
+    issue_number: i32,
+    msg: &str,
+    forge: &dyn ForgeClient,
+) -> Result<Registration, ForgeError> {
+    forge.create_issue_comment(issue_number, msg)?;
+    forge.close_issue(CloseType::NotPlanned, issue_number)?;
+    Ok(Registration::Rejected(msg.to_string()))
+}
+
+/// Takes the older versions of the challenger's bot off the ladder,
+/// versions submitted through other issues are notified and labeled.
+/// All of them are retired before the first notification so that a
+/// failing one can't leave an old version on the ladder.
+pub(crate) fn retire_previous_versions(
+    challenger: &Submission,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<(), ForgeError> {
+    let bot_id = match &challenger.bot {
+        Some(bot_id) => bot_id,
+        None => return Ok(()),
+    };
+    let mut previous_versions: Vec<Submission> = Submission::by_bot(bot_id, conn)
+        .into_iter()
+        .filter(|previous| previous.id != challenger.id && previous.retired == 0)
+        .collect();
+    for previous in previous_versions.iter_mut() {
+        previous.retired = 1;
+        previous.save(conn);
+    }
+    for previous in previous_versions.iter() {
+        if previous.has_issue() && previous.issue_number != challenger.issue_number {
+            forge.create_issue_comment(
+                previous.issue_number,
+                &format!(
+                    "Submission {} (version {}) has been retired and replaced by version {}: {}",
+                    previous.id, previous.version, challenger.version, challenger.issue_url
+                ),
+            )?;
+            forge.add_label(previous.issue_number, RETIRED_LABEL)?;
+        }
+    }
+    Ok(())
+}
+
+/// Appended to the submission comment of bots resembling other users' bots
+pub(crate) fn flagged_notice(report: &SimilarityReport) -> String {
+    format!(
+        "\n\nThis bot resembles bots submitted by other users, it has been accepted but may be reviewed\n\n{}",
+        report.to_markdown()
+    )
+}
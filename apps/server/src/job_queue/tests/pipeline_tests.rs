@@ -0,0 +1,940 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test as actix_test, web::Data, App};
+    use hmac::{Hmac, Mac};
+    use serde_json::{json, Value};
+    use sha2::Sha256;
+
+    use crate::{
+        backend::{
+            db::DbPool,
+            fixtures::{
+                setup as setup_database, submission, webhook_secret, UPWARDS_BOT, WEBHOOK_SECRET,
+            },
+            models::{
+                api_token_model::ApiToken,
+                job_model::{Job, JobStatus},
+                match_model::Match,
+                submission_model::Submission,
+                user_model::User,
+            },
+            services::{core::submit_challenge, v1},
+        },
+        external_related::{
+            forge_client::{CheckRunConclusion, CheckRunReport, CloseType},
+            recording_client::{ForgeCall, RecordingClient},
+        },
+        job_queue::{
+            issue_comment_job::ISSUE_COMMENTS_JOB, matchmaking_job::SCHEDULED_MATCHMAKING_JOB,
+            worker::run_next_job,
+        },
+    };
+
+    /**
+     * These tests drive the whole submission pipeline, from the
+     * signed webhook delivery through the job worker, and check
+     * everything that would have been sent to GitHub.
+     */
+    const REGISTERED_WITHOUT_OPPONENT: &str = "Bot has been registered but could not be match-maked against another bot, wait for someone else to create a bot...";
+
+    fn setup() -> (DbPool, RecordingClient) {
+        std::env::set_var("PULL_REQUEST_SUBMISSIONS", "true");
+        (setup_database(), RecordingClient::new())
+    }
+
+    /// Script as it is posted in an issue
+    fn fenced(script: &str) -> String {
+        format!("```lua\n{}\n```", script)
+    }
+
+    fn fixture(name: &str) -> Value {
+        let raw = std::fs::read_to_string(format!(
+            "src/external_related/github/tests/fixtures/{}.json",
+            name
+        ))
+        .expect("Could not load fixture");
+        serde_json::from_str(&raw).unwrap()
+    }
+
+    fn with_issue(mut payload: Value, issue_number: i32, author: &str) -> Value {
+        payload["issue"]["number"] = json!(issue_number);
+        payload["issue"]["html_url"] = json!(format!(
+            "https://github.com/Hampfh/Hampfh/issues/{}",
+            issue_number
+        ));
+        payload["issue"]["user"]["login"] = json!(author);
+        payload["sender"]["login"] = json!(author);
+        payload
+    }
+
+    fn opened(issue_number: i32, author: &str, body: &str) -> Value {
+        let mut payload = with_issue(fixture("issues_opened"), issue_number, author);
+        payload["issue"]["body"] = json!(body);
+        payload
+    }
+
+    fn comment(issue_number: i32, author: &str, body: &str) -> Value {
+        let mut payload = with_issue(fixture("issue_comment_created"), issue_number, author);
+        payload["issue"]["body"] = json!(fenced(UPWARDS_BOT));
+        payload["comment"]["body"] = json!(body);
+        payload["comment"]["user"]["login"] = json!(author);
+        payload
+    }
+
+    fn pull_request(name: &str, author: &str) -> Value {
+        let mut payload = fixture(name);
+        payload["pull_request"]["user"]["login"] = json!(author);
+        payload["sender"]["login"] = json!(author);
+        payload
+    }
+
+    fn bfs_bot() -> String {
+        std::fs::read_to_string("../scripts/bfs.lua").unwrap()
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(WEBHOOK_SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn delivery(event: &str, delivery: &str, payload: &Value) -> actix_test::TestRequest {
+        let body = serde_json::to_vec(payload).unwrap();
+        actix_test::TestRequest::post()
+            .uri("/api/challenge")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("X-GitHub-Event", event.to_string()))
+            .insert_header(("X-GitHub-Delivery", delivery.to_string()))
+            .insert_header(("X-Hub-Signature-256", sign(&body)))
+            .set_payload(body)
+    }
+
+    /// Runs the worker until the queue is empty
+    fn run_queue(pool: &DbPool, forge: &RecordingClient) {
+        let conn = pool.get().unwrap();
+        while run_next_job(&conn, forge) {}
+    }
+
+    fn submission_for(pool: &DbPool, issue_number: i32) -> Submission {
+        Submission::latest_by_issue_number(issue_number, &pool.get().unwrap()).unwrap()
+    }
+
+    fn thanks(author: &str, submission: &Submission) -> String {
+        format!(
+            "User: {}<br>Script-id: {}<br>Thanks for submitting!<br>Your code is being processed...",
+            author, submission.id
+        )
+    }
+
+    fn comment_call(issue_number: i32, body: &str) -> ForgeCall {
+        ForgeCall::Comment {
+            issue_number,
+            body: body.to_string(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn first_submission_waits_for_an_opponent() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        let status = actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        // Nothing is sent before the worker picks up the job
+        assert!(forge.calls().is_empty());
+        run_queue(&pool, &forge);
+
+        let submission = submission_for(&pool, 1);
+        assert_eq!(
+            forge.calls(),
+            vec![
+                comment_call(1, &thanks("alice", &submission)),
+                ForgeCall::Close {
+                    issue_number: 1,
+                    state: CloseType::Completed
+                },
+                ForgeCall::Publish,
+                // Results are posted by a queued job of their own
+                comment_call(1, REGISTERED_WITHOUT_OPPONENT),
+            ]
+        );
+
+        // A redelivery is acknowledged without running the pipeline again
+        let status = actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::OK);
+        run_queue(&pool, &forge);
+        assert_eq!(forge.calls().len(), 4);
+    }
+
+    #[actix_web::test]
+    async fn placement_results_are_posted_to_both_issues() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await
+        .status();
+        run_queue(&pool, &forge);
+        forge.take_calls();
+
+        let bfs = format!(
+            "```lua\n{}\n```",
+            std::fs::read_to_string("../scripts/bfs.lua").unwrap()
+        );
+        let status = actix_test::call_service(
+            &app,
+            delivery("issues", "d-2", &opened(2, "bob", &bfs)).to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        run_queue(&pool, &forge);
+
+        let alice = submission_for(&pool, 1);
+        let bob = submission_for(&pool, 2);
+        let matches = Match::list(&pool.get().unwrap());
+        assert_eq!(matches.len(), 1);
+        let played = &matches[0];
+        let result = |submission: &Submission| {
+            if played.winner == submission.id {
+                "WIN"
+            } else {
+                "LOSS"
+            }
+        };
+        let match_url = format!("../blob/live/data/matches/{}.md", played.id);
+
+        assert_eq!(
+            forge.comments(2),
+            vec![
+                thanks("bob", &bob),
+                format!(
+                    "[{}] Opponent: [{}](https://github.com/Hampfh/Hampfh/issues/1) &#124; [Match]({})<br>",
+                    result(&bob),
+                    alice.id,
+                    match_url
+                ),
+            ]
+        );
+        assert_eq!(
+            forge.comments(1),
+            vec![format!(
+                "[{}] Opponent: [{}](https://github.com/Hampfh/Hampfh/issues/2) &#124; [Match]({})",
+                result(&alice),
+                bob.id,
+                match_url
+            )]
+        );
+        assert_eq!(forge.issue_state(2), Some(CloseType::Completed));
+        assert_eq!(forge.issue_state(1), None);
+        assert_eq!(bob.matches_played, 1);
+        assert_eq!(alice.matches_played, 1);
+    }
+
+    #[actix_web::test]
+    async fn retried_job_does_not_replay_placements() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+        forge.panic_on_next_publish();
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-2", &opened(2, "bob", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+
+        let conn = pool.get().unwrap();
+        let mut failed = Job::by_status(JobStatus::Pending, &conn).pop().unwrap();
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(Match::list(&conn).len(), 1);
+        // Retry right away instead of waiting for the backoff
+        failed.run_at = chrono::Local::now().naive_local();
+        failed.save(&conn);
+        drop(conn);
+        forge.take_calls();
+        run_queue(&pool, &forge);
+
+        assert_eq!(Match::list(&pool.get().unwrap()).len(), 1);
+        assert_eq!(submission_for(&pool, 2).matches_played, 1);
+        assert_eq!(
+            forge.take_calls(),
+            vec![
+                ForgeCall::Close {
+                    issue_number: 2,
+                    state: CloseType::Completed
+                },
+                ForgeCall::Publish,
+            ]
+        );
+    }
+
+    #[actix_web::test]
+    async fn failed_comments_are_retried_without_replaying_matches() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-2", &opened(2, "bob", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        // The submission itself goes through, posting its results doesn't
+        assert!(run_next_job(&pool.get().unwrap(), &forge));
+        forge.fail_next_comment();
+        run_queue(&pool, &forge);
+
+        let conn = pool.get().unwrap();
+        let mut failed = Job::by_status(JobStatus::Pending, &conn).pop().unwrap();
+        assert_eq!(failed.kind, ISSUE_COMMENTS_JOB);
+        assert_eq!(failed.attempts, 1);
+        failed.run_at = chrono::Local::now().naive_local();
+        failed.save(&conn);
+        drop(conn);
+        forge.take_calls();
+        run_queue(&pool, &forge);
+
+        let conn = pool.get().unwrap();
+        assert_eq!(Match::list(&conn).len(), 1);
+        assert!(Job::by_status(JobStatus::Pending, &conn).is_empty());
+        assert_eq!(forge.calls().len(), 2);
+        assert_eq!(forge.comments(1).len(), 1);
+        assert_eq!(forge.comments(2).len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn new_version_retires_the_previous_one() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        let first = format!("```lua name=Walls\n{}\n```", UPWARDS_BOT);
+        let second = format!("```lua name=walls retire=true\n{}\n```", bfs_bot());
+        let third = second.replace(" retire=true", "");
+        let deliveries = [
+            ("d-1", opened(1, "alice", &first)),
+            ("d-2", opened(2, "alice", &second)),
+            ("d-3", opened(3, "alice", &third)),
+        ];
+        for (id, payload) in deliveries.iter() {
+            actix_test::call_service(&app, delivery("issues", id, payload).to_request()).await;
+            run_queue(&pool, &forge);
+        }
+
+        let versions: Vec<Submission> = (1..=3).map(|issue| submission_for(&pool, issue)).collect();
+        let lineage: Vec<(i32, i32)> = versions
+            .iter()
+            .map(|version| (version.version, version.retired))
+            .collect();
+        assert_eq!(lineage, vec![(1, 1), (2, 0), (3, 0)]);
+        assert!(versions
+            .iter()
+            .all(|version| version.bot == versions[0].bot));
+
+        // Versions of the same user's bot don't play each other
+        assert!(Match::list(&pool.get().unwrap()).is_empty());
+        assert_eq!(
+            forge.comments(1).last().unwrap(),
+            &format!(
+                "Submission {} (version 1) has been retired and replaced by version 2: https://github.com/Hampfh/Hampfh/issues/2",
+                versions[0].id
+            )
+        );
+        assert!(forge.calls().contains(&ForgeCall::AddLabel {
+            issue_number: 1,
+            label: "retired".to_string()
+        }));
+        assert!(!forge.calls().contains(&ForgeCall::AddLabel {
+            issue_number: 2,
+            label: "retired".to_string()
+        }));
+    }
+
+    #[actix_web::test]
+    async fn unnamed_submissions_are_separate_bots() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        // Both issues keep the title of the issue template
+        let deliveries = [
+            ("d-1", opened(1, "alice", &fenced(UPWARDS_BOT))),
+            ("d-2", opened(2, "alice", &fenced(&bfs_bot()))),
+        ];
+        for (id, payload) in deliveries.iter() {
+            actix_test::call_service(&app, delivery("issues", id, payload).to_request()).await;
+            run_queue(&pool, &forge);
+        }
+
+        let first = submission_for(&pool, 1);
+        let second = submission_for(&pool, 2);
+        assert_ne!(first.bot, second.bot);
+        assert_eq!((first.retired, second.retired), (0, 0));
+        assert!(!forge.calls().contains(&ForgeCall::AddLabel {
+            issue_number: 1,
+            label: "retired".to_string()
+        }));
+    }
+
+    #[actix_web::test]
+    async fn invalid_code_is_rejected() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(3, "carol", "no code here")).to_request(),
+        )
+        .await
+        .status();
+        run_queue(&pool, &forge);
+
+        assert_eq!(
+            forge.calls(),
+            vec![
+                comment_call(3, "Could not find a ```lua code block, please check the documentation on how to format your submissions"),
+                ForgeCall::Close {
+                    issue_number: 3,
+                    state: CloseType::NotPlanned
+                },
+            ]
+        );
+        assert!(Submission::latest_by_issue_number(3, &pool.get().unwrap()).is_none());
+    }
+
+    #[actix_web::test]
+    async fn commands_reply_on_the_issue() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await
+        .status();
+        run_queue(&pool, &forge);
+        forge.take_calls();
+
+        let status = actix_test::call_service(
+            &app,
+            delivery("issue_comment", "d-2", &comment(1, "alice", "/status")).to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        run_queue(&pool, &forge);
+        let submission = submission_for(&pool, 1);
+        assert_eq!(
+            forge.take_calls(),
+            vec![comment_call(
+                1,
+                &format!(
+                    "Script-id: {}<br>State: Active ✅<br>MMR: {}<br>Rank: 1 of 1<br>Wins: 0 &#124; Matches played: 0",
+                    submission.id,
+                    submission.mmr.round()
+                )
+            )]
+        );
+
+        actix_test::call_service(
+            &app,
+            delivery("issue_comment", "d-3", &comment(1, "alice", "/retire")).to_request(),
+        )
+        .await
+        .status();
+        run_queue(&pool, &forge);
+        assert_eq!(
+            forge.take_calls(),
+            vec![
+                ForgeCall::AddLabel {
+                    issue_number: 1,
+                    label: "retired".to_string()
+                },
+                ForgeCall::Close {
+                    issue_number: 1,
+                    state: CloseType::Completed
+                },
+                ForgeCall::Publish,
+                comment_call(
+                    1,
+                    &format!("Submission {} has been retired and will no longer be matched<br>Reopen the issue or use `/resubmit` to compete again", submission.id)
+                ),
+            ]
+        );
+        assert_eq!(submission_for(&pool, 1).retired, 1);
+
+        // Commands from anyone but the author never reach the queue
+        let mut payload = comment(1, "alice", "/rematch");
+        payload["sender"]["login"] = json!("mallory");
+        let status = actix_test::call_service(
+            &app,
+            delivery("issue_comment", "d-4", &payload).to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::OK);
+        run_queue(&pool, &forge);
+        assert!(forge.calls().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn reopened_issue_brings_back_the_retired_submission() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        actix_test::call_service(
+            &app,
+            delivery("issue_comment", "d-2", &comment(1, "alice", "/retire")).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+        let retired = submission_for(&pool, 1);
+        assert_eq!(retired.retired, 1);
+        forge.take_calls();
+
+        let mut reopened = with_issue(fixture("issues_reopened"), 1, "alice");
+        reopened["issue"]["body"] = json!(fenced(UPWARDS_BOT));
+        let status =
+            actix_test::call_service(&app, delivery("issues", "d-3", &reopened).to_request())
+                .await
+                .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        run_queue(&pool, &forge);
+
+        let active = submission_for(&pool, 1);
+        assert_eq!(active.id, retired.id);
+        assert_eq!(active.retired, 0);
+        assert_eq!(active.mmr, retired.mmr);
+        let calls = forge.take_calls();
+        assert!(calls.contains(&ForgeCall::RemoveLabel {
+            issue_number: 1,
+            label: "retired".to_string()
+        }));
+        assert!(calls.contains(&ForgeCall::Close {
+            issue_number: 1,
+            state: CloseType::Completed
+        }));
+        assert!(!calls.contains(&ForgeCall::Close {
+            issue_number: 1,
+            state: CloseType::NotPlanned
+        }));
+    }
+
+    #[actix_web::test]
+    async fn pull_request_bot_is_previewed_in_a_check_run() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+        forge.take_calls();
+        let alice = submission_for(&pool, 1);
+
+        forge.add_pull_request_file(70, "README.md", "modified", Some("# Bots"));
+        forge.add_pull_request_file(70, "bots/bfs.lua", "added", Some(&bfs_bot()));
+        let status = actix_test::call_service(
+            &app,
+            delivery(
+                "pull_request",
+                "d-2",
+                &pull_request("pull_request_opened", "bob"),
+            )
+            .to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        run_queue(&pool, &forge);
+
+        let calls = forge.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[0],
+            ForgeCall::CreateCheckRun {
+                head_sha: "6dcb09b5b57875f334f61aebed695e2e4193db5e".to_string(),
+                name: "Placement matches".to_string()
+            }
+        );
+        let report = match &calls[1] {
+            ForgeCall::CompleteCheckRun {
+                check_run_id: 1,
+                report,
+            } => report,
+            call => panic!("Expected the check run to be completed, got {:?}", call),
+        };
+        assert_eq!(report.conclusion, CheckRunConclusion::Success);
+        assert!(report.summary.starts_with(&format!(
+            "Bot: `bots/bfs.lua`\n\n| Opponent | Result |\n| :-- | :-- |\n| [{}](https://github.com/Hampfh/Hampfh/issues/1) |",
+            alice.id
+        )));
+
+        // Nothing is added to the ladder before the pull request is merged
+        let conn = pool.get().unwrap();
+        assert_eq!(Submission::list(&conn).len(), 1);
+        assert!(Match::list(&conn).is_empty());
+        assert_eq!(
+            Submission::by_id(&alice.id, &conn).unwrap().matches_played,
+            0
+        );
+    }
+
+    #[actix_web::test]
+    async fn pull_request_with_several_bots_fails_the_check() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        forge.add_pull_request_file(70, "bots/one.lua", "added", Some(&bfs_bot()));
+        forge.add_pull_request_file(70, "bots/two.lua", "added", Some(&bfs_bot()));
+        actix_test::call_service(
+            &app,
+            delivery(
+                "pull_request",
+                "d-1",
+                &pull_request("pull_request_opened", "bob"),
+            )
+            .to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+
+        assert_eq!(
+            forge.calls()[1],
+            ForgeCall::CompleteCheckRun {
+                check_run_id: 1,
+                report: CheckRunReport {
+                    conclusion: CheckRunConclusion::Failure,
+                    title: "Invalid bot submission".to_string(),
+                    summary: "A pull request may only add or change one bot, found: `bots/one.lua`, `bots/two.lua`".to_string(),
+                }
+            }
+        );
+    }
+
+    #[actix_web::test]
+    async fn retried_pull_request_check_reuses_its_check_run() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        // The bot can't be fetched on the first attempt
+        forge.add_pull_request_file(70, "bots/bfs.lua", "added", None);
+        actix_test::call_service(
+            &app,
+            delivery(
+                "pull_request",
+                "d-1",
+                &pull_request("pull_request_opened", "bob"),
+            )
+            .to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+
+        let conn = pool.get().unwrap();
+        let mut failed = Job::by_status(JobStatus::Pending, &conn).pop().unwrap();
+        assert_eq!(failed.attempts, 1);
+        failed.run_at = chrono::Local::now().naive_local();
+        failed.save(&conn);
+        drop(conn);
+        forge.set_file_content("bots/bfs.lua", &bfs_bot());
+        run_queue(&pool, &forge);
+
+        let calls = forge.calls();
+        let created = calls
+            .iter()
+            .filter(|call| matches!(call, ForgeCall::CreateCheckRun { .. }))
+            .count();
+        assert_eq!(created, 1);
+        match calls.last().unwrap() {
+            ForgeCall::CompleteCheckRun {
+                check_run_id: 1,
+                report,
+            } => assert_eq!(report.title, "No opponents"),
+            call => panic!("Expected the check run to be completed, got {:?}", call),
+        }
+    }
+
+    #[actix_web::test]
+    async fn merged_pull_request_joins_the_ladder() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge),
+        )
+        .await;
+
+        forge.add_pull_request_file(70, "bots/bfs.lua", "added", Some(&bfs_bot()));
+        let status = actix_test::call_service(
+            &app,
+            delivery(
+                "pull_request",
+                "d-1",
+                &pull_request("pull_request_merged", "bob"),
+            )
+            .to_request(),
+        )
+        .await
+        .status();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        run_queue(&pool, &forge);
+
+        let bob = submission_for(&pool, 70);
+        assert_eq!(bob.script, bfs_bot());
+        assert_eq!(bob.issue_url, "https://github.com/Hampfh/Hampfh/pull/70");
+        assert_eq!(
+            forge.calls(),
+            vec![
+                comment_call(
+                    70,
+                    &format!(
+                        "User: bob<br>Script-id: {}<br>`bots/bfs.lua` has joined the ladder!<br>Your placement matches are being played...",
+                        bob.id
+                    )
+                ),
+                ForgeCall::Publish,
+                comment_call(70, REGISTERED_WITHOUT_OPPONENT),
+            ]
+        );
+    }
+
+    #[actix_web::test]
+    async fn api_submissions_report_through_the_api() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .app_data(Data::new(webhook_secret()))
+                .service(submit_challenge)
+                .configure(v1::routes),
+        )
+        .await;
+
+        actix_test::call_service(
+            &app,
+            delivery("issues", "d-1", &opened(1, "alice", &fenced(UPWARDS_BOT))).to_request(),
+        )
+        .await;
+        run_queue(&pool, &forge);
+        forge.take_calls();
+
+        let conn = pool.get().unwrap();
+        let bob = User::create("bob", &conn).unwrap();
+        let (_, token) = ApiToken::create(&bob.id, "ci", &conn).unwrap();
+        drop(conn);
+        let response = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::post()
+                .uri("/api/v1/submissions")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({ "name": "bfs", "script": bfs_bot() }))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body: Value = actix_test::read_body_json(response).await;
+        assert_eq!(body["username"], "bob");
+        assert_eq!(body["bot_name"], "bfs");
+        assert_eq!(body["placement"]["status"], "pending");
+        assert!(forge.calls().is_empty());
+
+        run_queue(&pool, &forge);
+        let submission: Value = actix_test::call_and_read_body_json(
+            &app,
+            actix_test::TestRequest::get()
+                .uri(&format!(
+                    "/api/v1/submissions/{}",
+                    body["id"].as_str().unwrap()
+                ))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(submission["placement"]["status"], "completed");
+        assert_eq!(submission["matches"].as_array().unwrap().len(), 1);
+        assert_eq!(submission["matches_played"], 1);
+
+        // Only the opponent has an issue to report to
+        let calls = forge.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0], ForgeCall::Publish);
+        assert!(matches!(
+            calls[1],
+            ForgeCall::Comment {
+                issue_number: 1,
+                ..
+            }
+        ));
+    }
+
+    #[actix_web::test]
+    async fn api_submission_retires_the_previous_version() {
+        let (pool, forge) = setup();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .configure(v1::routes),
+        )
+        .await;
+
+        let conn = pool.get().unwrap();
+        let bob = User::create("bob", &conn).unwrap();
+        let (_, token) = ApiToken::create(&bob.id, "ci", &conn).unwrap();
+        drop(conn);
+        let mut ids = Vec::new();
+        for script in [UPWARDS_BOT.to_string(), bfs_bot()] {
+            let response = actix_test::call_service(
+                &app,
+                actix_test::TestRequest::post()
+                    .uri("/api/v1/submissions")
+                    .insert_header(("Authorization", format!("Bearer {}", token)))
+                    .set_json(json!({ "name": "walls", "script": script }))
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+            let body: Value = actix_test::read_body_json(response).await;
+            ids.push(body["id"].as_str().unwrap().to_string());
+            run_queue(&pool, &forge);
+        }
+
+        let conn = pool.get().unwrap();
+        let first = Submission::by_id(&ids[0], &conn).unwrap();
+        let second = Submission::by_id(&ids[1], &conn).unwrap();
+        assert_eq!((first.version, first.retired), (1, 1));
+        assert_eq!((second.version, second.retired), (2, 0));
+        // Neither version has an issue to report to
+        assert_eq!(forge.calls(), vec![ForgeCall::Publish, ForgeCall::Publish]);
+    }
+
+    #[test]
+    fn scheduled_matchmaking_is_played_by_the_worker() {
+        let (pool, forge) = setup();
+        let conn = pool.get().unwrap();
+        // Opponents are drawn at random, with enough players a round is
+        // all but certain to find the two pairs it needs
+        for player in [
+            "alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi",
+        ] {
+            submission(player, &format!("-- {}\n{}", player, UPWARDS_BOT), &conn);
+        }
+        let job = Job::create(SCHEDULED_MATCHMAKING_JOB, "", &conn).unwrap();
+        drop(conn);
+        run_queue(&pool, &forge);
+
+        let conn = pool.get().unwrap();
+        let job = Job::by_id(&job.id, &conn).unwrap();
+        assert_eq!(job.status, JobStatus::Completed.as_str());
+        assert_eq!(job.checkpoint.as_deref(), Some("played"));
+        let matches = Match::list(&conn).len();
+        assert!(matches >= 2);
+        // The fixtures share issue 1, every match is reported to it twice
+        let calls = forge.calls();
+        assert_eq!(calls[0], ForgeCall::Publish);
+        assert_eq!(forge.comments(1).len(), matches * 2);
+    }
+}
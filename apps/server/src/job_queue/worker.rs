@@ -0,0 +1,164 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use diesel::SqliteConnection;
+
+use crate::backend::{
+    self,
+    models::job_model::{Job, JobStatus},
+};
+use crate::external_related::forge_client::{forge_from_env, ForgeClient};
+
+use super::{
+    api_submission_job::{process_api_submission, API_SUBMISSION_JOB},
+    command_job::{process_command, COMMAND_JOB},
+    constants::{
+        JOB_POLL_INTERVAL_MS, JOB_RETRY_BASE_DELAY_SECONDS, JOB_RETRY_MAX_DELAY_SECONDS,
+        MAX_JOB_ATTEMPTS,
+    },
+    disqualification_job::{process_disqualification_rerun, DISQUALIFICATION_RERUN_JOB},
+    issue_comment_job::{process_issue_comments, ISSUE_COMMENTS_JOB},
+    matchmaking_job::{process_scheduled_matchmaking, SCHEDULED_MATCHMAKING_JOB},
+    pull_request_job::{
+        process_pull_request_check, process_pull_request_merge, PULL_REQUEST_CHECK_JOB,
+        PULL_REQUEST_MERGE_JOB,
+    },
+    submission_job::{process_resubmission, process_submission, RESUBMISSION_JOB, SUBMISSION_JOB},
+};
+
+/// Spawns the background thread that processes the job queue.
+/// Jobs are executed one at a time in the order they became due.
+pub(crate) fn start_worker() {
+    std::thread::spawn(move || {
+        let db_pool = backend::db::establish_connection();
+        let forge = forge_from_env();
+        if let Ok(conn) = db_pool.get() {
+            let requeued = Job::requeue_interrupted(&conn);
+            if requeued > 0 {
+                println!("Requeued {} interrupted job(s)", requeued);
+            }
+        }
+
+        loop {
+            let conn = match db_pool.get() {
+                Ok(conn) => conn,
+                Err(error) => {
+                    println!("Job worker could not connect to database: {}", error);
+                    std::thread::sleep(Duration::from_millis(JOB_POLL_INTERVAL_MS));
+                    continue;
+                }
+            };
+
+            if !run_next_job(&conn, forge.as_ref()) {
+                std::thread::sleep(Duration::from_millis(JOB_POLL_INTERVAL_MS));
+            }
+        }
+    });
+}
+
+/// Runs the next due job if there is one, returns false if the queue is empty
+pub(crate) fn run_next_job(conn: &SqliteConnection, forge: &dyn ForgeClient) -> bool {
+    match Job::next_due(conn) {
+        Some(job) => {
+            run_job(job, conn, forge);
+            true
+        }
+        None => false,
+    }
+}
+
+fn run_job(mut job: Job, conn: &SqliteConnection, forge: &dyn ForgeClient) {
+    job.set_status(JobStatus::Running);
+    job.attempts += 1;
+    job.save(conn);
+
+    // A panic inside a job should not take down the worker,
+    // it is treated as a regular failed attempt instead
+    let result = match panic::catch_unwind(AssertUnwindSafe(|| execute_job(&job, conn, forge))) {
+        Ok(result) => result,
+        Err(panic_payload) => Err(panic_message(panic_payload)),
+    };
+
+    match result {
+        Ok(msg) => {
+            println!("Job {} ({}) completed: {}", job.id, job.kind, msg);
+            job.set_status(JobStatus::Completed);
+            job.last_error = None;
+        }
+        Err(error) => {
+            println!(
+                "Job {} ({}) failed attempt {}: {}",
+                job.id, job.kind, job.attempts, error
+            );
+            job.last_error = Some(error);
+            if job.attempts >= MAX_JOB_ATTEMPTS {
+                job.set_status(JobStatus::Failed);
+            } else {
+                job.set_status(JobStatus::Pending);
+                job.run_at = chrono::Local::now().naive_local() + retry_delay(job.attempts);
+            }
+        }
+    }
+    job.save(conn);
+}
+
+fn execute_job(
+    job: &Job,
+    conn: &SqliteConnection,
+    forge: &dyn ForgeClient,
+) -> Result<String, String> {
+    match job.kind.as_str() {
+        SUBMISSION_JOB => process_submission(job, conn, forge),
+        RESUBMISSION_JOB => process_resubmission(job, conn, forge),
+        COMMAND_JOB => process_command(job, conn, forge),
+        PULL_REQUEST_CHECK_JOB => process_pull_request_check(job, conn, forge),
+        PULL_REQUEST_MERGE_JOB => process_pull_request_merge(job, conn, forge),
+        DISQUALIFICATION_RERUN_JOB => process_disqualification_rerun(&job.payload, conn, forge),
+        API_SUBMISSION_JOB => process_api_submission(job, conn, forge),
+        ISSUE_COMMENTS_JOB => process_issue_comments(job, conn, forge),
+        SCHEDULED_MATCHMAKING_JOB => process_scheduled_matchmaking(job, conn, forge),
+        kind => Err(format!("Unknown job kind: {}", kind)),
+    }
+}
+
+/// Exponential backoff, doubling for every failed attempt
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = std::cmp::max(attempts - 1, 0) as u32;
+    let seconds = JOB_RETRY_BASE_DELAY_SECONDS.saturating_mul(2_i64.saturating_pow(exponent));
+    chrono::Duration::seconds(std::cmp::min(seconds, JOB_RETRY_MAX_DELAY_SECONDS))
+}
+
+fn panic_message(panic_payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(msg) = panic_payload.downcast_ref::<&str>() {
+        return format!("Job panicked: {}", msg);
+    }
+    if let Some(msg) = panic_payload.downcast_ref::<String>() {
+        return format!("Job panicked: {}", msg);
+    }
+    "Job panicked".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::job_queue::constants::{JOB_RETRY_BASE_DELAY_SECONDS, JOB_RETRY_MAX_DELAY_SECONDS};
+
+    use super::retry_delay;
+
+    #[test]
+    fn retry_delay_doubles_each_attempt() {
+        assert_eq!(retry_delay(1).num_seconds(), JOB_RETRY_BASE_DELAY_SECONDS);
+        assert_eq!(
+            retry_delay(2).num_seconds(),
+            JOB_RETRY_BASE_DELAY_SECONDS * 2
+        );
+        assert_eq!(
+            retry_delay(3).num_seconds(),
+            JOB_RETRY_BASE_DELAY_SECONDS * 4
+        );
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        assert_eq!(retry_delay(40).num_seconds(), JOB_RETRY_MAX_DELAY_SECONDS);
+    }
+}
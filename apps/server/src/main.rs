@@ -1,6 +1,7 @@
 use actix_web::{web::Data, App, HttpServer};
 
 use crate::backend::{db::run_migrations, services::routes::routes};
+use crate::external_related::github::webhook_signature::WebhookSecret;
 
 #[macro_use]
 extern crate diesel;
@@ -8,11 +9,14 @@ extern crate diesel;
 extern crate diesel_migrations;
 embed_migrations!();
 
+mod analytics;
 mod backend;
 mod cli;
 mod external_related;
 mod game;
+mod job_queue;
 mod match_maker;
+mod plagiarism;
 
 #[actix_web::main]
 async fn main() -> Result<(), std::io::Error> {
@@ -24,13 +28,18 @@ async fn main() -> Result<(), std::io::Error> {
         return Ok(());
     }
 
+    job_queue::worker::start_worker();
+
     let port = 8095;
 
+    let webhook_secret = WebhookSecret::from_env();
+
     println!("Listening on port {}", port);
     HttpServer::new(move || {
         let db_connection = backend::db::establish_connection();
         App::new()
             .app_data(Data::new(db_connection))
+            .app_data(Data::new(webhook_secret.clone()))
             .configure(routes)
     })
     .bind((std::env::var("IP").unwrap(), port))?
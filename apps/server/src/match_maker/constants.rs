@@ -1,2 +1,8 @@
 pub(crate) const MMR_START_RATING: f32 = 1500.0;
 pub(crate) const DEFAULT_MATCH_GAIN: f32 = 100.0;
+/// Amplifier passed to calculate_mmr for ladder matches
+pub(crate) const LADDER_MMR_AMPLIFIER: f32 = 0.5;
+/// Turn timeouts are played again after this delay, when the host is hopefully less busy
+pub(crate) const DISQUALIFICATION_RERUN_DELAY_SECONDS: i64 = 10 * 60;
+/// Reinstatements due to the server after which timeouts are no longer rerun
+pub(crate) const MAX_INFRASTRUCTURE_REINSTATEMENTS: usize = 3;
@@ -0,0 +1,164 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::{
+    disqualification_model::{Disqualification, DisqualificationKind, DisqualificationStatus},
+    job_model::Job,
+    submission_model::Submission,
+};
+use crate::job_queue::disqualification_job::DISQUALIFICATION_RERUN_JOB;
+
+use super::constants::{DISQUALIFICATION_RERUN_DELAY_SECONDS, MAX_INFRASTRUCTURE_REINSTATEMENTS};
+use super::live_matches::MatchKind;
+use super::match_executor::{execute_match_queue, replay_disqualifications, MatchReport};
+
+pub(crate) enum RerunOutcome {
+    /// The disqualification was reinstated or confirmed in the meantime
+    AlreadyResolved,
+    /// The submission failed again, the disqualification is confirmed
+    Reproduced,
+    /// The server was at fault, the submission is back in the ladder
+    /// and the reports of the match that was played instead are returned
+    Reinstated(Vec<(MatchReport, MatchReport)>),
+}
+
+/// Records why the players of a match were disqualified. Timeouts
+/// might be caused by load on the host, those matches are rerun later.
+pub(super) fn record_disqualifications(
+    p1: &Submission,
+    p2: &Submission,
+    kind: DisqualificationKind,
+    reason: &str,
+    match_id: Option<&str>,
+    conn: &SqliteConnection,
+) {
+    for (submission, opponent, flipped) in [(p1, p2, true), (p2, p1, false)] {
+        if submission.disqualified == 0 {
+            continue;
+        }
+        let record = match Disqualification::create(
+            &submission.id,
+            Some(&opponent.id),
+            match_id,
+            kind,
+            reason,
+            flipped,
+            conn,
+        ) {
+            Some(record) => record,
+            None => {
+                println!("Internal error, could not record disqualification");
+                continue;
+            }
+        };
+
+        if kind.may_be_infrastructure()
+            && infrastructure_reinstatements(&submission.id, conn)
+                < MAX_INFRASTRUCTURE_REINSTATEMENTS
+        {
+            let run_at = chrono::Local::now().naive_local()
+                + chrono::Duration::seconds(DISQUALIFICATION_RERUN_DELAY_SECONDS);
+            if Job::schedule(DISQUALIFICATION_RERUN_JOB, &record.id, run_at, conn).is_none() {
+                println!("Could not schedule rerun of disqualification {}", record.id);
+            }
+        }
+    }
+}
+
+fn infrastructure_reinstatements(submission_id: &str, conn: &SqliteConnection) -> usize {
+    Disqualification::by_submission(submission_id, conn)
+        .iter()
+        .filter(|record| record.infrastructure >= 1)
+        .count()
+}
+
+/// Lifts a disqualification, the submission takes part in matches
+/// again unless it has other disqualifications that are not lifted.
+pub(crate) fn reinstate(
+    record: &mut Disqualification,
+    conn: &SqliteConnection,
+) -> Result<Submission, String> {
+    if record.is_reinstated() {
+        return Err(format!(
+            "Disqualification {} has already been reinstated",
+            record.id
+        ));
+    }
+    let mut submission = match Submission::by_id(&record.submission, conn) {
+        Some(submission) => submission,
+        None => return Err(format!("Could not find submission {}", record.submission)),
+    };
+
+    record.set_status(DisqualificationStatus::Reinstated);
+    record.save(conn);
+
+    let still_disqualified = Disqualification::by_submission(&submission.id, conn)
+        .iter()
+        .any(|other| !other.is_reinstated());
+    if !still_disqualified {
+        submission.disqualified = 0;
+        submission.save(conn);
+    }
+    Ok(submission)
+}
+
+/// Plays the disqualifying match again. If the submission doesn't fail
+/// this time the server was at fault, it is reinstated and the match is
+/// recorded as if it had been played normally.
+pub(crate) fn rerun(
+    record: &mut Disqualification,
+    conn: &SqliteConnection,
+) -> Result<RerunOutcome, String> {
+    if record.status != DisqualificationStatus::Active.as_str() {
+        return Ok(RerunOutcome::AlreadyResolved);
+    }
+    let (submission, opponent) = match (
+        Submission::by_id(&record.submission, conn),
+        record
+            .opponent
+            .as_ref()
+            .and_then(|opponent| Submission::by_id(opponent, conn)),
+    ) {
+        (Some(submission), Some(opponent)) => (submission, opponent),
+        _ => {
+            return Err(format!(
+                "Players of disqualification {} are missing",
+                record.id
+            ))
+        }
+    };
+
+    let mut candidate = submission.clone();
+    candidate.disqualified = 0;
+    let (p1, p2) = pair(record, &candidate, &opponent);
+    let failed_again = replay_disqualifications(&p1, &p2).contains(&submission.id);
+    if failed_again {
+        record.set_status(DisqualificationStatus::Confirmed);
+        record.save(conn);
+        return Ok(RerunOutcome::Reproduced);
+    }
+
+    record.infrastructure = 1;
+    let submission = reinstate(record, conn)?;
+    if submission.disqualified >= 1 || opponent.disqualified >= 1 || opponent.retired >= 1 {
+        return Ok(RerunOutcome::Reinstated(Vec::new()));
+    }
+    let (p1, p2) = pair(record, &submission, &opponent);
+    Ok(RerunOutcome::Reinstated(execute_match_queue(
+        conn,
+        vec![(p1, p2)],
+        MatchKind::Rerun,
+    )))
+}
+
+/// The players in the same order as in the disqualifying match
+fn pair(
+    record: &Disqualification,
+    submission: &Submission,
+    opponent: &Submission,
+) -> (Submission, Submission) {
+    if record.flipped >= 1 {
+        (submission.clone(), opponent.clone())
+    } else {
+        (opponent.clone(), submission.clone())
+    }
+}
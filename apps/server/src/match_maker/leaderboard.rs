@@ -0,0 +1,118 @@
+use crate::backend::models::bot_model::Bot;
+use crate::backend::models::submission_model::Submission;
+
+/// A row of the leaderboard, a bot is represented by its best active version
+#[derive(Debug, Clone)]
+pub(crate) struct BotStanding {
+    pub bot: Bot,
+    pub current: Submission,
+    /// Every version of the bot, oldest first
+    pub versions: Vec<Submission>,
+}
+
+impl BotStanding {
+    /// Rating gained or lost compared to the version before the current one
+    pub fn rating_delta(&self) -> Option<f32> {
+        self.versions
+            .iter()
+            .rev()
+            .find(|version| version.version < self.current.version)
+            .map(|previous| self.current.mmr - previous.mmr)
+    }
+}
+
+/// One standing per bot that still has an active version, best rated first
+pub(crate) fn bot_standings(bots: &[Bot], submissions: &[Submission]) -> Vec<BotStanding> {
+    let mut standings: Vec<BotStanding> = bots
+        .iter()
+        .filter_map(|bot| {
+            let mut versions: Vec<Submission> = submissions
+                .iter()
+                .filter(|submission| submission.bot.as_deref() == Some(bot.id.as_str()))
+                .cloned()
+                .collect();
+            versions.sort_by_key(|submission| submission.version);
+
+            let current = versions
+                .iter()
+                .filter(|version| version.disqualified == 0 && version.retired == 0)
+                .max_by(|a, b| a.mmr.total_cmp(&b.mmr))?
+                .clone();
+            Some(BotStanding {
+                bot: bot.clone(),
+                current,
+                versions,
+            })
+        })
+        .collect();
+    standings.sort_by(|a, b| b.current.mmr.total_cmp(&a.current.mmr));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bot_standings;
+    use crate::backend::models::{bot_model::Bot, submission_model::Submission};
+
+    fn bot(id: &str) -> Bot {
+        Bot {
+            id: id.to_string(),
+            user: "alice".to_string(),
+            name: id.to_string(),
+            created_at: chrono::Local::now().naive_local(),
+            updated_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn version(bot_id: &str, version: i32, mmr: f32, retired: i32) -> Submission {
+        let mut submission = Submission::unsaved("alice", "", None, "", 1);
+        submission.bot = Some(bot_id.to_string());
+        submission.version = version;
+        submission.mmr = mmr;
+        submission.retired = retired;
+        submission
+    }
+
+    #[test]
+    fn shows_one_row_per_bot() {
+        let bots = vec![bot("walls"), bot("runner"), bot("retired")];
+        let submissions = vec![
+            version("walls", 2, 1600.0, 0),
+            version("walls", 1, 1450.0, 1),
+            version("runner", 1, 1700.0, 0),
+            version("retired", 1, 1800.0, 1),
+        ];
+        let standings = bot_standings(&bots, &submissions);
+
+        let rows: Vec<(&str, i32, Option<f32>)> = standings
+            .iter()
+            .map(|standing| {
+                (
+                    standing.bot.id.as_str(),
+                    standing.current.version,
+                    standing.rating_delta(),
+                )
+            })
+            .collect();
+        assert_eq!(rows, vec![("runner", 1, None), ("walls", 2, Some(150.0))]);
+        let history: Vec<i32> = standings[1]
+            .versions
+            .iter()
+            .map(|version| version.version)
+            .collect();
+        assert_eq!(history, vec![1, 2]);
+    }
+
+    #[test]
+    fn picks_the_best_of_several_active_versions() {
+        let bots = vec![bot("walls")];
+        let submissions = vec![
+            version("walls", 1, 1650.0, 0),
+            version("walls", 2, 1550.0, 0),
+        ];
+        let standings = bot_standings(&bots, &submissions);
+        assert_eq!(standings.len(), 1);
+        assert_eq!(standings[0].current.version, 1);
+        assert_eq!(standings[0].rating_delta(), None);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::backend::models::submission_model::Submission;
+use crate::game::{
+    board::board_to_string,
+    evaluation::{evaluate, win_probability},
+    game::{Game, MAX_TURNS, TURN_TIME_LIMIT_MS},
+    replay::{next_frame, start_frame, ReplayAction, ReplayFrame},
+};
+
+/**
+ * Matches that are being played right now. Every turn is published
+ * here and forwarded to the spectators over websockets. The index
+ * lives in the memory of the server process, so only the matches of
+ * its job worker show up, which includes scheduled matchmaking.
+ * Matches played by the cli in a process of its own don't. A match
+ * is removed as soon as it is over, the stored match takes over then.
+ */
+static LIVE_MATCHES: OnceLock<Mutex<HashMap<String, LiveMatch>>> = OnceLock::new();
+
+/// Why a match is played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MatchKind {
+    Placement,
+    Scheduled,
+    /// A match played again to review a disqualification
+    Rerun,
+    /// Started from the admin cli
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LiveMatchSummary {
+    pub id: String,
+    pub kind: MatchKind,
+    /// Submission ids, player one moves first
+    pub player_one: String,
+    pub player_two: String,
+    /// Turns played so far
+    pub turn: usize,
+    pub started_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum LiveEvent {
+    Started(LiveMatchSummary),
+    Turn(LiveTurn),
+    Finished(LiveResult),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LiveTurn {
+    pub turn: usize,
+    /// Same format as the boards of stored turns
+    pub board: String,
+    pub action: ReplayAction,
+    pub player_one_walls: i32,
+    pub player_two_walls: i32,
+    /// For player one, see game::evaluation
+    pub win_probability: f32,
+    pub clock: LiveClock,
+}
+
+/// Thinking time in milliseconds
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LiveClock {
+    /// What the move of this turn took
+    pub turn: u64,
+    pub player_one: u64,
+    pub player_two: u64,
+    /// Per move, a bot that takes longer loses
+    pub limit: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LiveResult {
+    /// Submission id, missing when the match ended without a winner
+    pub winner: Option<String>,
+    pub error: Option<String>,
+    /// The stored match, missing when the match was not recorded
+    pub match_id: Option<String>,
+}
+
+struct LiveMatch {
+    summary: LiveMatchSummary,
+    /// Every event so far, spectators that join late are sent these first
+    history: Vec<LiveEvent>,
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+/// Publishes the turns of a match while it is played, the
+/// match leaves the index when the handle is dropped
+pub(crate) struct LiveHandle {
+    id: String,
+    frame: ReplayFrame,
+    player_one_clock: Duration,
+    player_two_clock: Duration,
+    finished: bool,
+}
+
+impl LiveHandle {
+    /// Called after every turn, turns that failed have no board and are skipped
+    pub(crate) fn on_turn(&mut self, game: &Game, elapsed: Duration) {
+        let board = match game.turns.get(self.frame.turn) {
+            Some(board) => board,
+            None => return,
+        };
+        // Player one moves on odd turns
+        if self.frame.turn.is_multiple_of(2) {
+            self.player_one_clock += elapsed;
+        } else {
+            self.player_two_clock += elapsed;
+        }
+        self.frame = next_frame(&self.frame, board);
+        publish(
+            &self.id,
+            LiveEvent::Turn(LiveTurn {
+                turn: self.frame.turn,
+                board: board_to_string(board.clone()),
+                action: self.frame.action.clone(),
+                player_one_walls: self.frame.player_one.wall_count,
+                player_two_walls: self.frame.player_two.wall_count,
+                win_probability: win_probability(evaluate(&self.frame)),
+                clock: LiveClock {
+                    turn: elapsed.as_millis() as u64,
+                    player_one: self.player_one_clock.as_millis() as u64,
+                    player_two: self.player_two_clock.as_millis() as u64,
+                    limit: TURN_TIME_LIMIT_MS,
+                },
+            }),
+        );
+    }
+
+    pub(crate) fn finish(mut self, result: LiveResult) {
+        publish(&self.id, LiveEvent::Finished(result));
+        self.finished = true;
+    }
+}
+
+impl Drop for LiveHandle {
+    fn drop(&mut self) {
+        if !self.finished {
+            publish(
+                &self.id,
+                LiveEvent::Finished(LiveResult {
+                    winner: None,
+                    error: Some("The match was aborted".to_string()),
+                    match_id: None,
+                }),
+            );
+        }
+        registry().remove(&self.id);
+    }
+}
+
+/// Adds a match to the index, its turns are published through the handle
+pub(crate) fn start(
+    kind: MatchKind,
+    player_one: &Submission,
+    player_two: &Submission,
+) -> LiveHandle {
+    let summary = LiveMatchSummary {
+        id: Uuid::new_v4().to_hyphenated().to_string(),
+        kind,
+        player_one: player_one.id.clone(),
+        player_two: player_two.id.clone(),
+        turn: 0,
+        started_at: chrono::Local::now().naive_local(),
+    };
+    // Room for every event of a match, so that no spectator can fall behind
+    let (sender, _) = broadcast::channel(MAX_TURNS as usize + 2);
+    let id = summary.id.clone();
+    registry().insert(
+        id.clone(),
+        LiveMatch {
+            history: vec![LiveEvent::Started(summary.clone())],
+            summary,
+            sender,
+        },
+    );
+    LiveHandle {
+        id,
+        frame: start_frame(),
+        player_one_clock: Duration::ZERO,
+        player_two_clock: Duration::ZERO,
+        finished: false,
+    }
+}
+
+/// The matches in progress, oldest first
+pub(crate) fn list() -> Vec<LiveMatchSummary> {
+    let mut matches: Vec<LiveMatchSummary> = registry()
+        .values()
+        .map(|current| current.summary.clone())
+        .collect();
+    matches.sort_by_key(|current| current.started_at);
+    matches
+}
+
+/// The events of a match so far and a receiver for the ones to come
+pub(crate) fn subscribe(id: &str) -> Option<(Vec<LiveEvent>, broadcast::Receiver<LiveEvent>)> {
+    registry()
+        .get(id)
+        .map(|current| (current.history.clone(), current.sender.subscribe()))
+}
+
+fn publish(id: &str, event: LiveEvent) {
+    let mut matches = registry();
+    if let Some(current) = matches.get_mut(id) {
+        if let LiveEvent::Turn(turn) = &event {
+            current.summary.turn = turn.turn;
+        }
+        current.history.push(event.clone());
+        // Without spectators there is no one to send to
+        let _ = current.sender.send(event);
+    }
+}
+
+/// A panic while the lock is held leaves the index usable
+fn registry() -> MutexGuard<'static, HashMap<String, LiveMatch>> {
+    LIVE_MATCHES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, start, subscribe, LiveEvent, LiveResult, MatchKind};
+    use crate::backend::models::submission_model::Submission;
+    use crate::game::{board::populate_board, methods};
+    use std::time::Duration;
+
+    #[test]
+    fn publishes_turns_until_the_match_is_over() {
+        let player_one = Submission::unsaved("alice", "", None, "", 1);
+        let player_two = Submission::unsaved("bob", "", None, "", 2);
+        let mut live = start(MatchKind::Placement, &player_one, &player_two);
+        let id = list()
+            .into_iter()
+            .find(|current| current.player_one == player_one.id)
+            .unwrap()
+            .id;
+
+        let mut game = methods::new(String::new());
+        game.player_one.y -= 1;
+        game.turns.push(populate_board(
+            &game.player_one,
+            &game.player_two,
+            &game.walls,
+        ));
+        live.on_turn(&game, Duration::from_millis(12));
+        // A failed turn adds no board
+        live.on_turn(&game, Duration::from_millis(500));
+
+        let (history, mut receiver) = subscribe(&id).unwrap();
+        assert_eq!(history.len(), 2);
+        match &history[1] {
+            LiveEvent::Turn(turn) => {
+                assert_eq!(turn.turn, 1);
+                assert_eq!((turn.clock.player_one, turn.clock.player_two), (12, 0));
+            }
+            event => panic!("Expected a turn, got {:?}", event),
+        }
+        assert_eq!(
+            list().iter().find(|current| current.id == id).unwrap().turn,
+            1
+        );
+
+        live.finish(LiveResult {
+            winner: Some(player_one.id.clone()),
+            error: None,
+            match_id: None,
+        });
+        assert!(matches!(
+            receiver.try_recv().unwrap(),
+            LiveEvent::Finished(LiveResult {
+                winner: Some(_),
+                ..
+            })
+        ));
+        assert!(subscribe(&id).is_none());
+    }
+}
@@ -1,16 +1,24 @@
 use diesel::SqliteConnection;
 
 use crate::{
-    backend::models::{match_model::Match, submission_model::Submission, turn_model::Turn},
+    backend::models::{
+        disqualification_model::DisqualificationKind,
+        match_model::Match,
+        submission_model::{Submission, NO_ISSUE},
+        turn_model::Turn,
+    },
     external_related::repo_updater::get_issue_url,
     game::{
         board::{board_to_string, Tile},
-        entry_point::initialize_game_session,
-        game::{ErrorType, GameResult},
+        entry_point::{initialize_observed_game_session, initialize_traced_game_session},
+        game::{ErrorType, GameResult, TurnTrace},
         player::PlayerType,
     },
 };
 
+use super::constants::LADDER_MMR_AMPLIFIER;
+use super::disqualifications::record_disqualifications;
+use super::live_matches::{self, LiveHandle, LiveResult, MatchKind};
 use super::mmr::{calculate_mmr, MMR};
 
 pub(crate) struct MatchReport {
@@ -18,14 +26,24 @@ pub(crate) struct MatchReport {
     pub(crate) issue_number: i32,
 }
 
-pub(super) fn execute_match_queue(
+impl MatchReport {
+    /// Reports of submissions made through the http api are not posted anywhere
+    pub(crate) fn has_issue(&self) -> bool {
+        self.issue_number != NO_ISSUE
+    }
+}
+
+/// Plays and records the matches, each one can be watched live while it is played
+pub(crate) fn execute_match_queue(
     conn: &SqliteConnection,
     match_queue: Vec<(Submission, Submission)>,
+    kind: MatchKind,
 ) -> Vec<(MatchReport, MatchReport)> {
     // TODO if a submission is disqualified and are queued to play multiple
     // TODO games, those games should be skipped.
     let mut round_reports: Vec<(MatchReport, MatchReport)> = Vec::new();
     for i in 0..match_queue.len() {
+        let mut live = live_matches::start(kind, &match_queue[i].0, &match_queue[i].1);
         let MatchReturn {
             mut p1,
             mut p2,
@@ -34,8 +52,10 @@ pub(super) fn execute_match_queue(
             error_msg,
             error_fault,
             turns,
+            traces,
             critical_error,
-        } = start_match(match_queue[i].clone());
+            disqualification,
+        } = start_match(match_queue[i].clone(), Some(&mut live));
 
         // If the new challenger has a part in the error
         // we disqualify it directly here
@@ -71,6 +91,49 @@ pub(super) fn execute_match_queue(
 
             p1.save(conn);
             p2.save(conn);
+
+            if let Some(disqualification) = disqualification {
+                // The match is kept so that the disqualification can be reviewed,
+                // without a fault there is no winner and nothing to record. It is
+                // listed and counted in the stats report like any other match,
+                // ratings, wins and matches played are left as they were
+                let match_id = match &error_fault {
+                    Some(fault) => {
+                        let p1_is_winner = *fault == PlayerType::Regular;
+                        let (winner, loser) = if p1_is_winner { (&p1, &p2) } else { (&p2, &p1) };
+                        save_match(
+                            &winner.id,
+                            &loser.id,
+                            p1_is_winner,
+                            error_msg.clone(),
+                            &turns,
+                            &traces,
+                            conn,
+                        )
+                        .map(|record| record.id)
+                    }
+                    None => None,
+                };
+                record_disqualifications(
+                    &p1,
+                    &p2,
+                    disqualification,
+                    error_msg.as_deref().unwrap_or_default(),
+                    match_id.as_deref(),
+                    conn,
+                );
+                live.finish(LiveResult {
+                    winner: winner_id,
+                    error: error_msg,
+                    match_id,
+                });
+            } else {
+                live.finish(LiveResult {
+                    winner: winner_id,
+                    error: error_msg,
+                    match_id: None,
+                });
+            }
             continue;
         }
 
@@ -87,7 +150,7 @@ pub(super) fn execute_match_queue(
                 matches_played: p2.matches_played,
             },
             p1.id == winner_id,
-            0.5,
+            LADDER_MMR_AMPLIFIER,
         );
 
         // Assign new mmr
@@ -102,27 +165,29 @@ pub(super) fn execute_match_queue(
         p1.save(conn);
         p2.save(conn);
 
-        // We never save a match if it wasn't successful
-        // If we get to this point we know there
-        // were no errors
+        // Matches that disqualified a player were handled above, this
+        // one has a winner, possibly by a rule violation of the loser,
+        // and is the only kind that changes ratings
 
         let p1_is_winner = p1.id == winner_id;
-        let match_record =
-            match Match::create(&winner_id, &loser_id, p1_is_winner, error_msg.clone(), conn) {
-                Some(match_record) => {
-                    // Generate turns
-                    let mut turn_index = 1;
-                    for turn in turns {
-                        Turn::create(&match_record.id, turn_index, &board_to_string(turn), conn);
-                        turn_index += 1;
-                    }
-                    match_record
-                }
-                None => {
-                    println!("Internal error, could not create match");
-                    continue;
-                }
-            };
+        let match_record = save_match(
+            &winner_id,
+            &loser_id,
+            p1_is_winner,
+            error_msg.clone(),
+            &turns,
+            &traces,
+            conn,
+        );
+        live.finish(LiveResult {
+            winner: Some(winner_id.clone()),
+            error: error_msg.clone(),
+            match_id: match_record.as_ref().map(|record| record.id.clone()),
+        });
+        let match_record = match match_record {
+            Some(match_record) => match_record,
+            None => continue,
+        };
 
         let report = create_report_text(
             error_msg.clone(),
@@ -149,24 +214,113 @@ pub(super) fn execute_match_queue(
     return round_reports;
 }
 
+fn save_match(
+    winner_id: &str,
+    loser_id: &str,
+    p1_is_winner: bool,
+    error_msg: Option<String>,
+    turns: &[Vec<Tile>],
+    traces: &[TurnTrace],
+    conn: &SqliteConnection,
+) -> Option<Match> {
+    match Match::create(winner_id, loser_id, p1_is_winner, error_msg, conn) {
+        Some(match_record) => {
+            // Generate turns
+            for (index, turn) in turns.iter().enumerate() {
+                let log = traces
+                    .get(index)
+                    .filter(|trace| !trace.log.is_empty())
+                    .map(|trace| trace.log.join("\n"));
+                Turn::create(
+                    &match_record.id,
+                    index as i32 + 1,
+                    &board_to_string(turn.clone()),
+                    log.as_deref(),
+                    conn,
+                );
+            }
+            Some(match_record)
+        }
+        None => {
+            println!("Internal error, could not create match");
+            None
+        }
+    }
+}
+
+/// Plays a match again without recording anything and
+/// returns the ids of the submissions that would be disqualified
+pub(super) fn replay_disqualifications(p1: &Submission, p2: &Submission) -> Vec<String> {
+    let MatchReturn { p1, p2, .. } = start_match((p1.clone(), p2.clone()), None);
+    [p1, p2]
+        .into_iter()
+        .filter(|submission| submission.disqualified >= 1)
+        .map(|submission| submission.id)
+        .collect()
+}
+
+/// Outcome of a match that was played without being recorded
+pub(crate) struct MatchPreview {
+    pub(crate) opponent: Submission,
+    /// None if the match ended without a winner
+    pub(crate) won: Option<bool>,
+    pub(crate) error_msg: Option<String>,
+    /// The challenger caused an error it would be disqualified for
+    pub(crate) disqualified: bool,
+}
+
+/// Plays the challenger as p1 against every opponent, neither
+/// the submissions nor the matches are saved.
+pub(crate) fn preview_matches(
+    challenger: &Submission,
+    opponents: Vec<Submission>,
+) -> Vec<MatchPreview> {
+    opponents
+        .into_iter()
+        .map(|opponent| {
+            let MatchReturn {
+                p1,
+                winner_id,
+                error_msg,
+                ..
+            } = start_match((challenger.clone(), opponent.clone()), None);
+            MatchPreview {
+                won: winner_id.map(|winner| winner == p1.id),
+                disqualified: p1.disqualified >= 1,
+                opponent,
+                error_msg,
+            }
+        })
+        .collect()
+}
+
 struct MatchReturn {
     p1: Submission,
     p2: Submission,
     winner_id: Option<String>,
     loser_id: Option<String>,
     turns: Vec<Vec<Tile>>,
+    traces: Vec<TurnTrace>,
     error_msg: Option<String>,
     error_fault: Option<PlayerType>,
     critical_error: bool,
+    disqualification: Option<DisqualificationKind>,
 }
 
-fn start_match(players: (Submission, Submission)) -> MatchReturn {
+fn start_match(players: (Submission, Submission), live: Option<&mut LiveHandle>) -> MatchReturn {
     let mut error_fault: Option<PlayerType> = None;
     let mut error_msg: Option<String> = None;
 
     let (mut p1, mut p2) = players;
 
-    let (result, turns) = initialize_game_session(&p1.script, &p2.script);
+    let (result, turns, traces) = match live {
+        Some(live) => {
+            initialize_observed_game_session(&p1.script, &p2.script, &mut |game, elapsed| {
+                live.on_turn(game, elapsed)
+            })
+        }
+        None => initialize_traced_game_session(&p1.script, &p2.script),
+    };
     let mut winner: Option<String> = None;
     let mut loser: Option<String> = None;
 
@@ -174,6 +328,7 @@ fn start_match(players: (Submission, Submission)) -> MatchReturn {
     let p2_id = p2.id.clone();
 
     let mut critical_error = false;
+    let mut disqualification = None;
     match result {
         GameResult::PlayerOneWon => {
             p1.wins += 1;
@@ -206,15 +361,18 @@ fn start_match(players: (Submission, Submission)) -> MatchReturn {
                     error_fault = fault;
                     error_msg = Some(reason);
                     critical_error = true;
+                    disqualification = Some(DisqualificationKind::RuntimeError);
                 }
                 ErrorType::TurnTimeout { fault } => {
                     error_fault = fault;
                     error_msg = Some("Turn timeout".to_string());
                     critical_error = true;
+                    disqualification = Some(DisqualificationKind::TurnTimeout);
                 }
                 ErrorType::GameDeadlock => {
                     error_msg = Some("Deadlock, both bots failed".to_string());
                     critical_error = true;
+                    disqualification = Some(DisqualificationKind::Deadlock);
                 }
             }
 
@@ -246,9 +404,11 @@ fn start_match(players: (Submission, Submission)) -> MatchReturn {
         winner_id: winner,
         loser_id: loser,
         turns,
+        traces,
         error_msg,
         error_fault,
         critical_error,
+        disqualification,
     };
 }
 
@@ -6,10 +6,10 @@ pub(crate) fn create_match_making_queue(
     submissions: Vec<Submission>,
 ) -> Vec<(Submission, Submission)> {
     let mut submissions = submissions;
-    // Remove all submissions of disqualified bots
+    // Remove all submissions of disqualified and retired bots
     submissions = submissions
         .into_iter()
-        .filter(|submission| submission.disqualified == 0)
+        .filter(|submission| submission.disqualified == 0 && submission.retired == 0)
         .collect();
 
     if submissions.len() < 2 {
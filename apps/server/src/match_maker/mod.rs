@@ -1,7 +1,14 @@
 pub(crate) mod constants;
+pub(crate) mod disqualifications;
+pub(crate) mod leaderboard;
+pub(crate) mod live_matches;
 pub(crate) mod match_executor;
 pub(crate) mod match_make;
 pub(crate) mod mmr;
 pub(crate) mod placements;
 pub(crate) mod regenerate_markdown_files;
 pub(crate) mod scheduler;
+
+mod tests {
+    mod disqualification_tests;
+}
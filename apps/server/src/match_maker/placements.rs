@@ -1,7 +1,18 @@
 use crate::backend::models::submission_model::Submission;
 use diesel::SqliteConnection;
 
-use super::match_executor::{execute_match_queue, MatchReport};
+use super::{
+    constants::LADDER_MMR_AMPLIFIER,
+    live_matches::MatchKind,
+    match_executor::{execute_match_queue, preview_matches, MatchPreview, MatchReport},
+    mmr::{calculate_mmr, MMR},
+};
+
+pub(crate) struct PlacementPreview {
+    pub(crate) matches: Vec<MatchPreview>,
+    /// Rating the challenger would end up with after the matches
+    pub(crate) mmr: f32,
+}
 
 pub(crate) fn run_placements(
     challenger: &Submission,
@@ -17,9 +28,46 @@ pub(crate) fn run_placements(
             .iter()
             .map(|current| (challenger.clone(), current.clone()))
             .collect(),
+        MatchKind::Placement,
     );
 }
 
+/// Plays the placement matches of a bot that isn't part of the
+/// ladder yet, nothing is written to the database.
+pub(crate) fn preview_placements(
+    challenger: &Submission,
+    conn: &SqliteConnection,
+) -> PlacementPreview {
+    let opponents = make_selection(Submission::list(conn), challenger, 10);
+    let matches = preview_matches(challenger, opponents);
+
+    let mut rating = challenger.mmr;
+    let mut matches_played = challenger.matches_played;
+    for preview in matches.iter() {
+        if let Some(won) = preview.won {
+            rating = calculate_mmr(
+                MMR {
+                    rating,
+                    matches_played,
+                },
+                MMR {
+                    rating: preview.opponent.mmr,
+                    matches_played: preview.opponent.matches_played,
+                },
+                won,
+                LADDER_MMR_AMPLIFIER,
+            )
+            .0;
+            matches_played += 1;
+        }
+    }
+
+    PlacementPreview {
+        matches,
+        mmr: rating,
+    }
+}
+
 fn make_selection(
     submissions: Vec<Submission>,
     challenger: &Submission,
@@ -28,11 +76,12 @@ fn make_selection(
     let mut submissions = submissions;
     let mut match_queue: Vec<Submission> = Vec::new();
 
-    // Filter submissions: We don't allow the challenger to play against: itself, disqualified bots, or bots that are by the same author
+    // Filter submissions: We don't allow the challenger to play against: itself, disqualified or retired bots, or bots that are by the same author
     submissions = submissions
         .into_iter()
         .filter(|submission| {
             submission.disqualified == 0
+                && submission.retired == 0
                 && submission.id != challenger.id
                 && submission.user != challenger.user
         })
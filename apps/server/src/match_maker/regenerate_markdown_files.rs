@@ -2,21 +2,18 @@ use diesel::SqliteConnection;
 
 use crate::{
     backend::models::{
-        match_model::Match, submission_model::Submission, turn_model::Turn, user_model::User,
+        bot_model::Bot, match_model::Match, submission_model::Submission, turn_model::Turn,
+        user_model::User,
     },
-    external_related::{
-        readme_factory::{
-            build_match_files_wrapper, build_match_log_wrapper, build_submission_log_wrapper,
-            clear_match_dir, generate_readme, write_file,
-        },
-        repo_updater,
+    external_related::readme_factory::{
+        build_match_files_wrapper, build_match_log_wrapper, build_submission_log_wrapper,
+        clear_match_dir, generate_readme, write_file,
     },
 };
 
-pub(crate) fn regen_markdown_files_and_update_repo(
-    conn: &SqliteConnection,
-) -> Result<String, String> {
-    repo_updater::reset_to_master_branch();
+/// Rebuilds the README, match files and logs from the database,
+/// publishing them is up to the forge client.
+pub(crate) fn regen_markdown_files(conn: &SqliteConnection) -> Result<(), String> {
     clear_match_dir();
     build_match_files_wrapper();
     build_match_log_wrapper();
@@ -26,17 +23,12 @@ pub(crate) fn regen_markdown_files_and_update_repo(
         generate_readme(
             User::list(&conn),
             Submission::list(&conn),
+            Bot::list(conn),
             Match::list(&conn),
             Turn::list(&conn),
         ),
     ) {
-        Ok(_) => {
-            repo_updater::update_repo(format!(
-                "Match Maker update {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-            ));
-            Ok(format!("Successfully updated repository"))
-        }
+        Ok(_) => Ok(()),
         Err(error) => Err(format!("Could not update README.md: {}", error)),
     }
 }
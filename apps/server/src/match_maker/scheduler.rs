@@ -2,33 +2,40 @@ use diesel::SqliteConnection;
 
 use crate::{
     backend::models::submission_model::Submission,
-    external_related::github::create_issue_comment::create_issue_comment,
+    job_queue::issue_comment_job::queue_match_reports,
 };
 
 use super::{
-    match_executor::{execute_match_queue, MatchReport},
+    live_matches::MatchKind,
+    match_executor::{execute_match_queue, preview_matches, MatchPreview},
     match_make::create_match_making_queue,
-    regenerate_markdown_files::regen_markdown_files_and_update_repo,
 };
 
-pub(crate) fn run_scheduled_matchmaking(conn: &SqliteConnection) {
+/// Plays a round of matchmaking and queues the reports for the
+/// issues of the players. Returns false if there weren't enough
+/// submissions to play a round.
+pub(crate) fn run_scheduled_matchmaking(conn: &SqliteConnection) -> Result<bool, String> {
     let submissions = Submission::list(conn);
     let match_queue = create_match_making_queue(submissions);
     if match_queue.len() < 2 {
-        println!("Not enough submissions to execute match-queue");
-        return;
-    }
-    let match_reports = execute_match_queue(conn, match_queue);
-    publish_match_reports(match_reports);
-    match regen_markdown_files_and_update_repo(conn) {
-        Ok(_) => (),
-        Err(error) => println!("Could not update README.md: {}", error),
+        return Ok(false);
     }
+    let match_reports = execute_match_queue(conn, match_queue, MatchKind::Scheduled);
+    queue_match_reports(&match_reports, conn)?;
+    Ok(true)
 }
 
-fn publish_match_reports(match_reports: Vec<(MatchReport, MatchReport)>) {
-    for (report1, report2) in match_reports {
-        create_issue_comment(report1.issue_number, &report1.report);
-        create_issue_comment(report2.issue_number, &report2.report);
-    }
+/// Plays the matches a scheduled matchmaking would play right now,
+/// nothing is saved and nothing is posted to the forge.
+pub(crate) fn preview_scheduled_matchmaking(
+    conn: &SqliteConnection,
+) -> Vec<(Submission, MatchPreview)> {
+    create_match_making_queue(Submission::list(conn))
+        .into_iter()
+        .flat_map(|(p1, p2)| {
+            preview_matches(&p1, vec![p2])
+                .into_iter()
+                .map(move |preview| (p1.clone(), preview))
+        })
+        .collect()
 }
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests {
+    use diesel::SqliteConnection;
+
+    use crate::backend::fixtures::{setup, submission, UPWARDS_BOT};
+    use crate::backend::models::{
+        disqualification_model::{Disqualification, DisqualificationKind},
+        job_model::Job,
+        match_model::Match,
+        submission_model::Submission,
+    };
+    use crate::job_queue::disqualification_job::DISQUALIFICATION_RERUN_JOB;
+    use crate::match_maker::{
+        disqualifications::{record_disqualifications, reinstate, rerun, RerunOutcome},
+        live_matches::MatchKind,
+        match_executor::execute_match_queue,
+    };
+
+    const CRASHING_BOT: &str = "function onTurn(context)\n    error(\"boom\")\nend\n\nfunction onJump(context)\n    return \"0\"\nend\n";
+    /// A record as if the submission had timed out against the opponent
+    fn timeout(
+        submission: &mut Submission,
+        opponent: &Submission,
+        conn: &SqliteConnection,
+    ) -> Disqualification {
+        submission.disqualified = 1;
+        submission.save(conn);
+        Disqualification::create(
+            &submission.id,
+            Some(&opponent.id),
+            None,
+            DisqualificationKind::TurnTimeout,
+            "Turn timeout",
+            true,
+            conn,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn records_why_a_submission_was_disqualified() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let crashing = submission("alice", CRASHING_BOT, &conn);
+        let upwards = submission("bob", UPWARDS_BOT, &conn);
+
+        execute_match_queue(
+            &conn,
+            vec![(crashing.clone(), upwards.clone())],
+            MatchKind::Scheduled,
+        );
+
+        let records = Disqualification::by_submission(&crashing.id, &conn);
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.kind(), DisqualificationKind::RuntimeError);
+        assert_eq!(record.opponent.as_deref(), Some(upwards.id.as_str()));
+        assert_eq!(record.status, "active");
+        // The match is kept for review, the opponent won it
+        let recorded = Match::by_id(record.match_id.as_ref().unwrap(), &conn).unwrap();
+        assert_eq!(recorded.winner, upwards.id);
+        assert!(Disqualification::by_submission(&upwards.id, &conn).is_empty());
+        // Runtime errors are the bot's fault, they are not rerun
+        assert!(Job::list(&conn).is_empty());
+        // Neither player's rating is affected by the match
+        for player in [&crashing, &upwards] {
+            let after = Submission::by_id(&player.id, &conn).unwrap();
+            assert_eq!(
+                (after.mmr, after.wins, after.matches_played),
+                (player.mmr, player.wins, player.matches_played)
+            );
+        }
+    }
+
+    #[test]
+    fn reinstating_lifts_the_disqualification() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let mut crashing = submission("alice", CRASHING_BOT, &conn);
+        let upwards = submission("bob", UPWARDS_BOT, &conn);
+        let mut first = timeout(&mut crashing, &upwards, &conn);
+        let mut second = timeout(&mut crashing, &upwards, &conn);
+
+        // Still disqualified by the second record
+        assert_eq!(reinstate(&mut first, &conn).unwrap().disqualified, 1);
+        assert!(reinstate(&mut first, &conn).is_err());
+        assert_eq!(reinstate(&mut second, &conn).unwrap().disqualified, 0);
+        assert!(Disqualification::by_id(&first.id, &conn)
+            .unwrap()
+            .is_reinstated());
+    }
+
+    #[test]
+    fn reruns_confirm_failures_of_the_bot() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let mut crashing = submission("alice", CRASHING_BOT, &conn);
+        let upwards = submission("bob", UPWARDS_BOT, &conn);
+        let mut record = timeout(&mut crashing, &upwards, &conn);
+
+        assert!(matches!(
+            rerun(&mut record, &conn),
+            Ok(RerunOutcome::Reproduced)
+        ));
+        assert_eq!(
+            Disqualification::by_id(&record.id, &conn).unwrap().status,
+            "confirmed"
+        );
+        assert_eq!(
+            Submission::by_id(&crashing.id, &conn).unwrap().disqualified,
+            1
+        );
+        assert!(matches!(
+            rerun(&mut record, &conn),
+            Ok(RerunOutcome::AlreadyResolved)
+        ));
+    }
+
+    #[test]
+    fn reruns_reinstate_server_failures() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let mut upwards = submission("alice", UPWARDS_BOT, &conn);
+        let opponent = submission("bob", UPWARDS_BOT, &conn);
+        let mut record = timeout(&mut upwards, &opponent, &conn);
+
+        let reports = match rerun(&mut record, &conn) {
+            Ok(RerunOutcome::Reinstated(reports)) => reports,
+            _ => panic!("Expected the submission to be reinstated"),
+        };
+        assert_eq!(reports.len(), 1);
+        let record = Disqualification::by_id(&record.id, &conn).unwrap();
+        assert!(record.is_reinstated());
+        assert_eq!(record.infrastructure, 1);
+        let upwards = Submission::by_id(&upwards.id, &conn).unwrap();
+        assert_eq!(upwards.disqualified, 0);
+        assert_eq!(upwards.matches_played, 1);
+        assert_eq!(Match::list(&conn).len(), 1);
+    }
+
+    #[test]
+    fn timeouts_are_rerun_later() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let mut upwards = submission("alice", UPWARDS_BOT, &conn);
+        let opponent = submission("bob", UPWARDS_BOT, &conn);
+        upwards.disqualified = 1;
+
+        record_disqualifications(
+            &upwards,
+            &opponent,
+            DisqualificationKind::TurnTimeout,
+            "Turn timeout",
+            None,
+            &conn,
+        );
+        let record = &Disqualification::by_submission(&upwards.id, &conn)[0];
+        let jobs = Job::list(&conn);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].kind, DISQUALIFICATION_RERUN_JOB);
+        assert_eq!(jobs[0].payload, record.id);
+        assert!(jobs[0].run_at > chrono::Local::now().naive_local());
+    }
+
+    #[test]
+    fn deadlocks_are_not_rerun() {
+        let pool = setup();
+        let conn = pool.get().unwrap();
+        let mut upwards = submission("alice", UPWARDS_BOT, &conn);
+        let mut opponent = submission("bob", UPWARDS_BOT, &conn);
+        upwards.disqualified = 1;
+        opponent.disqualified = 1;
+
+        record_disqualifications(
+            &upwards,
+            &opponent,
+            DisqualificationKind::Deadlock,
+            "Deadlock, both bots failed",
+            None,
+            &conn,
+        );
+        assert_eq!(Disqualification::by_submission(&upwards.id, &conn).len(), 1);
+        assert_eq!(
+            Disqualification::by_submission(&opponent.id, &conn).len(),
+            1
+        );
+        assert!(Job::list(&conn).is_empty());
+    }
+}
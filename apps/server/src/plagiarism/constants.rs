@@ -0,0 +1,13 @@
+/// Number of consecutive normalised tokens hashed together
+pub(crate) const KGRAM_LENGTH: usize = 6;
+/// Number of consecutive k-grams a fingerprint is picked from
+pub(crate) const WINNOWING_WINDOW: usize = 4;
+/// Scripts with fewer fingerprints are too small to say anything about,
+/// e.g. a bot that always returns the same move
+pub(crate) const MIN_FINGERPRINTS: usize = 10;
+/// Similar submissions are listed in the report from this similarity on
+pub(crate) const FLAG_SIMILARITY: f32 = 0.6;
+/// Submissions this similar to a bot of another user are rejected
+pub(crate) const REJECT_SIMILARITY: f32 = 0.9;
+/// Most similar submissions listed in a report
+pub(crate) const MAX_REPORTED_SUBMISSIONS: usize = 5;
@@ -0,0 +1,120 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use super::constants::{KGRAM_LENGTH, WINNOWING_WINDOW};
+use super::lua_lexer::{tokenize, Token};
+
+/// Winnowing fingerprints of a script (Schleimer et al. 2003).
+///
+/// Every k-gram of normalised tokens is hashed and the smallest hash
+/// of each window of consecutive k-grams is kept. Any shared run of
+/// at least KGRAM_LENGTH + WINNOWING_WINDOW - 1 tokens is guaranteed
+/// to produce a shared fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fingerprint {
+    hashes: HashSet<u64>,
+}
+
+impl Fingerprint {
+    pub fn of_script(script: &str) -> Self {
+        Self::of_tokens(&tokenize(script))
+    }
+
+    pub fn of_tokens(tokens: &[Token]) -> Self {
+        let kgrams: Vec<u64> = if tokens.len() < KGRAM_LENGTH {
+            // Too short for a single k-gram, the whole script is one
+            if tokens.is_empty() {
+                Vec::new()
+            } else {
+                vec![hash(tokens)]
+            }
+        } else {
+            tokens.windows(KGRAM_LENGTH).map(hash).collect()
+        };
+
+        let hashes = if kgrams.len() <= WINNOWING_WINDOW {
+            kgrams.iter().min().into_iter().copied().collect()
+        } else {
+            kgrams
+                .windows(WINNOWING_WINDOW)
+                .map(|window| *window.iter().min().unwrap())
+                .collect()
+        };
+        Fingerprint { hashes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Share of our fingerprints that also appear in `other`, between 0 and 1
+    pub fn containment_in(&self, other: &Fingerprint) -> f32 {
+        if self.hashes.is_empty() {
+            return 0.0;
+        }
+        let shared = self.hashes.intersection(&other.hashes).count();
+        shared as f32 / self.hashes.len() as f32
+    }
+
+    /// How much of either script is contained in the other, between 0 and 1.
+    /// Containment rather than symmetric overlap, so that padding a copied bot
+    /// with extra code doesn't hide where the rest of it came from.
+    pub fn similarity(&self, other: &Fingerprint) -> f32 {
+        self.containment_in(other).max(other.containment_in(self))
+    }
+}
+
+fn hash(tokens: &[Token]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tokens.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fingerprint;
+
+    const BFS: &str = include_str!("../../../scripts/bfs.lua");
+    const DODGER: &str = include_str!("../../../scripts/trivial_dodger.lua");
+
+    fn similarity(a: &str, b: &str) -> f32 {
+        Fingerprint::of_script(a).similarity(&Fingerprint::of_script(b))
+    }
+
+    #[test]
+    fn renamed_copies_are_identical() {
+        let renamed = BFS
+            .replace("queue", "todo")
+            .replace("visited", "seen")
+            .replace("-- ", "-- changed comment ");
+        assert_ne!(renamed, BFS);
+        assert_eq!(similarity(&renamed, BFS), 1.0);
+    }
+
+    #[test]
+    fn padded_copies_are_still_contained() {
+        let padded = format!(
+            "local function unused(a, b)\n    return a * b + 42\nend\n{}",
+            BFS
+        );
+        assert!(similarity(&padded, BFS) > 0.8);
+        // However much code is added, all of the original is in there
+        let combined = format!("{}\n{}", BFS, DODGER);
+        assert_eq!(similarity(&combined, BFS), 1.0);
+        assert_eq!(similarity(BFS, &combined), 1.0);
+    }
+
+    #[test]
+    fn different_bots_share_little() {
+        assert!(similarity(BFS, DODGER) < 0.5);
+        assert!(similarity(DODGER, BFS) < 0.5);
+    }
+
+    #[test]
+    fn empty_scripts_match_nothing() {
+        assert_eq!(Fingerprint::of_script("").len(), 0);
+        assert_eq!(similarity("", BFS), 0.0);
+        assert_eq!(similarity("-- only a comment", ""), 0.0);
+    }
+}
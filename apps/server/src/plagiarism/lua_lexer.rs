@@ -0,0 +1,218 @@
+/// Tokens of a lua script with everything that can be changed
+/// without changing the program normalised away. Identifiers,
+/// strings and numbers only keep their kind, comments and
+/// whitespace are dropped. Keywords and operators stay as is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Token {
+    Keyword(&'static str),
+    Identifier,
+    String,
+    Number,
+    Symbol(String),
+}
+
+const KEYWORDS: [&str; 22] = [
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Longest first, so that "..." isn't read as ".." and "."
+const SYMBOLS: [&str; 15] = [
+    "...", "..", "==", "~=", "<=", ">=", "::", "//", "<<", ">>", "+=", "-=", "*=", "/=", "=>",
+];
+
+pub(crate) fn tokenize(script: &str) -> Vec<Token> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            // Comments, either long --[[ ]] or until the end of the line
+            i += 2;
+            match long_bracket_level(&chars, i) {
+                Some(level) => i = skip_long_bracket(&chars, i, level),
+                None => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+            }
+        } else if c == '"' || c == '\'' {
+            i = skip_quoted_string(&chars, i);
+            tokens.push(Token::String);
+        } else if c == '[' && long_bracket_level(&chars, i).is_some() {
+            let level = long_bracket_level(&chars, i).unwrap();
+            i = skip_long_bracket(&chars, i, level);
+            tokens.push(Token::String);
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit()))
+        {
+            i = skip_number(&chars, i);
+            tokens.push(Token::Number);
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match KEYWORDS.iter().find(|keyword| **keyword == word) {
+                Some(keyword) => tokens.push(Token::Keyword(keyword)),
+                None => tokens.push(Token::Identifier),
+            }
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| {
+                    symbol
+                        .chars()
+                        .enumerate()
+                        .all(|(offset, expected)| chars.get(i + offset) == Some(&expected))
+                })
+                .map(|symbol| symbol.to_string())
+                .unwrap_or_else(|| c.to_string());
+            i += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+    tokens
+}
+
+/// Level of a long bracket such as [[ or [==[ starting at `start`
+fn long_bracket_level(chars: &[char], start: usize) -> Option<usize> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+    let level = chars[start + 1..].iter().take_while(|c| **c == '=').count();
+    match chars.get(start + 1 + level) {
+        Some('[') => Some(level),
+        _ => None,
+    }
+}
+
+/// Returns the index after the closing bracket of the same level
+fn skip_long_bracket(chars: &[char], start: usize, level: usize) -> usize {
+    let mut i = start + level + 2;
+    while i < chars.len() {
+        if chars[i] == ']'
+            && chars[i + 1..].iter().take(level).all(|c| *c == '=')
+            && chars.get(i + 1 + level) == Some(&']')
+        {
+            return i + level + 2;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+fn skip_quoted_string(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote || c == '\n' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+fn skip_number(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    let is_hex = chars[i] == '0' && matches!(chars.get(i + 1), Some('x') | Some('X'));
+    if is_hex {
+        i += 2;
+    }
+    while i < chars.len() {
+        let c = chars[i];
+        let exponent = if is_hex { "pP" } else { "eE" };
+        if exponent.contains(c) && matches!(chars.get(i + 1), Some('+') | Some('-')) {
+            i += 2;
+        } else if c.is_ascii_alphanumeric() || c == '.' {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Token};
+
+    fn symbol(value: &str) -> Token {
+        Token::Symbol(value.to_string())
+    }
+
+    #[test]
+    fn normalises_identifiers_and_literals() {
+        assert_eq!(
+            tokenize("local walls = player.wallCount + 10"),
+            vec![
+                Token::Keyword("local"),
+                Token::Identifier,
+                symbol("="),
+                Token::Identifier,
+                symbol("."),
+                Token::Identifier,
+                symbol("+"),
+                Token::Number,
+            ]
+        );
+        assert_eq!(
+            tokenize("local a = 'up'"),
+            tokenize("local direction = \"down\"")
+        );
+    }
+
+    #[test]
+    fn skips_comments() {
+        let plain = tokenize("return x");
+        let cases = vec![
+            "-- comment\nreturn x",
+            "return x -- trailing",
+            "--[[ long\ncomment ]] return x",
+            "--[==[ nested ]] still comment ]==] return x",
+        ];
+        for case in cases {
+            assert_eq!(tokenize(case), plain, "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn reads_every_literal_as_a_single_token() {
+        let cases = vec![
+            "\"escaped \\\" quote\"",
+            "'single'",
+            "[[long\nstring]]",
+            "[=[with ]] inside]=]",
+        ];
+        for case in cases {
+            assert_eq!(tokenize(case), vec![Token::String], "{:?}", case);
+        }
+        for case in ["3", "3.14", "0x1F", "1e-5", ".5", "0x1p4"] {
+            assert_eq!(tokenize(case), vec![Token::Number], "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn reads_multi_character_symbols() {
+        assert_eq!(
+            tokenize("a ~= b .. c ... d"),
+            vec![
+                Token::Identifier,
+                symbol("~="),
+                Token::Identifier,
+                symbol(".."),
+                Token::Identifier,
+                symbol("..."),
+                Token::Identifier,
+            ]
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub(crate) mod constants;
+pub(crate) mod fingerprint;
+pub(crate) mod lua_lexer;
+pub(crate) mod similarity_check;
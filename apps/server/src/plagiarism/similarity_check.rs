@@ -0,0 +1,154 @@
+use diesel::SqliteConnection;
+
+use crate::backend::models::submission_model::Submission;
+
+use super::constants::{
+    FLAG_SIMILARITY, MAX_REPORTED_SUBMISSIONS, MIN_FINGERPRINTS, REJECT_SIMILARITY,
+};
+use super::fingerprint::Fingerprint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Original,
+    /// Accepted, but the similar submissions are reported
+    Flagged,
+    Rejected,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SimilarSubmission {
+    pub id: String,
+    pub issue_url: String,
+    pub similarity: f32,
+}
+
+/// Submissions of other users that a script resembles, most similar first
+#[derive(Debug, Clone)]
+pub(crate) struct SimilarityReport {
+    pub similar: Vec<SimilarSubmission>,
+}
+
+/// Compares a script against every submission made by other users
+pub(crate) fn check_similarity(
+    script: &str,
+    user_id: &str,
+    conn: &SqliteConnection,
+) -> SimilarityReport {
+    compare(script, user_id, &Submission::list(conn))
+}
+
+pub(crate) fn compare(script: &str, user_id: &str, submissions: &[Submission]) -> SimilarityReport {
+    let fingerprint = Fingerprint::of_script(script);
+    if fingerprint.len() < MIN_FINGERPRINTS {
+        return SimilarityReport {
+            similar: Vec::new(),
+        };
+    }
+
+    let mut similar: Vec<SimilarSubmission> = submissions
+        .iter()
+        .filter(|submission| submission.user != user_id)
+        .filter_map(|submission| {
+            let existing = Fingerprint::of_script(&submission.script);
+            // Tiny bots would be contained in almost anything
+            if existing.len() < MIN_FINGERPRINTS {
+                return None;
+            }
+            Some(SimilarSubmission {
+                id: submission.id.clone(),
+                issue_url: submission.issue_url.clone(),
+                similarity: fingerprint.similarity(&existing),
+            })
+        })
+        .filter(|submission| submission.similarity >= FLAG_SIMILARITY)
+        .collect();
+    similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    similar.truncate(MAX_REPORTED_SUBMISSIONS);
+    SimilarityReport { similar }
+}
+
+impl SimilarityReport {
+    pub fn verdict(&self) -> Verdict {
+        match self.similar.first() {
+            Some(closest) if closest.similarity >= REJECT_SIMILARITY => Verdict::Rejected,
+            Some(_) => Verdict::Flagged,
+            None => Verdict::Original,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut output =
+            "Similarity report:\n\n| Submission | Similarity |\n| :-- | --: |\n".to_string();
+        for submission in self.similar.iter() {
+            output += &format!(
+                "| [{}]({}) | {}% |\n",
+                submission.id,
+                submission.issue_url,
+                (submission.similarity * 100.0).round()
+            );
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Verdict};
+    use crate::backend::models::submission_model::Submission;
+
+    const BFS: &str = include_str!("../../../scripts/bfs.lua");
+    const DODGER: &str = include_str!("../../../scripts/trivial_dodger.lua");
+
+    fn submission(user: &str, script: &str) -> Submission {
+        Submission::unsaved(
+            user,
+            script,
+            None,
+            "https://github.com/Hampfh/Hampfh/issues/1",
+            1,
+        )
+    }
+
+    #[test]
+    fn judges_copies_from_other_users() {
+        let renamed = BFS.replace("queue", "todo").replace("visited", "seen");
+        let combined = format!("{}\n{}", BFS, DODGER);
+        let cases = vec![
+            ("renamed copy", renamed.as_str(), Verdict::Rejected),
+            (
+                "copy combined with another bot",
+                combined.as_str(),
+                Verdict::Rejected,
+            ),
+            ("other bot", DODGER, Verdict::Original),
+            (
+                "too short to compare",
+                "function onTurn() return \"0\" end",
+                Verdict::Original,
+            ),
+        ];
+        let submissions = vec![submission("alice", BFS)];
+        for (description, script, expected) in cases {
+            let report = compare(script, "bob", &submissions);
+            assert_eq!(report.verdict(), expected, "{}", description);
+        }
+    }
+
+    #[test]
+    fn ignores_the_users_own_submissions() {
+        let report = compare(BFS, "alice", &[submission("alice", BFS)]);
+        assert_eq!(report.verdict(), Verdict::Original);
+    }
+
+    #[test]
+    fn reports_most_similar_first() {
+        let submissions = vec![submission("alice", DODGER), submission("carol", BFS)];
+        let report = compare(BFS, "bob", &submissions);
+        assert_eq!(report.similar.len(), 1);
+        assert_eq!(report.similar[0].id, submissions[1].id);
+        assert!(report.to_markdown().contains(&format!(
+            "| [{}](https://github.com/Hampfh/Hampfh/issues/1) | 100% |",
+            submissions[1].id
+        )));
+    }
+}